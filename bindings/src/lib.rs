@@ -1,6 +1,9 @@
+use std::path::Path;
 use std::sync::Arc;
 
 use cooklang::aisle::parse as parse_aisle_config_original;
+use cooklang::images::find_images;
+use cooklang::line_index::LineIndex;
 
 pub mod aisle;
 pub mod model;
@@ -16,7 +19,35 @@ pub fn parse_recipe(input: String, scaling_factor: f64) -> CooklangRecipe {
 
     let scaled = parsed.scale(scaling_factor, parser.converter());
 
-    into_simple_recipe(&scaled)
+    into_simple_recipe(&scaled, parser.converter())
+}
+
+#[uniffi::export]
+pub fn parse_recipe_file(
+    path: String,
+    scaling_factor: f64,
+) -> Result<CooklangRecipe, CooklangError> {
+    let parser = cooklang::CooklangParser::canonical();
+
+    let input = std::fs::read_to_string(&path).map_err(|e| CooklangError::Io {
+        message: e.to_string(),
+    })?;
+    let (mut parsed, _warnings) =
+        parser
+            .parse(&input)
+            .into_result()
+            .map_err(|report| CooklangError::Parse {
+                message: report.to_string(),
+            })?;
+
+    // the recipe is still useful without its images
+    if let Ok(images) = find_images(Path::new(&path)) {
+        parsed.attach_images(&images);
+    }
+
+    let scaled = parsed.scale(scaling_factor, parser.converter());
+
+    Ok(into_simple_recipe(&scaled, parser.converter()))
 }
 
 #[uniffi::export]
@@ -24,9 +55,7 @@ pub fn parse_metadata(input: String, scaling_factor: f64) -> CooklangMetadata {
     let mut metadata = CooklangMetadata::new();
     let parser = cooklang::CooklangParser::canonical();
 
-    let (parsed, _warnings) = parser.parse(&input)
-        .into_result()
-        .unwrap();
+    let (parsed, _warnings) = parser.parse(&input).into_result().unwrap();
 
     let scaled = parsed.scale(scaling_factor, parser.converter());
 
@@ -116,6 +145,30 @@ pub fn combine_ingredients_selected(
     combined
 }
 
+/// Line and column of a byte offset in the input
+///
+/// Offsets past the end are the end of the input, and offsets in the middle of
+/// a char are the start of the char.
+#[uniffi::export]
+pub fn offset_to_position(input: String, offset: u32, unit: ColumnUnit) -> Position {
+    let mut offset = (offset as usize).min(input.len());
+    while !input.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    LineIndex::new(&input)
+        .line_col(&input, offset, unit.into())
+        .into()
+}
+
+/// Byte offset of a line and column in the input
+///
+/// Positions past the end of a line are the end of the line, and lines past
+/// the end are the end of the input.
+#[uniffi::export]
+pub fn position_to_offset(input: String, position: Position, unit: ColumnUnit) -> u32 {
+    LineIndex::new(&input).offset(&input, position.into(), unit.into()) as u32
+}
+
 uniffi::setup_scaffolding!();
 
 #[cfg(test)]
@@ -132,7 +185,7 @@ mod tests {
 a test @step @salt{1%mg} more text
 "#
             .to_string(),
-            1.0
+            1.0,
         );
 
         assert_eq!(
@@ -209,7 +262,7 @@ source: https://google.com
 a test @step @salt{1%mg} more text
 "#
             .to_string(),
-            1.0
+            1.0,
         );
 
         assert_eq!(
@@ -357,7 +410,7 @@ dried oregano
 Cook @onions{3%large} until brown
 "#
             .to_string(),
-            1.0
+            1.0,
         );
 
         let first_section = recipe
@@ -416,7 +469,7 @@ add @tomatoes{400%g}
 simmer for 10 minutes
 "#
             .to_string(),
-            1.0
+            1.0,
         );
         let first_section = recipe
             .sections
@@ -466,6 +519,42 @@ simmer for 10 minutes
         );
     }
 
+    #[test]
+    fn test_parse_recipe_file() {
+        use crate::{parse_recipe_file, Block};
+
+        let dir = std::env::temp_dir().join(format!("cooklang-bindings-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Bread.cook");
+        std::fs::write(&path, "Mix @flour{200%g}.\n\nBake.\n").unwrap();
+        std::fs::write(dir.join("Bread.2.jpg"), "").unwrap();
+
+        let recipe = parse_recipe_file(path.display().to_string(), 1.0).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let images = recipe.sections[0]
+            .blocks
+            .iter()
+            .map(|block| match block {
+                Block::StepBlock(step) => step.images.clone(),
+                _ => panic!("Expected block to be a Step"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            images,
+            vec![vec![], vec![dir.join("Bread.2.jpg").display().to_string()]]
+        );
+    }
+
+    #[test]
+    fn test_parse_recipe_file_missing() {
+        use crate::{parse_recipe_file, CooklangError};
+
+        let path = std::env::temp_dir().join("cooklang-bindings-missing/Nothing.cook");
+        let result = parse_recipe_file(path.display().to_string(), 1.0);
+        assert!(matches!(result, Err(CooklangError::Io { .. })));
+    }
+
     #[test]
     fn test_parse_recipe_with_sections() {
         use crate::{parse_recipe, Block, Item};
@@ -481,7 +570,7 @@ Mix @flour{200%g} and @water{50%ml} together until smooth.
 Combine @cheese{100%g} and @spinach{50%g}, then season to taste.
 "#
             .to_string(),
-            1.0
+            1.0,
         );
 
         let mut sections = recipe.sections.into_iter();
@@ -548,4 +637,103 @@ Combine @cheese{100%g} and @spinach{50%g}, then season to taste.
             ]
         );
     }
+
+    #[test]
+    fn test_grouped_ingredients() {
+        use crate::{
+            parse_recipe, Block, GroupedIngredient, GroupedQuantityKey, QuantityType, Value,
+        };
+        use std::collections::HashMap;
+
+        let recipe = parse_recipe(
+            r#"
+Mix @flour{200%g} and @salt.
+
+Add @flour{50%g}.
+"#
+            .to_string(),
+            1.0,
+        );
+
+        let grams = |value| {
+            HashMap::from([(
+                GroupedQuantityKey {
+                    name: "g".to_string(),
+                    unit_type: QuantityType::Number,
+                },
+                Value::Number { value },
+            )])
+        };
+        let section = &recipe.sections[0];
+        assert_eq!(
+            section.grouped_ingredients,
+            vec![
+                GroupedIngredient {
+                    index: 0,
+                    quantity: grams(200.0),
+                },
+                GroupedIngredient {
+                    index: 1,
+                    quantity: HashMap::from([(
+                        GroupedQuantityKey {
+                            name: "".to_string(),
+                            unit_type: QuantityType::Empty,
+                        },
+                        Value::Empty,
+                    )]),
+                },
+                GroupedIngredient {
+                    index: 2,
+                    quantity: grams(50.0),
+                },
+            ]
+        );
+        let Block::StepBlock(step) = &section.blocks[1] else {
+            panic!("Expected block to be a Step")
+        };
+        assert_eq!(
+            step.grouped_ingredients,
+            vec![GroupedIngredient {
+                index: 2,
+                quantity: grams(50.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_positions() {
+        use crate::{offset_to_position, position_to_offset, ColumnUnit, Position};
+
+        let input = "Add @ñoras{}.\nBake 🍞.".to_string();
+        let end = input.len() as u32;
+        let pos = |line, column| Position { line, column };
+
+        assert_eq!(
+            offset_to_position(input.clone(), 13, ColumnUnit::Utf8),
+            pos(0, 13)
+        );
+        assert_eq!(
+            offset_to_position(input.clone(), 13, ColumnUnit::Char),
+            pos(0, 12)
+        );
+        assert_eq!(
+            offset_to_position(input.clone(), end, ColumnUnit::Utf16),
+            pos(1, 8)
+        );
+        // in the middle of ñ
+        assert_eq!(
+            offset_to_position(input.clone(), 6, ColumnUnit::Utf8),
+            pos(0, 5)
+        );
+        assert_eq!(
+            offset_to_position(input.clone(), 100, ColumnUnit::Char),
+            pos(1, 7)
+        );
+
+        assert_eq!(
+            position_to_offset(input.clone(), pos(1, 8), ColumnUnit::Utf16),
+            end
+        );
+        assert_eq!(position_to_offset(input, pos(0, 12), ColumnUnit::Char), 13);
+    }
 }
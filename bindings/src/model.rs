@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
+use cooklang::ingredient_list::GroupedIngredient as OriginalGroupedIngredient;
+use cooklang::line_index::{ColumnUnit as OriginalColumnUnit, LineCol};
 use cooklang::model::Item as OriginalItem;
-use cooklang::quantity::{
-    Quantity as OriginalQuantity, Value as OriginalValue
-};
+use cooklang::quantity::{Quantity as OriginalQuantity, Value as OriginalValue};
+use cooklang::Converter;
 use cooklang::ScaledRecipe as OriginalRecipe;
 
 #[derive(uniffi::Record, Debug)]
@@ -23,6 +24,7 @@ pub struct Section {
     pub ingredient_refs: Vec<ComponentRef>,
     pub cookware_refs: Vec<ComponentRef>,
     pub timer_refs: Vec<ComponentRef>,
+    pub grouped_ingredients: Vec<GroupedIngredient>,
 }
 
 #[derive(uniffi::Enum, Debug)]
@@ -45,6 +47,8 @@ pub struct Step {
     pub ingredient_refs: Vec<ComponentRef>,
     pub cookware_refs: Vec<ComponentRef>,
     pub timer_refs: Vec<ComponentRef>,
+    pub grouped_ingredients: Vec<GroupedIngredient>,
+    pub images: Vec<String>,
 }
 
 #[derive(uniffi::Record, Debug)]
@@ -81,6 +85,54 @@ pub enum Item {
 
 pub type IngredientList = HashMap<String, GroupedQuantity>;
 
+/// An ingredient used in a step or section with the quantities of all its
+/// uses there grouped. References are resolved to their definition.
+#[derive(uniffi::Record, Debug, PartialEq, Clone)]
+pub struct GroupedIngredient {
+    /// Index of the definition in `CooklangRecipe::ingredients`
+    pub index: ComponentRef,
+    pub quantity: GroupedQuantity,
+}
+
+#[derive(uniffi::Enum, Debug, Clone, Copy, PartialEq)]
+pub enum ColumnUnit {
+    Utf8,
+    Utf16,
+    Char,
+}
+
+impl From<ColumnUnit> for OriginalColumnUnit {
+    fn from(unit: ColumnUnit) -> Self {
+        match unit {
+            ColumnUnit::Utf8 => OriginalColumnUnit::Utf8,
+            ColumnUnit::Utf16 => OriginalColumnUnit::Utf16,
+            ColumnUnit::Char => OriginalColumnUnit::Char,
+        }
+    }
+}
+
+/// A line and a column, both starting at 0
+#[derive(uniffi::Record, Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl From<LineCol> for Position {
+    fn from(pos: LineCol) -> Self {
+        Position {
+            line: pos.line as u32,
+            column: pos.column as u32,
+        }
+    }
+}
+
+impl From<Position> for LineCol {
+    fn from(pos: Position) -> Self {
+        LineCol::new(pos.line as usize, pos.column as usize)
+    }
+}
+
 pub(crate) fn into_group_quantity(amount: &Option<Amount>) -> GroupedQuantity {
     // options here:
     // - same units:
@@ -170,6 +222,16 @@ pub enum Value {
 // TODO, should be more complex and support canonical keys
 pub type CooklangMetadata = HashMap<String, String>;
 
+#[derive(uniffi::Error, thiserror::Error, Debug)]
+pub enum CooklangError {
+    /// The recipe file could not be read
+    #[error("Can't read the recipe file: {message}")]
+    Io { message: String },
+    /// The recipe has errors
+    #[error("Can't parse the recipe: {message}")]
+    Parse { message: String },
+}
+
 trait Amountable {
     fn extract_amount(&self) -> Amount;
 }
@@ -325,13 +387,32 @@ pub(crate) fn into_item(item: &OriginalItem) -> Item {
             index: *index as u32,
         },
         // returning an empty block of text as it's not supported by the spec
-        OriginalItem::InlineQuantity { index: _ } => Item::Text {
-            value: "".to_string(),
-        },
+        OriginalItem::InlineQuantity { index: _ } | OriginalItem::Temperature { index: _ } => {
+            Item::Text {
+                value: "".to_string(),
+            }
+        }
+    }
+}
+
+pub(crate) fn into_grouped_ingredient(grouped: &OriginalGroupedIngredient) -> GroupedIngredient {
+    let mut quantity = GroupedQuantity::default();
+    for q in grouped.quantity.iter() {
+        merge_grouped_quantities(
+            &mut quantity,
+            &into_group_quantity(&Some(q.extract_amount())),
+        );
+    }
+    if quantity.is_empty() {
+        quantity = into_group_quantity(&None);
+    }
+    GroupedIngredient {
+        index: grouped.index as u32,
+        quantity,
     }
 }
 
-pub(crate) fn into_simple_recipe(recipe: &OriginalRecipe) -> CooklangRecipe {
+pub(crate) fn into_simple_recipe(recipe: &OriginalRecipe, converter: &Converter) -> CooklangRecipe {
     let mut metadata = CooklangMetadata::new();
     let ingredients: Vec<Ingredient> = recipe.ingredients.iter().map(|i| i.into()).collect();
     let cookware: Vec<Cookware> = recipe.cookware.iter().map(|i| i.into()).collect();
@@ -374,11 +455,18 @@ pub(crate) fn into_simple_recipe(recipe: &OriginalRecipe) -> CooklangRecipe {
                         };
                         items.push(item);
                     }
+                    let grouped_ingredients = recipe
+                        .group_step_ingredients(step, converter)
+                        .iter()
+                        .map(into_grouped_ingredient)
+                        .collect();
                     blocks.push(Block::StepBlock(Step {
                         items,
                         ingredient_refs: step_ingredient_refs.clone(),
                         cookware_refs: step_cookware_refs.clone(),
                         timer_refs: step_timer_refs.clone(),
+                        grouped_ingredients,
+                        images: step.images.clone(),
                     }));
                     ingredient_refs.extend(step_ingredient_refs);
                     cookware_refs.extend(step_cookware_refs);
@@ -393,12 +481,18 @@ pub(crate) fn into_simple_recipe(recipe: &OriginalRecipe) -> CooklangRecipe {
             }
         }
 
+        let grouped_ingredients = recipe
+            .group_section_ingredients(section, converter)
+            .iter()
+            .map(into_grouped_ingredient)
+            .collect();
         sections.push(Section {
             title: section.name.clone(),
             blocks,
             ingredient_refs,
             cookware_refs,
             timer_refs,
+            grouped_ingredients,
         });
     }
 
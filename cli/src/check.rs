@@ -0,0 +1,102 @@
+//! `check` command
+
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Context as _;
+use cooklang::{
+    analysis::SourceMap,
+    lint::{lint, LintOptions},
+    metadata_schema::MetadataSchema,
+    report::Diagnostic,
+    ParseOptions,
+};
+use serde::Serialize;
+
+use crate::{read_input, Context, Format};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Recipe files, `-` for stdin
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Also check the style of the recipes, like unused ingredients
+    #[arg(long)]
+    lint: bool,
+    /// JSON Schema file to validate the metadata of the recipes
+    #[arg(long)]
+    schema: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct FileDiagnostics {
+    file: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+pub fn run(args: Args, ctx: &Context) -> anyhow::Result<ExitCode> {
+    let color = std::io::stdout().is_terminal();
+    let mut output = Vec::new();
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    let schema = match &args.schema {
+        Some(path) => {
+            let input = read_input(path)?;
+            let schema = MetadataSchema::from_json(&input.text)
+                .with_context(|| format!("invalid schema '{}'", input.name))?;
+            Some(schema)
+        }
+        None => None,
+    };
+
+    for path in &args.files {
+        let input = read_input(path)?;
+        let mut source_map = SourceMap::default();
+        let options = ParseOptions {
+            source_map: Some(&mut source_map),
+            metadata_schema: schema.as_ref(),
+            ..Default::default()
+        };
+        let (recipe, report) = ctx
+            .parser
+            .parse_with_options(&input.text, options)
+            .into_tuple();
+        let lint_report = recipe
+            .filter(|_| args.lint && !report.has_errors())
+            .map(|recipe| {
+                let options = LintOptions::default();
+                lint(&input.text, &recipe, &source_map, &ctx.parser, &options)
+            });
+
+        let mut diagnostics = Vec::new();
+        for report in std::iter::once(&report).chain(&lint_report) {
+            errors += report.errors().count();
+            warnings += report.warnings().count();
+            match ctx.format {
+                Format::Human => report.print(&input.name, &input.text, color)?,
+                _ => diagnostics.extend(report.to_diagnostics(&input.text)),
+            }
+        }
+        output.push(FileDiagnostics {
+            file: input.name,
+            diagnostics,
+        });
+    }
+
+    if ctx.format == Format::Human {
+        println!(
+            "checked {} recipes: {errors} errors, {warnings} warnings",
+            output.len()
+        );
+    } else {
+        ctx.write_data(&output)?;
+    }
+
+    Ok(if errors > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
@@ -0,0 +1,353 @@
+//! `fmt` command
+
+use std::io::IsTerminal;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{bail, ensure};
+use cooklang::{
+    parser::{Event, PullParser, Quantity},
+    Extensions, Span,
+};
+
+use crate::{read_input, Context, Format};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Recipe files, `-` for stdin
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Write the formatted recipes back to the files instead of printing them
+    #[arg(long, conflicts_with = "check")]
+    write: bool,
+    /// Only list the recipes that are not formatted, and fail if any
+    #[arg(long)]
+    check: bool,
+}
+
+pub fn run(args: Args, ctx: &Context) -> anyhow::Result<ExitCode> {
+    ensure!(
+        ctx.format == Format::Human,
+        "fmt only has the human output format"
+    );
+    let color = std::io::stderr().is_terminal();
+    let mut failed = false;
+    for path in &args.files {
+        let input = read_input(path)?;
+        let report = ctx.parser.parse(&input.text).into_report();
+        if report.has_errors() {
+            report.eprint(&input.name, &input.text, color)?;
+            eprintln!("error: '{}' has errors, not formatted", input.name);
+            failed = true;
+            continue;
+        }
+        let formatted = format(&input.text, ctx.parser.extensions());
+        if args.check {
+            if formatted != input.text {
+                println!("{}", input.name);
+                failed = true;
+            }
+        } else if args.write {
+            if path == Path::new("-") {
+                bail!("can't write to stdin");
+            }
+            if formatted != input.text {
+                std::fs::write(path, formatted)?;
+            }
+        } else {
+            print!("{formatted}");
+        }
+    }
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Formats a recipe
+///
+/// The recipe should have no errors. Only the layout changes, so it parses
+/// as the same recipe:
+/// - Quantities are trimmed and the unit separated with `%`.
+/// - Metadata lines are `>> key: value` and sections `== name ==`.
+/// - No trailing whitespace, at most one blank line in a row and a newline
+///   at the end.
+///
+/// Line endings are kept. Block comments and notes are left as they are,
+/// only blank lines around them change.
+pub fn format(input: &str, extensions: Extensions) -> String {
+    let mut edits = Vec::new();
+    let mut frontmatter_end = 0;
+    for event in PullParser::new(input, extensions) {
+        match event {
+            Event::YAMLFrontMatter(text) => {
+                // the closing `---` line
+                let end = text.span().end();
+                let close = end + input[end..].find("---").unwrap_or(0);
+                frontmatter_end = input[close..]
+                    .find('\n')
+                    .map_or(input.len(), |i| close + i + 1);
+            }
+            Event::Metadata { key, value } => {
+                let key = trimmed(input, key.span());
+                let value = trimmed(input, value.span());
+                let line_start = line_start(input, key.start);
+                if input[line_start..key.start].trim() == ">>" {
+                    edits.push((line_start..key.start, ">> ".to_string()));
+                }
+                if !value.is_empty() && input[key.end..value.start].trim() == ":" {
+                    edits.push((key.end..value.start, ": ".to_string()));
+                }
+            }
+            Event::Section {
+                name: Some(name),
+                optional,
+            } => {
+                let name = trimmed(input, name.span());
+                let line_start = line_start(input, name.start);
+                let prefix = &input[line_start..name.start];
+                let marker = prefix.trim_matches(|c: char| c == '=' || c.is_whitespace());
+                if marker != if optional { "?" } else { "" } {
+                    continue;
+                }
+                let open = if optional { "==? " } else { "== " };
+                edits.push((line_start..name.start, open.to_string()));
+                let rest = &input[name.end..];
+                let marker = rest
+                    .find(|c: char| c != '=' && c != ' ' && c != '\t')
+                    .map_or(rest, |i| &rest[..i]);
+                let marker_end = name.end + marker.trim_end().len();
+                edits.push((name.end..marker_end, " ==".to_string()));
+            }
+            Event::Ingredient(igr) => {
+                if let Some(q) = &igr.quantity {
+                    edits.extend(quantity_edit(input, q.span(), q.value()));
+                }
+            }
+            Event::Timer(timer) => {
+                if let Some(q) = &timer.quantity {
+                    edits.extend(quantity_edit(input, q.span(), q.value()));
+                }
+            }
+            Event::Temperature(temp) => {
+                let q = &temp.quantity;
+                edits.extend(quantity_edit(input, q.span(), q.value()));
+            }
+            _ => {}
+        }
+    }
+
+    let mut body = String::with_capacity(input.len());
+    let mut last = frontmatter_end;
+    for (range, replacement) in edits {
+        if range.start < last {
+            continue;
+        }
+        body.push_str(&input[last..range.start]);
+        body.push_str(&replacement);
+        last = range.end;
+    }
+    body.push_str(&input[last..]);
+
+    let mut out = input[..frontmatter_end].to_string();
+    // line ending of the first blank line skipped
+    let mut blank = None;
+    let mut newline = if input.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut in_comment = false;
+    let mut block_start = true;
+    let mut in_note = false;
+    for line in body.split_inclusive('\n') {
+        let content = line.strip_suffix('\n').unwrap_or(line);
+        let content = content.strip_suffix('\r').unwrap_or(content);
+        let ending = &line[content.len()..];
+        if !ending.is_empty() {
+            newline = ending;
+        }
+
+        let starts_in_comment = in_comment;
+        in_comment = ends_in_block_comment(content, in_comment);
+        if !starts_in_comment && content.trim().is_empty() {
+            if !out.is_empty() && blank.is_none() {
+                blank = Some(newline);
+            }
+            block_start = true;
+            continue;
+        }
+        if block_start {
+            let trimmed = content.trim_start();
+            in_note = trimmed.starts_with('>') && !trimmed.starts_with(">>");
+            block_start = false;
+        }
+        if let Some(blank) = blank.take() {
+            out.push_str(blank);
+        }
+        if in_comment || in_note {
+            out.push_str(content);
+        } else {
+            out.push_str(content.trim_end());
+        }
+        out.push_str(newline);
+    }
+    out
+}
+
+/// If a line ends inside a `[- block comment -]`
+fn ends_in_block_comment(line: &str, mut in_comment: bool) -> bool {
+    let mut rest = line;
+    loop {
+        if in_comment {
+            let Some(close) = rest.find("-]") else {
+                return true;
+            };
+            rest = &rest[close + 2..];
+            in_comment = false;
+        } else {
+            let Some(open) = rest.find("[-") else {
+                return false;
+            };
+            // a line comment ignores everything after it
+            if rest
+                .find("--")
+                .is_some_and(|line_comment| line_comment < open)
+            {
+                return false;
+            }
+            rest = &rest[open + 2..];
+            in_comment = true;
+        }
+    }
+}
+
+/// Replaces what is between the braces of a quantity
+fn quantity_edit(input: &str, span: Span, quantity: &Quantity) -> Option<(Range<usize>, String)> {
+    let open = input[..span.start()].rfind('{')? + 1;
+    let close = span.end() + input[span.end()..].find('}')?;
+    let value_end = quantity.value.span().end();
+    // the value with any modifier before, like the scaling lock
+    let mut text = input[open..value_end].trim().to_string();
+    if let Some(unit) = &quantity.unit {
+        text.push('%');
+        text.push_str(&input[trimmed(input, unit.span())]);
+    }
+    Some((open..close, text))
+}
+
+fn trimmed(input: &str, span: Span) -> Range<usize> {
+    let s = &input[span.range()];
+    let start = span.start() + (s.len() - s.trim_start().len());
+    let end = span.start() + s.trim_end().len();
+    start..end.max(start)
+}
+
+fn line_start(input: &str, offset: usize) -> usize {
+    input[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cooklang::CooklangParser;
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {"
+        >>servings:2
+        >>   source   :   grandma
+
+
+        =Dough=
+        Mix @flour{ 500 % g } and @water{300 %ml}.
+        Let it rest ~{ 1 %hour }.
+        ==?Sauce
+        Add @salt{=1%tsp} and @basil{a few}. -- comment
+        Cook in a #pot{} or bake at ^{ 180 % °C }(fan).
+
+
+
+    "};
+
+    #[test]
+    fn format_recipe() {
+        let formatted = format(INPUT, Extensions::all());
+        assert_eq!(
+            formatted,
+            indoc! {"
+                >> servings: 2
+                >> source: grandma
+
+                == Dough ==
+                Mix @flour{500%g} and @water{300%ml}.
+                Let it rest ~{1%hour}.
+                ==? Sauce ==
+                Add @salt{=1%tsp} and @basil{a few}. -- comment
+                Cook in a #pot{} or bake at ^{180%°C}(fan).
+            "}
+        );
+    }
+
+    #[test]
+    fn same_recipe() {
+        let parser = CooklangParser::default();
+        let formatted = format(INPUT, parser.extensions());
+        let a = parser.parse(INPUT).into_output().unwrap();
+        let b = parser.parse(&formatted).into_output().unwrap();
+        assert_eq!(a, b);
+        assert_eq!(format(&formatted, parser.extensions()), formatted);
+    }
+
+    #[test]
+    fn frontmatter_untouched() {
+        let input = "---\ntitle: x  \n\n\ntags: [a]\n---\n\n\nStep @one{ 1 }.  \n";
+        let formatted = format(input, Extensions::all());
+        assert_eq!(
+            formatted,
+            "---\ntitle: x  \n\n\ntags: [a]\n---\n\nStep @one{1}.\n"
+        );
+    }
+
+    #[test]
+    fn line_endings() {
+        let input = "Step @one{ 1 }.  \r\n\r\n\r\n\r\nStep two.\r\n";
+        let formatted = format(input, Extensions::all());
+        assert_eq!(formatted, "Step @one{1}.\r\n\r\nStep two.\r\n");
+        assert_eq!(
+            format("Step.\r\nMore.", Extensions::all()),
+            "Step.\r\nMore.\r\n"
+        );
+    }
+
+    #[test]
+    fn comments_and_notes_untouched() {
+        let input = indoc! {"
+            Step @one{ 1 }. [- block  
+              comment  
+
+
+            -] more.  
+            -- line [- not a block  
+
+            > note  
+            text  
+
+            Last.  
+        "};
+        let formatted = format(input, Extensions::all());
+        assert_eq!(
+            formatted,
+            indoc! {"
+                Step @one{1}. [- block  
+                  comment  
+
+
+                -] more.
+                -- line [- not a block
+
+                > note  
+                text  
+
+                Last.
+            "}
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! `import` command
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{ensure, Context as _};
+use clap::ValueEnum;
+use cooklang::import;
+
+use crate::{read_input, Context, Format};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// File to import, `-` for stdin
+    file: PathBuf,
+    /// Format of the file
+    #[arg(long, value_enum, default_value_t = From::Jsonld)]
+    from: From,
+    /// Write the recipe to this file instead of printing it
+    ///
+    /// For files with many recipes, a directory where each one is written as
+    /// `<title>.cook`.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum From {
+    /// schema.org Recipe JSON-LD
+    Jsonld,
+    /// Paprika recipe JSON, unzipped
+    Paprika,
+    /// MealMaster text, can have many recipes
+    Mealmaster,
+    /// RecipeML XML, can have many recipes
+    Recipeml,
+}
+
+pub fn run(args: Args, ctx: &Context) -> anyhow::Result<ExitCode> {
+    ensure!(
+        ctx.format == Format::Human,
+        "import only has the human output format"
+    );
+    let input = read_input(&args.file)?;
+    let converter = ctx.parser.converter();
+    let recipes = match args.from {
+        From::Jsonld => import::jsonld::import(&input.text, converter).map(|r| vec![r]),
+        From::Paprika => import::paprika::import(&input.text, converter).map(|r| vec![r]),
+        From::Mealmaster => import::mealmaster::import(&input.text, converter),
+        From::Recipeml => import::recipeml::import(&input.text, converter),
+    }
+    .with_context(|| format!("failed to import '{}'", input.name))?;
+
+    let [imported] = recipes.as_slice() else {
+        let dir = args.output.with_context(|| {
+            format!(
+                "'{}' has {} recipes, use --output to write them to a directory",
+                input.name,
+                recipes.len()
+            )
+        })?;
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create '{}'", dir.display()))?;
+        for (index, imported) in recipes.iter().enumerate() {
+            let name = file_name(imported, ctx).unwrap_or_else(|| format!("recipe-{}", index + 1));
+            let path = dir.join(format!("{name}.cook"));
+            warn(imported, Some(&path));
+            write(&path, &imported.text)?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    warn(imported, None);
+    match &args.output {
+        Some(path) => write(path, &imported.text)?,
+        None => print!("{}", imported.text),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn warn(imported: &import::ImportedRecipe, path: Option<&Path>) {
+    for warning in &imported.warnings {
+        match path {
+            Some(path) => eprintln!("warning: {}: {warning}", path.display()),
+            None => eprintln!("warning: {warning}"),
+        }
+    }
+}
+
+fn write(path: &Path, text: &str) -> anyhow::Result<()> {
+    std::fs::write(path, text).with_context(|| format!("failed to write '{}'", path.display()))
+}
+
+/// File name from the title, without the characters not valid in paths
+fn file_name(imported: &import::ImportedRecipe, ctx: &Context) -> Option<String> {
+    let recipe = imported.parse(&ctx.parser).into_output()?;
+    let name = recipe
+        .metadata
+        .title()?
+        .chars()
+        .filter(|c| !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .collect::<String>();
+    let name = name.trim().trim_start_matches('.');
+    (!name.is_empty()).then(|| name.to_string())
+}
@@ -0,0 +1,186 @@
+//! `cooklang` command line tool
+
+use std::io::{IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use anyhow::{bail, Context as _};
+use clap::{Parser, Subcommand, ValueEnum};
+use cooklang::{
+    convert::System, scale::SectionScale, Converter, CooklangParser, ScalableRecipe, ScaledRecipe,
+};
+use serde::Serialize;
+
+mod check;
+mod fmt;
+mod import;
+mod recipe;
+mod shopping_list;
+mod units;
+
+#[derive(Parser)]
+#[command(name = "cooklang", version, about = "Work with Cooklang recipes")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Output format
+    #[arg(long, short, global = true, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check recipes for errors and warnings
+    Check(check::Args),
+    /// Show a recipe
+    Recipe(recipe::Args),
+    /// Merge the ingredients of recipes into a shopping list
+    ShoppingList(shopping_list::Args),
+    /// Show the known units
+    Units(units::Args),
+    /// Format recipes
+    Fmt(fmt::Args),
+    /// Convert a recipe from another format to Cooklang
+    Import(import::Args),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+    Yaml,
+}
+
+/// Shared state of the commands
+struct Context {
+    parser: CooklangParser,
+    format: Format,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let ctx = Context {
+        parser: CooklangParser::default(),
+        format: cli.format,
+    };
+    let result = match cli.command {
+        Command::Check(args) => check::run(args, &ctx),
+        Command::Recipe(args) => recipe::run(args, &ctx),
+        Command::ShoppingList(args) => shopping_list::run(args, &ctx),
+        Command::Units(args) => units::run(args, &ctx),
+        Command::Fmt(args) => fmt::run(args, &ctx),
+        Command::Import(args) => import::run(args, &ctx),
+    };
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// A file read from disk or stdin
+struct Input {
+    /// Name to show in the diagnostics
+    name: String,
+    text: String,
+}
+
+/// Reads a file, `-` is stdin
+fn read_input(path: &Path) -> anyhow::Result<Input> {
+    if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .context("failed to read stdin")?;
+        return Ok(Input {
+            name: "<stdin>".to_string(),
+            text,
+        });
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read '{}'", path.display()))?;
+    Ok(Input {
+        name: path.display().to_string(),
+        text,
+    })
+}
+
+impl Context {
+    /// Parses a recipe, printing the warnings and failing if it has errors
+    fn parse(&self, input: &Input) -> anyhow::Result<ScalableRecipe> {
+        let color = std::io::stderr().is_terminal();
+        match self.parser.parse(&input.text).into_result() {
+            Ok((recipe, warnings)) => {
+                warnings.eprint(&input.name, &input.text, color)?;
+                Ok(recipe)
+            }
+            Err(report) => {
+                report.eprint(&input.name, &input.text, color)?;
+                bail!("failed to parse '{}'", input.name)
+            }
+        }
+    }
+
+    /// Writes serializable data in the JSON or YAML format
+    fn write_data(&self, value: &impl Serialize) -> anyhow::Result<()> {
+        let mut out = std::io::stdout().lock();
+        match self.format {
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut out, value)?;
+                writeln!(out)?;
+            }
+            Format::Yaml => serde_yaml::to_writer(&mut out, value)?,
+            Format::Human => unreachable!("human output is written by each command"),
+        }
+        Ok(())
+    }
+}
+
+/// How to scale and convert a recipe
+#[derive(clap::Args)]
+struct ScaleArgs {
+    /// Scale the quantities by a factor
+    #[arg(long, conflicts_with = "servings")]
+    scale: Option<f64>,
+    /// Scale the quantities to a number of servings
+    #[arg(long)]
+    servings: Option<u32>,
+    /// Convert the quantities to `metric` or `imperial` units
+    #[arg(long)]
+    system: Option<System>,
+    /// Leave out the optional sections, like `==? Sauce ==`
+    #[arg(long)]
+    skip_optional: bool,
+}
+
+impl ScaleArgs {
+    fn apply(&self, recipe: ScalableRecipe, converter: &Converter) -> ScaledRecipe {
+        let skipped = recipe
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| self.skip_optional && s.optional)
+            .map(|(index, _)| (index, SectionScale::Skip))
+            .collect::<Vec<_>>();
+        let factor = match (self.scale, self.servings) {
+            (Some(factor), _) => Some(factor),
+            (None, Some(servings)) => {
+                let base = recipe.servings().and_then(|s| s.first()).copied();
+                Some(f64::from(servings) / f64::from(base.unwrap_or(1)))
+            }
+            (None, None) if !skipped.is_empty() => Some(1.0),
+            (None, None) => None,
+        };
+        let mut recipe = match factor {
+            Some(factor) => recipe.scale_sections(factor, &skipped, converter),
+            None => recipe.default_scale(),
+        };
+        if let Some(system) = self.system {
+            // quantities that can't be converted are left as they are
+            let _ = recipe.convert(system, converter);
+        }
+        recipe
+    }
+}
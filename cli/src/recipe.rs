@@ -0,0 +1,157 @@
+//! `recipe` command
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::Context as _;
+use cooklang::{
+    images::find_images, metadata::CooklangValueExt, Content, Converter, Item, ScaledRecipe,
+};
+
+use crate::{read_input, Context, Format, ScaleArgs};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Recipe file, `-` for stdin
+    file: PathBuf,
+    #[command(flatten)]
+    scale: ScaleArgs,
+}
+
+pub fn run(args: Args, ctx: &Context) -> anyhow::Result<ExitCode> {
+    let input = read_input(&args.file)?;
+    let mut recipe = ctx.parse(&input)?;
+    if args.file != Path::new("-") {
+        let images = find_images(&args.file)
+            .with_context(|| format!("failed to find the images of '{}'", input.name))?;
+        recipe.attach_images(&images);
+    }
+    let recipe = args.scale.apply(recipe, ctx.parser.converter());
+    match ctx.format {
+        Format::Human => write_recipe(
+            &recipe,
+            ctx.parser.converter(),
+            &mut std::io::stdout().lock(),
+        )?,
+        _ => ctx.write_data(&recipe)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn write_recipe(
+    recipe: &ScaledRecipe,
+    converter: &Converter,
+    w: &mut impl Write,
+) -> std::io::Result<()> {
+    if let Some(title) = recipe.metadata.title() {
+        writeln!(w, "{title}\n")?;
+    }
+    let metadata = recipe
+        .metadata
+        .map
+        .iter()
+        .filter_map(|(key, value)| Some((key.as_str_like()?, value.as_str_like()?)))
+        .filter(|(key, _)| key != "title")
+        .collect::<Vec<_>>();
+    if !metadata.is_empty() {
+        for (key, value) in metadata {
+            writeln!(w, "{key}: {value}")?;
+        }
+        writeln!(w)?;
+    }
+
+    let ingredients = recipe.group_ingredients(converter);
+    let ingredients = ingredients
+        .iter()
+        .filter(|e| e.ingredient.modifiers().should_be_listed())
+        .collect::<Vec<_>>();
+    if !ingredients.is_empty() {
+        writeln!(w, "Ingredients:")?;
+        for entry in ingredients {
+            write!(w, "  - {}", entry.ingredient.display_name())?;
+            if !entry.quantity.is_empty() {
+                write!(w, ": {}", entry.quantity)?;
+            }
+            if let Some(note) = &entry.ingredient.note {
+                write!(w, " ({note})")?;
+            }
+            writeln!(w)?;
+        }
+        writeln!(w)?;
+    }
+
+    let cookware = recipe
+        .group_cookware()
+        .into_iter()
+        .filter(|e| e.cookware.modifiers().should_be_listed())
+        .collect::<Vec<_>>();
+    if !cookware.is_empty() {
+        writeln!(w, "Cookware:")?;
+        for entry in cookware {
+            write!(w, "  - {}", entry.cookware.display_name())?;
+            if !entry.amount.is_empty() {
+                write!(w, ": {}", entry.amount)?;
+            }
+            if let Some(note) = &entry.cookware.note {
+                write!(w, " ({note})")?;
+            }
+            writeln!(w)?;
+        }
+        writeln!(w)?;
+    }
+
+    for (index, section) in recipe.sections.iter().enumerate() {
+        if recipe.is_section_skipped(index) {
+            continue;
+        }
+        if let Some(name) = &section.name {
+            writeln!(w, "== {name} ==")?;
+        } else if recipe.sections.len() > 1 {
+            writeln!(w, "== Section {} ==", index + 1)?;
+        }
+        for content in &section.content {
+            match content {
+                Content::Step(step) => {
+                    write!(w, "{}. ", step.number)?;
+                    for item in &step.items {
+                        write_item(recipe, item, w)?;
+                    }
+                    writeln!(w)?;
+                    for image in &step.images {
+                        writeln!(w, "   [{image}]")?;
+                    }
+                }
+                Content::Text(text) => writeln!(w, "{text}")?,
+            }
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+fn write_item(recipe: &ScaledRecipe, item: &Item, w: &mut impl Write) -> std::io::Result<()> {
+    match item {
+        Item::Text { value } => write!(w, "{value}"),
+        Item::Ingredient { index } => {
+            let ingredient = &recipe.ingredients[*index];
+            write!(w, "{}", ingredient.display_name())?;
+            if let Some(quantity) = &ingredient.quantity {
+                write!(w, " ({quantity})")?;
+            }
+            Ok(())
+        }
+        Item::Cookware { index } => write!(w, "{}", recipe.cookware[*index].display_name()),
+        Item::Timer { index } => {
+            let timer = &recipe.timers[*index];
+            match (&timer.name, &timer.quantity) {
+                (Some(name), Some(quantity)) => write!(w, "{name} ({quantity})"),
+                (Some(name), None) => write!(w, "{name}"),
+                (None, Some(quantity)) => write!(w, "{quantity}"),
+                (None, None) => Ok(()),
+            }
+        }
+        Item::InlineQuantity { index } => write!(w, "{}", recipe.inline_quantities[*index]),
+        Item::Temperature { index } => write!(w, "{}", recipe.temperatures[*index]),
+    }
+}
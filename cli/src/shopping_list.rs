@@ -0,0 +1,98 @@
+//! `shopping-list` command
+
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::bail;
+use cooklang::{aisle::AisleConf, ingredient_list::IngredientList, ScaledQuantity};
+use serde::Serialize;
+
+use crate::{read_input, Context, Format, ScaleArgs};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Recipe files, `-` for stdin
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Aisle configuration file to group the ingredients by category
+    #[arg(long)]
+    aisle: Option<PathBuf>,
+    #[command(flatten)]
+    scale: ScaleArgs,
+}
+
+#[derive(Serialize)]
+struct Category<'a> {
+    name: &'a str,
+    ingredients: Vec<Ingredient<'a>>,
+}
+
+#[derive(Serialize)]
+struct Ingredient<'a> {
+    name: &'a str,
+    quantities: Vec<&'a ScaledQuantity>,
+}
+
+pub fn run(args: Args, ctx: &Context) -> anyhow::Result<ExitCode> {
+    let converter = ctx.parser.converter();
+    let mut list = IngredientList::new();
+    for path in &args.files {
+        let input = read_input(path)?;
+        let recipe = ctx.parse(&input)?;
+        let recipe = args.scale.apply(recipe, converter);
+        list.add_recipe(&recipe, converter, true);
+    }
+
+    let aisle_input = args.aisle.as_deref().map(read_input).transpose()?;
+    let aisle = match &aisle_input {
+        Some(input) => match cooklang::aisle::parse(&input.text) {
+            Ok(aisle) => aisle,
+            Err(err) => {
+                let color = std::io::stderr().is_terminal();
+                cooklang::error::write_rich_error(
+                    &err,
+                    &input.name,
+                    &input.text,
+                    color,
+                    std::io::stderr().lock(),
+                )?;
+                bail!("failed to parse '{}'", input.name);
+            }
+        },
+        None => AisleConf::default(),
+    };
+    let categories = list.categorize(&aisle);
+
+    if ctx.format == Format::Human {
+        let mut w = std::io::stdout().lock();
+        for (category, list) in categories.iter() {
+            if args.aisle.is_some() {
+                writeln!(w, "[{category}]")?;
+            }
+            for (name, quantity) in list.iter() {
+                if quantity.is_empty() {
+                    writeln!(w, "{name}")?;
+                } else {
+                    writeln!(w, "{name}: {quantity}")?;
+                }
+            }
+        }
+    } else {
+        let output = categories
+            .iter()
+            .map(|(name, list)| Category {
+                name,
+                ingredients: list
+                    .iter()
+                    .map(|(name, quantity)| Ingredient {
+                        name,
+                        quantities: quantity.iter().collect(),
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        ctx.write_data(&output)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,84 @@
+//! `units` command
+
+use std::io::Write;
+use std::process::ExitCode;
+
+use anyhow::Context as _;
+use cooklang::convert::{PhysicalQuantity, System};
+
+use crate::{Context, Format};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Show only this unit, by any of its names, symbols or aliases
+    unit: Option<String>,
+    /// Show only the units of `metric` or `imperial` system
+    #[arg(long)]
+    system: Option<System>,
+    /// Show only the units of `volume`, `mass`, `length`, `temperature` or
+    /// `time`
+    #[arg(long)]
+    quantity: Option<PhysicalQuantity>,
+}
+
+pub fn run(args: Args, ctx: &Context) -> anyhow::Result<ExitCode> {
+    let converter = ctx.parser.converter();
+
+    if let Some(name) = &args.unit {
+        let unit = converter
+            .find_unit(name)
+            .with_context(|| format!("unknown unit '{name}'"))?;
+        if ctx.format == Format::Human {
+            let mut w = std::io::stdout().lock();
+            writeln!(w, "{}", unit.symbol())?;
+            write_list(&mut w, "names", &unit.names)?;
+            write_list(&mut w, "symbols", &unit.symbols)?;
+            write_list(&mut w, "aliases", &unit.aliases)?;
+            writeln!(w, "  quantity: {}", unit.physical_quantity)?;
+            if let Some(system) = unit.system {
+                writeln!(w, "  system: {system}")?;
+            }
+            writeln!(w, "  ratio: {}", unit.ratio)?;
+            if unit.difference != 0.0 {
+                writeln!(w, "  difference: {}", unit.difference)?;
+            }
+            writeln!(w, "  best unit: {}", converter.is_best_unit(&unit))?;
+        } else {
+            ctx.write_data(&unit)?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut units = converter
+        .all_units()
+        .filter(|u| args.system.is_none_or(|s| u.system == Some(s)))
+        .filter(|u| args.quantity.is_none_or(|q| u.physical_quantity == q))
+        .collect::<Vec<_>>();
+    units.sort_by_key(|u| u.physical_quantity);
+    if ctx.format == Format::Human {
+        let mut w = std::io::stdout().lock();
+        let mut last_quantity = None;
+        for unit in units {
+            if last_quantity != Some(unit.physical_quantity) {
+                writeln!(w, "{}:", unit.physical_quantity)?;
+                last_quantity = Some(unit.physical_quantity);
+            }
+            write!(w, "  {:<8}", unit.symbol())?;
+            write!(w, " {}", unit.names.join(", "))?;
+            if let Some(system) = unit.system {
+                write!(w, " ({system})")?;
+            }
+            writeln!(w)?;
+        }
+    } else {
+        ctx.write_data(&units)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn write_list(w: &mut impl Write, name: &str, list: &[std::sync::Arc<str>]) -> std::io::Result<()> {
+    if !list.is_empty() {
+        writeln!(w, "  {name}: {}", list.join(", "))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,290 @@
+//! Runs the `cooklang` binary
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+use indoc::indoc;
+use serde_json::Value;
+
+fn cooklang(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cooklang"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("start cooklang");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn json(output: &Output) -> Value {
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+fn write_file(name: &str, content: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+const PANCAKES: &str = indoc! {"
+    ---
+    servings: 2
+    ---
+    Mix @flour{200%g}, @milk{300%ml} and @eggs{2} in a #bowl.
+
+    Cook in a #pan for ~{2%minutes}.
+"};
+
+#[test]
+fn check() {
+    let out = cooklang(&["check", "-f", "json", "-"], "Add @flour{100%}.");
+    assert!(out.status.success());
+    let value = json(&out);
+    assert_eq!(value[0]["file"], "<stdin>");
+    assert_eq!(value[0]["diagnostics"][0]["code"], "empty-unit");
+
+    let out = cooklang(&["check", "-"], "Add @&flour{}.");
+    assert!(!out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("1 errors, 0 warnings"));
+
+    let out = cooklang(&["check", "--lint", "-f", "json", "-"], PANCAKES);
+    let codes = json(&out)[0]["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["code"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(codes, ["missing-metadata", "unnamed-timer"]);
+}
+
+#[test]
+fn check_schema() {
+    let schema = write_file(
+        "schema.json",
+        r#"{"required": ["title"], "properties": {"servings": {"type": "string"}}}"#,
+    );
+    let schema = schema.to_str().unwrap();
+    let out = cooklang(&["check", "--schema", schema, "-f", "json", "-"], PANCAKES);
+    assert!(out.status.success());
+    let messages = json(&out)[0]["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["message"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Invalid metadata: metadata is missing the required key 'title'",
+            "Invalid metadata: 'servings' must be a string, found an integer",
+        ]
+    );
+
+    // the schema is read before the recipes, so they are not in stdin
+    let bad = write_file("bad_schema.json", r#"{"type": "recipe"}"#);
+    let recipe = write_file("check_schema.cook", PANCAKES);
+    let args = [
+        "check",
+        "--schema",
+        bad.to_str().unwrap(),
+        recipe.to_str().unwrap(),
+    ];
+    let out = cooklang(&args, "");
+    assert!(!out.status.success());
+}
+
+#[test]
+fn recipe() {
+    let out = cooklang(&["recipe", "-f", "json", "--servings", "4", "-"], PANCAKES);
+    assert!(out.status.success());
+    let value = json(&out);
+    assert_eq!(value["ingredients"][0]["name"], "flour");
+    assert_eq!(
+        value["ingredients"][0]["quantity"]["value"]["value"]["value"],
+        400.0
+    );
+
+    let out = cooklang(&["recipe", "-"], PANCAKES);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("  - milk: 300 ml\n"));
+    assert!(stdout.contains("1. Mix flour (200 g), milk (300 ml) and eggs (2) in a bowl.\n"));
+
+    let input = "Cook @pasta{200%g}.\n\n==? Sauce ==\nAdd @tomato{100%g}.";
+    let out = cooklang(&["shopping-list", "--skip-optional", "-"], input);
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "pasta: 200 g\n");
+    let out = cooklang(&["recipe", "--skip-optional", "-"], input);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("Cook pasta (200 g)."));
+    assert!(!stdout.contains("Sauce"));
+    assert!(!stdout.contains("tomato"));
+
+    let out = cooklang(&["recipe", "-"], "Add @&flour{}.");
+    assert!(!out.status.success());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("failed to parse '<stdin>'"));
+}
+
+#[test]
+fn recipe_images() {
+    let path = write_file("Toast.cook", "Toast the @bread{}.\n\nAdd @butter{}.");
+    let image = write_file("Toast.2.jpg", "");
+    write_file("Toast.3.jpg", "");
+
+    let out = cooklang(&["recipe", "-f", "json", path.to_str().unwrap()], "");
+    assert!(out.status.success());
+    let content = &json(&out)["sections"][0]["content"];
+    assert!(content[0]["value"].get("images").is_none());
+    assert_eq!(
+        content[1]["value"]["images"],
+        serde_json::json!([image.to_str().unwrap()])
+    );
+
+    let out = cooklang(&["recipe", path.to_str().unwrap()], "");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains(&format!("2. Add butter.\n   [{}]\n", image.display())));
+}
+
+#[test]
+fn shopping_list() {
+    let a = write_file("shopping_a.cook", PANCAKES);
+    let b = write_file("shopping_b.cook", "Add @flour{100%g} and @salt.");
+    let aisle = write_file("shopping.conf", "[baking]\nflour\nsalt\n[dairy]\nmilk\n");
+    let (a, b, aisle) = (
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+        aisle.to_str().unwrap(),
+    );
+
+    let out = cooklang(&["shopping-list", a, b, "--aisle", aisle], "");
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        indoc! {"
+            [baking]
+            flour: 300 g
+            salt
+            [dairy]
+            milk: 300 ml
+            [other]
+            eggs: 2
+        "}
+    );
+
+    let out = cooklang(&["shopping-list", "-f", "yaml", b], "");
+    let value: serde_yaml::Value = serde_yaml::from_slice(&out.stdout).unwrap();
+    assert_eq!(value[0]["name"].as_str(), Some("other"));
+    assert_eq!(value[0]["ingredients"][1]["name"].as_str(), Some("salt"));
+}
+
+#[test]
+fn units() {
+    let out = cooklang(&["units", "-f", "json", "tsp"], "");
+    assert!(out.status.success());
+    let value = json(&out);
+    assert_eq!(value["physical_quantity"], "volume");
+    assert_eq!(value["system"], "imperial");
+
+    let out = cooklang(&["units", "-f", "json", "--quantity", "time"], "");
+    let value = json(&out);
+    assert!(value
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|u| u["physical_quantity"] == "time"));
+
+    let out = cooklang(&["units", "nope"], "");
+    assert!(!out.status.success());
+}
+
+#[test]
+fn fmt() {
+    let out = cooklang(
+        &["fmt", "-"],
+        ">>servings:2\nAdd @flour{ 100 % g }.  \n\n\n",
+    );
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        ">> servings: 2\nAdd @flour{100%g}.\n"
+    );
+
+    let path = write_file("fmt.cook", "Add @flour{ 100 % g }.\n");
+    let path = path.to_str().unwrap();
+    let out = cooklang(&["fmt", "--check", path], "");
+    assert!(!out.status.success());
+    let out = cooklang(&["fmt", "--write", path], "");
+    assert!(out.status.success());
+    let out = cooklang(&["fmt", "--check", path], "");
+    assert!(out.status.success());
+}
+
+#[test]
+fn import() {
+    let json = r#"{
+        "@type": "Recipe",
+        "name": "Salad",
+        "recipeIngredient": ["1 head lettuce", "2 tbsp olive oil"],
+        "recipeInstructions": "Chop the lettuce and dress with the oil."
+    }"#;
+    let out = cooklang(&["import", "-"], json);
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        indoc! {"
+            ---
+            title: Salad
+            ---
+
+            Chop the @head lettuce|lettuce{1} and dress with the @olive oil|oil{2%tbsp}.
+        "}
+    );
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("warning: Ingredient 'olive oil' matched 'oil'"));
+
+    let out = cooklang(&["import", "-"], "[]");
+    assert!(!out.status.success());
+}
+
+#[test]
+fn import_many() {
+    let input = indoc! {"
+        MMMMM----- Recipe via Meal-Master (tm) v8.05
+              Title: Toast
+
+              1 sl Bread
+
+          Toast the bread.
+        MMMMM
+        MMMMM----- Recipe via Meal-Master (tm) v8.05
+              Title: Tea
+
+              1 c  Water
+
+          Boil the water.
+        MMMMM
+    "};
+    let out = cooklang(&["import", "--from", "mealmaster", "-"], input);
+    assert!(!out.status.success());
+
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("imported");
+    let dir_arg = dir.to_str().unwrap();
+    let out = cooklang(
+        &["import", "--from", "mealmaster", "-", "-o", dir_arg],
+        input,
+    );
+    assert!(out.status.success());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("Toast.cook")).unwrap(),
+        "---\ntitle: Toast\n---\n\nToast the @bread{1%slice}.\n"
+    );
+    assert!(dir.join("Tea.cook").exists());
+}
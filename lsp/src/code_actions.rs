@@ -0,0 +1,43 @@
+//! Quick fixes from the suggestions of the diagnostics
+
+use std::collections::HashMap;
+
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionResponse, Range, TextEdit, Url,
+    WorkspaceEdit,
+};
+
+use crate::{diagnostics::to_lsp_diagnostic, document::Document};
+
+pub(crate) fn code_actions(doc: &Document, uri: &Url, range: Range) -> Option<CodeActionResponse> {
+    let start = doc.offset(range.start);
+    let end = doc.offset(range.end);
+
+    let mut actions = Vec::new();
+    for diag in doc.report.iter() {
+        let Some((span, _)) = diag.labels.first() else {
+            continue;
+        };
+        if span.end() < start || span.start() > end {
+            continue;
+        }
+        for suggestion in &diag.suggestions {
+            let edit = TextEdit::new(
+                doc.range(suggestion.span),
+                suggestion.replacement.to_string(),
+            );
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: suggestion.message.to_string(),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![to_lsp_diagnostic(doc, uri, diag)]),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                    ..Default::default()
+                }),
+                is_preferred: Some(diag.suggestions.len() == 1),
+                ..Default::default()
+            }));
+        }
+    }
+    Some(actions)
+}
@@ -0,0 +1,114 @@
+//! Completion of ingredient, cookware and unit names
+
+use std::collections::BTreeSet;
+
+use lsp_types::{CompletionItem, CompletionItemKind, CompletionResponse, Position};
+
+use cooklang::Converter;
+
+use crate::document::Document;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Context {
+    Ingredient,
+    Cookware,
+    Unit,
+}
+
+pub(crate) fn completion(
+    doc: &Document,
+    position: Position,
+    converter: &Converter,
+) -> Option<CompletionResponse> {
+    let offset = doc.offset(position);
+    let line_start = doc.text()[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let context = context(&doc.text()[line_start..offset])?;
+
+    let items = match context {
+        Context::Ingredient | Context::Cookware => {
+            // a previous parse is still useful to complete names
+            let recipe = &doc.parsed.as_ref()?.recipe;
+            let names: BTreeSet<&str> = if context == Context::Ingredient {
+                recipe.ingredients.iter().map(|i| i.name.as_str()).collect()
+            } else {
+                recipe.cookware.iter().map(|c| c.name.as_str()).collect()
+            };
+            let kind = if context == Context::Ingredient {
+                "ingredient"
+            } else {
+                "cookware"
+            };
+            names
+                .into_iter()
+                .map(|name| CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(CompletionItemKind::VARIABLE),
+                    detail: Some(kind.to_string()),
+                    // multi word names need the braces
+                    insert_text: name
+                        .contains(char::is_whitespace)
+                        .then(|| format!("{name}{{}}")),
+                    ..Default::default()
+                })
+                .collect()
+        }
+        Context::Unit => {
+            let mut seen = BTreeSet::new();
+            let mut items = Vec::new();
+            for unit in converter.all_units() {
+                for key in unit.symbols.iter().chain(&unit.names) {
+                    if seen.insert(key.to_string()) {
+                        items.push(CompletionItem {
+                            label: key.to_string(),
+                            kind: Some(CompletionItemKind::UNIT),
+                            detail: Some(unit.physical_quantity.to_string()),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+            items
+        }
+    };
+    Some(CompletionResponse::Array(items))
+}
+
+/// What to complete given the text of the line before the cursor
+fn context(before: &str) -> Option<Context> {
+    let open = before.rfind('{');
+    if open > before.rfind('}') {
+        // inside a quantity, units go after the `%`
+        return before[open? + 1..].contains('%').then_some(Context::Unit);
+    }
+    let sigil = before.rfind(['@', '#'])?;
+    let rest = &before[sigil + 1..];
+    // only modifiers, the intermediate reference and the name
+    if rest
+        .contains(|c: char| !(c.is_alphanumeric() || c.is_whitespace() || "&?-+()~=".contains(c)))
+    {
+        return None;
+    }
+    match before.as_bytes()[sigil] {
+        b'@' => Some(Context::Ingredient),
+        _ => Some(Context::Cookware),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completion_context() {
+        assert_eq!(context("Add @"), Some(Context::Ingredient));
+        assert_eq!(context("Add @&fl"), Some(Context::Ingredient));
+        assert_eq!(context("Add @&(~1)do"), Some(Context::Ingredient));
+        assert_eq!(context("Add #big"), Some(Context::Cookware));
+        assert_eq!(context("Add @flour{100%"), Some(Context::Unit));
+        assert_eq!(context("Add @flour{100%k"), Some(Context::Unit));
+        assert_eq!(context("Add @flour{100"), None);
+        assert_eq!(context("Add @flour{100%g}"), None);
+        assert_eq!(context("Add @flour{100%g} and"), None);
+        assert_eq!(context("Add flour"), None);
+    }
+}
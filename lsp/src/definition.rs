@@ -0,0 +1,50 @@
+//! Go to definition of references and intermediate preparations
+
+use lsp_types::{GotoDefinitionResponse, Location, Position, Url};
+
+use cooklang::{IngredientReferenceTarget, Span};
+
+use crate::document::{Document, Element, Parsed};
+
+pub(crate) fn definition(
+    doc: &Document,
+    uri: &Url,
+    position: Position,
+) -> Option<GotoDefinitionResponse> {
+    let parsed = doc.current()?;
+    let (element, _) = parsed.element_at(doc.offset(position))?;
+    let span = definition_span(parsed, element)?;
+    Some(GotoDefinitionResponse::Scalar(Location::new(
+        uri.clone(),
+        doc.range(span),
+    )))
+}
+
+/// Where the component that `element` references is
+pub(crate) fn definition_span(parsed: &Parsed, element: Element) -> Option<Span> {
+    let map = &parsed.source_map;
+    match element {
+        Element::Ingredient(index) => {
+            let ingredient = &parsed.recipe.ingredients[index];
+            let (target_index, target) = ingredient.relation.references_to()?;
+            match target {
+                IngredientReferenceTarget::Ingredient => map.ingredients.get(target_index).copied(),
+                IngredientReferenceTarget::Step => {
+                    let graph = parsed.recipe.step_graph();
+                    let node = graph.ingredient_node(index)?;
+                    let section = graph.steps()[node].section;
+                    map.sections[section].content.get(target_index).copied()
+                }
+                IngredientReferenceTarget::Section => {
+                    let section = map.sections.get(target_index)?;
+                    section.name.or_else(|| section.content.first().copied())
+                }
+            }
+        }
+        Element::Cookware(index) => {
+            let target_index = parsed.recipe.cookware[index].relation.references_to()?;
+            map.cookware.get(target_index).copied()
+        }
+        Element::Timer(_) | Element::InlineQuantity(_) => None,
+    }
+}
@@ -0,0 +1,58 @@
+//! Parser diagnostics to LSP diagnostics
+
+use std::error::Error;
+
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Url,
+};
+
+use cooklang::error::{Severity, SourceDiag};
+
+use crate::document::Document;
+
+pub(crate) fn diagnostics(doc: &Document, uri: &Url) -> Vec<Diagnostic> {
+    doc.report
+        .iter()
+        .map(|diag| to_lsp_diagnostic(doc, uri, diag))
+        .collect()
+}
+
+pub(crate) fn to_lsp_diagnostic(doc: &Document, uri: &Url, diag: &SourceDiag) -> Diagnostic {
+    let mut labels = diag.labels.iter();
+    let (range, mut message) = match labels.next() {
+        Some((span, label)) => {
+            let message = match label {
+                Some(label) => format!("{}: {label}", diag.message),
+                None => diag.message.to_string(),
+            };
+            (doc.range(*span), message)
+        }
+        None => (Default::default(), diag.message.to_string()),
+    };
+    if let Some(source) = diag.source() {
+        message.push_str(&format!("\n{source}"));
+    }
+    for hint in &diag.hints {
+        message.push_str(&format!("\nhelp: {hint}"));
+    }
+
+    let related = labels
+        .map(|(span, label)| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), doc.range(*span)),
+            message: label.as_deref().unwrap_or("here").to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    Diagnostic {
+        range,
+        severity: Some(match diag.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+        }),
+        code: Some(NumberOrString::String(diag.code.to_string())),
+        source: Some("cooklang".to_string()),
+        message,
+        related_information: (!related.is_empty()).then_some(related),
+        ..Default::default()
+    }
+}
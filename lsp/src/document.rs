@@ -0,0 +1,149 @@
+//! Open documents and their parsed state
+
+use cooklang::{
+    analysis::SourceMap,
+    error::SourceReport,
+    incremental::{IncrementalState, TextEdit},
+    line_index::{ColumnUnit, LineCol, LineIndex},
+    CooklangParser, ParseOptions, ScalableRecipe, Span,
+};
+use lsp_types::TextDocumentContentChangeEvent;
+
+pub(crate) struct Document {
+    state: IncrementalState,
+    pub line_index: LineIndex,
+    pub report: SourceReport,
+    /// Last recipe that could be parsed
+    ///
+    /// This may be from a previous version of the text if the current one has
+    /// errors, so spans may not match [`Document::text`]. Check
+    /// [`Parsed::is_current`].
+    pub parsed: Option<Parsed>,
+}
+
+pub(crate) struct Parsed {
+    pub recipe: ScalableRecipe,
+    pub source_map: SourceMap,
+    pub is_current: bool,
+}
+
+/// A recipe component, with its index in the recipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Element {
+    Ingredient(usize),
+    Cookware(usize),
+    Timer(usize),
+    InlineQuantity(usize),
+}
+
+impl Parsed {
+    /// Component at a byte offset
+    pub fn element_at(&self, offset: usize) -> Option<(Element, Span)> {
+        let map = &self.source_map;
+        let find = |spans: &[Span]| {
+            spans
+                .iter()
+                .position(|s| s.start() <= offset && offset <= s.end())
+        };
+        if let Some(i) = find(&map.ingredients) {
+            return Some((Element::Ingredient(i), map.ingredients[i]));
+        }
+        if let Some(i) = find(&map.cookware) {
+            return Some((Element::Cookware(i), map.cookware[i]));
+        }
+        if let Some(i) = find(&map.timers) {
+            return Some((Element::Timer(i), map.timers[i]));
+        }
+        find(&map.inline_quantities).map(|i| (Element::InlineQuantity(i), map.inline_quantities[i]))
+    }
+}
+
+impl Document {
+    pub fn new(text: String, parser: &CooklangParser) -> Self {
+        let mut state = IncrementalState::new(text);
+        let (parsed, report) = parse(&mut state, parser);
+        Self {
+            line_index: LineIndex::new(state.input()),
+            state,
+            report,
+            parsed,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        self.state.input()
+    }
+
+    /// Applies the changes to the text and parses it again
+    pub fn update(
+        &mut self,
+        changes: Vec<TextDocumentContentChangeEvent>,
+        parser: &CooklangParser,
+    ) {
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let edit = TextEdit::new(
+                        self.offset(range.start)..self.offset(range.end),
+                        change.text,
+                    );
+                    // an invalid range is ignored, like other malformed
+                    // notifications
+                    if self.state.edit(&edit).is_err() {
+                        continue;
+                    }
+                }
+                None => self.state = IncrementalState::new(change.text),
+            }
+            // positions of the next change are in the updated text
+            self.line_index = LineIndex::new(self.state.input());
+        }
+
+        let (parsed, report) = parse(&mut self.state, parser);
+        match parsed {
+            Some(parsed) => self.parsed = Some(parsed),
+            None => {
+                if let Some(parsed) = &mut self.parsed {
+                    parsed.is_current = false;
+                }
+            }
+        }
+        self.report = report;
+    }
+
+    /// Parsed recipe only if it matches the current text
+    pub fn current(&self) -> Option<&Parsed> {
+        self.parsed.as_ref().filter(|p| p.is_current)
+    }
+
+    pub fn position(&self, offset: usize) -> lsp_types::Position {
+        let pos = self
+            .line_index
+            .line_col(self.text(), offset, ColumnUnit::Utf16);
+        lsp_types::Position::new(pos.line as u32, pos.column as u32)
+    }
+
+    pub fn range(&self, span: Span) -> lsp_types::Range {
+        lsp_types::Range::new(self.position(span.start()), self.position(span.end()))
+    }
+
+    pub fn offset(&self, position: lsp_types::Position) -> usize {
+        let pos = LineCol::new(position.line as usize, position.character as usize);
+        self.line_index.offset(self.text(), pos, ColumnUnit::Utf16)
+    }
+}
+
+fn parse(state: &mut IncrementalState, parser: &CooklangParser) -> (Option<Parsed>, SourceReport) {
+    let mut source_map = SourceMap::default();
+    let options = ParseOptions {
+        source_map: Some(&mut source_map),
+        ..Default::default()
+    };
+    let (recipe, report) = parser.parse_state_with_options(state, options).into_tuple();
+    let parsed = recipe.map(|recipe| Parsed {
+        recipe,
+        source_map,
+        is_current: true,
+    });
+    (parsed, report)
+}
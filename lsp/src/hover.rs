@@ -0,0 +1,128 @@
+//! Hover information for components
+
+use std::fmt::Write;
+
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position};
+
+use cooklang::{quantity::QuantityValue, Converter, IngredientReferenceTarget, Quantity, Span};
+
+use crate::document::{Document, Element};
+
+pub(crate) fn hover(doc: &Document, position: Position, converter: &Converter) -> Option<Hover> {
+    let parsed = doc.current()?;
+    let (element, span) = parsed.element_at(doc.offset(position))?;
+    let recipe = &parsed.recipe;
+
+    let mut s = String::new();
+    match element {
+        Element::Ingredient(index) => {
+            let igr = &recipe.ingredients[index];
+            writeln!(s, "**{}** *ingredient*", igr.display_name()).unwrap();
+            if let Some(q) = &igr.quantity {
+                write_quantity(&mut s, q, converter);
+            }
+            if let Some(note) = &igr.note {
+                writeln!(s, "\nNote: {note}").unwrap();
+            }
+            if let Some(reference) = &igr.reference {
+                writeln!(s, "\nRecipe: `{}`", reference.path("/")).unwrap();
+            }
+            if let Some((target_index, target)) = igr.relation.references_to() {
+                let text = match target {
+                    IngredientReferenceTarget::Ingredient => {
+                        let line = line_of(doc, parsed.source_map.ingredients[target_index]);
+                        format!("References the ingredient defined in line {line}")
+                    }
+                    IngredientReferenceTarget::Step => {
+                        let graph = recipe.step_graph();
+                        let section = graph
+                            .ingredient_node(index)
+                            .map(|n| graph.steps()[n].section);
+                        match section.and_then(|s| recipe.sections[s].content.get(target_index)) {
+                            Some(content) if content.is_step() => {
+                                format!("Output of step {}", content.unwrap_step().number)
+                            }
+                            _ => "Output of a previous step".to_string(),
+                        }
+                    }
+                    IngredientReferenceTarget::Section => {
+                        match &recipe.sections[target_index].name {
+                            Some(name) => format!("Output of section *{name}*"),
+                            None => format!("Output of section {}", target_index + 1),
+                        }
+                    }
+                };
+                writeln!(s, "\n{text}").unwrap();
+            } else {
+                write_referenced_from(&mut s, igr.relation.referenced_from().len());
+            }
+        }
+        Element::Cookware(index) => {
+            let cw = &recipe.cookware[index];
+            writeln!(s, "**{}** *cookware*", cw.display_name()).unwrap();
+            if let Some(amount) = &cw.quantity {
+                writeln!(s, "\nAmount: `{amount}`").unwrap();
+            }
+            if let Some(note) = &cw.note {
+                writeln!(s, "\nNote: {note}").unwrap();
+            }
+            if let Some(target_index) = cw.relation.references_to() {
+                let line = line_of(doc, parsed.source_map.cookware[target_index]);
+                writeln!(s, "\nReferences the cookware defined in line {line}").unwrap();
+            } else {
+                write_referenced_from(&mut s, cw.relation.referenced_from().len());
+            }
+        }
+        Element::Timer(index) => {
+            let timer = &recipe.timers[index];
+            match &timer.name {
+                Some(name) => writeln!(s, "**{name}** *timer*").unwrap(),
+                None => writeln!(s, "*timer*").unwrap(),
+            }
+            if let Some(q) = &timer.quantity {
+                write_quantity(&mut s, q, converter);
+            }
+        }
+        Element::InlineQuantity(index) => {
+            writeln!(s, "*inline quantity*").unwrap();
+            write_quantity(&mut s, &recipe.inline_quantities[index], converter);
+        }
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: s,
+        }),
+        range: Some(doc.range(span)),
+    })
+}
+
+fn write_quantity<V: QuantityValue>(s: &mut String, q: &Quantity<V>, converter: &Converter) {
+    writeln!(s, "\nQuantity: `{q}`").unwrap();
+    let Some(unit_text) = q.unit() else {
+        return;
+    };
+    match converter.find_unit(unit_text) {
+        Some(unit) => {
+            write!(s, "\nUnit: {unit:#} (`{unit}`), {}", unit.physical_quantity).unwrap();
+            if let Some(system) = unit.system {
+                write!(s, ", {system}").unwrap();
+            }
+            writeln!(s).unwrap();
+        }
+        None => writeln!(s, "\nUnknown unit `{unit_text}`").unwrap(),
+    }
+}
+
+fn write_referenced_from(s: &mut String, count: usize) {
+    match count {
+        0 => {}
+        1 => writeln!(s, "\nReferenced 1 time").unwrap(),
+        n => writeln!(s, "\nReferenced {n} times").unwrap(),
+    }
+}
+
+fn line_of(doc: &Document, span: Span) -> u32 {
+    doc.range(span).start.line + 1
+}
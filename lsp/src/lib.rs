@@ -0,0 +1,216 @@
+//! Language server for [cooklang](https://cooklang.org/) recipes.
+//!
+//! It provides:
+//! - Diagnostics from the parser.
+//! - Hover with the component, its quantity, unit and what it references.
+//! - Go to definition for references (`@&flour`) and intermediate preparations
+//!   (`@&(~1)dough`).
+//! - Completion of ingredient, cookware and unit names.
+//! - Quick fixes for the diagnostics that suggest one.
+//! - Document symbols for sections and steps.
+//! - Semantic tokens, from [`cooklang::highlight`].
+//!
+//! The server talks the protocol over stdio, see the `cooklang-lsp` binary.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{self, Notification as _},
+    request::{self, Request as _},
+    CodeActionProviderCapability, CompletionOptions, HoverProviderCapability, OneOf,
+    PublishDiagnosticsParams, SemanticTokensFullOptions, SemanticTokensOptions,
+    SemanticTokensResult, SemanticTokensServerCapabilities, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use cooklang::CooklangParser;
+
+mod code_actions;
+mod completion;
+mod definition;
+mod diagnostics;
+mod document;
+mod hover;
+mod semantic_tokens;
+mod symbols;
+
+use document::Document;
+
+type BoxError = Box<dyn Error + Sync + Send>;
+
+/// Capabilities the server announces in the initialization
+pub fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::INCREMENTAL,
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["@".into(), "#".into(), "%".into()]),
+            ..Default::default()
+        }),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: semantic_tokens::legend(),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    }
+}
+
+/// Runs the server until the client asks for a shutdown
+///
+/// Use [`Connection::stdio`] to create the connection.
+pub fn run(connection: &Connection) -> Result<(), BoxError> {
+    let capabilities = serde_json::to_value(server_capabilities())?;
+    connection.initialize(capabilities)?;
+
+    let mut server = Server::new();
+    for msg in &connection.receiver {
+        match msg {
+            Message::Request(req) => {
+                if connection.handle_shutdown(&req)? {
+                    return Ok(());
+                }
+                let response = server.handle_request(req);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(not) => {
+                if let Some(params) = server.handle_notification(not) {
+                    let not = Notification::new(
+                        notification::PublishDiagnostics::METHOD.to_string(),
+                        params,
+                    );
+                    connection.sender.send(Message::Notification(not))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+struct Server {
+    parser: CooklangParser,
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    fn new() -> Self {
+        Self {
+            parser: CooklangParser::default(),
+            documents: HashMap::new(),
+        }
+    }
+
+    fn handle_request(&mut self, req: Request) -> Response {
+        let id = req.id.clone();
+        let result = match req.method.as_str() {
+            request::HoverRequest::METHOD => self.on::<request::HoverRequest>(req, |s, p| {
+                let pos = p.text_document_position_params;
+                let doc = s.documents.get(&pos.text_document.uri)?;
+                hover::hover(doc, pos.position, s.parser.converter())
+            }),
+            request::GotoDefinition::METHOD => self.on::<request::GotoDefinition>(req, |s, p| {
+                let pos = p.text_document_position_params;
+                let doc = s.documents.get(&pos.text_document.uri)?;
+                definition::definition(doc, &pos.text_document.uri, pos.position)
+            }),
+            request::Completion::METHOD => self.on::<request::Completion>(req, |s, p| {
+                let pos = p.text_document_position;
+                let doc = s.documents.get(&pos.text_document.uri)?;
+                completion::completion(doc, pos.position, s.parser.converter())
+            }),
+            request::DocumentSymbolRequest::METHOD => {
+                self.on::<request::DocumentSymbolRequest>(req, |s, p| {
+                    let doc = s.documents.get(&p.text_document.uri)?;
+                    symbols::document_symbols(doc)
+                })
+            }
+            request::CodeActionRequest::METHOD => {
+                self.on::<request::CodeActionRequest>(req, |s, p| {
+                    let doc = s.documents.get(&p.text_document.uri)?;
+                    code_actions::code_actions(doc, &p.text_document.uri, p.range)
+                })
+            }
+            request::SemanticTokensFullRequest::METHOD => self
+                .on::<request::SemanticTokensFullRequest>(req, |s, p| {
+                    let doc = s.documents.get(&p.text_document.uri)?;
+                    let tokens = semantic_tokens::semantic_tokens(doc, s.parser.extensions());
+                    Some(SemanticTokensResult::Tokens(tokens))
+                }),
+            _ => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("Unsupported request: {}", req.method),
+                )
+            }
+        };
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err(err) => Response::new_err(id, ErrorCode::InvalidParams as i32, err.to_string()),
+        }
+    }
+
+    fn on<R>(
+        &mut self,
+        req: Request,
+        f: impl FnOnce(&mut Self, R::Params) -> R::Result,
+    ) -> Result<serde_json::Value, serde_json::Error>
+    where
+        R: request::Request,
+        R::Params: DeserializeOwned,
+        R::Result: Serialize,
+    {
+        let params = serde_json::from_value(req.params)?;
+        serde_json::to_value(f(self, params))
+    }
+
+    /// Returns the diagnostics to publish, if any
+    fn handle_notification(&mut self, not: Notification) -> Option<serde_json::Value> {
+        let uri = match not.method.as_str() {
+            notification::DidOpenTextDocument::METHOD => {
+                let params = cast::<notification::DidOpenTextDocument>(not)?;
+                let doc = Document::new(params.text_document.text, &self.parser);
+                self.documents.insert(params.text_document.uri.clone(), doc);
+                params.text_document.uri
+            }
+            notification::DidChangeTextDocument::METHOD => {
+                let params = cast::<notification::DidChangeTextDocument>(not)?;
+                let uri = params.text_document.uri;
+                let doc = self.documents.get_mut(&uri)?;
+                doc.update(params.content_changes, &self.parser);
+                uri
+            }
+            notification::DidCloseTextDocument::METHOD => {
+                let params = cast::<notification::DidCloseTextDocument>(not)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                let params = PublishDiagnosticsParams::new(uri, Vec::new(), None);
+                return serde_json::to_value(params).ok();
+            }
+            _ => return None,
+        };
+        let doc = &self.documents[&uri];
+        let diagnostics = diagnostics::diagnostics(doc, &uri);
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        serde_json::to_value(params).ok()
+    }
+}
+
+fn cast<N>(not: Notification) -> Option<N::Params>
+where
+    N: notification::Notification,
+    N::Params: DeserializeOwned,
+{
+    serde_json::from_value(not.params).ok()
+}
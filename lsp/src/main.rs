@@ -0,0 +1,9 @@
+use lsp_server::Connection;
+
+fn main() -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let (connection, io_threads) = Connection::stdio();
+    cooklang_lsp::run(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Semantic tokens from the highlighting of the core crate
+
+use lsp_types::{SemanticToken, SemanticTokenType, SemanticTokens, SemanticTokensLegend};
+
+use cooklang::{
+    highlight::{highlight, HighlightKind},
+    Extensions,
+};
+
+use crate::document::Document;
+
+const TOKEN_TYPES: [SemanticTokenType; 11] = [
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::EVENT,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::STRING,
+    SemanticTokenType::MODIFIER,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::COMMENT,
+];
+
+pub(crate) fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: Vec::new(),
+    }
+}
+
+fn token_type(kind: HighlightKind) -> Option<u32> {
+    let ty = match kind {
+        HighlightKind::Ingredient => SemanticTokenType::VARIABLE,
+        HighlightKind::Cookware => SemanticTokenType::CLASS,
+        HighlightKind::Timer => SemanticTokenType::EVENT,
+        HighlightKind::Quantity => SemanticTokenType::NUMBER,
+        HighlightKind::Unit => SemanticTokenType::TYPE,
+        HighlightKind::Note | HighlightKind::MetadataValue | HighlightKind::Frontmatter => {
+            SemanticTokenType::STRING
+        }
+        HighlightKind::Modifier => SemanticTokenType::MODIFIER,
+        HighlightKind::Marker => SemanticTokenType::OPERATOR,
+        HighlightKind::MetadataKey => SemanticTokenType::PROPERTY,
+        HighlightKind::Section => SemanticTokenType::NAMESPACE,
+        HighlightKind::Comment => SemanticTokenType::COMMENT,
+        _ => return None,
+    };
+    TOKEN_TYPES.iter().position(|t| *t == ty).map(|i| i as u32)
+}
+
+pub(crate) fn semantic_tokens(doc: &Document, extensions: Extensions) -> SemanticTokens {
+    let text = doc.text();
+    let mut data = Vec::new();
+    let (mut prev_line, mut prev_start) = (0, 0);
+
+    for hl in highlight(text, extensions) {
+        let Some(token_type) = token_type(hl.kind) else {
+            continue;
+        };
+        // tokens can't span multiple lines, so split comments and frontmatter
+        let mut offset = hl.span.start();
+        for line in text[hl.span.range()].split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                continue;
+            }
+            let pos = doc.position(start);
+            let delta_line = pos.line - prev_line;
+            let delta_start = if delta_line == 0 {
+                pos.character - prev_start
+            } else {
+                pos.character
+            };
+            data.push(SemanticToken {
+                delta_line,
+                delta_start,
+                length: line.encode_utf16().count() as u32,
+                token_type,
+                token_modifiers_bitset: 0,
+            });
+            prev_line = pos.line;
+            prev_start = pos.character;
+        }
+    }
+
+    SemanticTokens {
+        result_id: None,
+        data,
+    }
+}
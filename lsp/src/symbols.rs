@@ -0,0 +1,85 @@
+//! Document symbols for sections and steps
+
+use lsp_types::{DocumentSymbol, DocumentSymbolResponse, SymbolKind};
+
+use cooklang::{Content, Span};
+
+use crate::document::Document;
+
+pub(crate) fn document_symbols(doc: &Document) -> Option<DocumentSymbolResponse> {
+    let parsed = doc.current()?;
+    let recipe = &parsed.recipe;
+
+    let mut symbols = Vec::new();
+    for (index, (section, spans)) in recipe
+        .sections
+        .iter()
+        .zip(&parsed.source_map.sections)
+        .enumerate()
+    {
+        let steps = section
+            .content
+            .iter()
+            .zip(&spans.content)
+            .filter_map(|(content, &span)| match content {
+                Content::Step(step) => Some(symbol(
+                    doc,
+                    format!("Step {}", step.number),
+                    SymbolKind::FUNCTION,
+                    span,
+                    span,
+                    None,
+                )),
+                Content::Text(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        // unnamed sections are only useful if there are more
+        if section.name.is_none() && recipe.sections.len() == 1 {
+            symbols.extend(steps);
+            continue;
+        }
+
+        let name = section
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Section {}", index + 1));
+        let start = spans.name.or(spans.content.first().copied());
+        let end = spans.content.last().copied().or(spans.name);
+        let (Some(start), Some(end)) = (start, end) else {
+            continue;
+        };
+        let range = Span::from(start.start()..end.end());
+        let selection = spans.name.unwrap_or(range);
+        symbols.push(symbol(
+            doc,
+            name,
+            SymbolKind::NAMESPACE,
+            range,
+            selection,
+            Some(steps),
+        ));
+    }
+    Some(DocumentSymbolResponse::Nested(symbols))
+}
+
+fn symbol(
+    doc: &Document,
+    name: String,
+    kind: SymbolKind,
+    range: Span,
+    selection: Span,
+    children: Option<Vec<DocumentSymbol>>,
+) -> DocumentSymbol {
+    #[allow(deprecated)] // the deprecated field has to be initialized
+    DocumentSymbol {
+        name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: doc.range(range),
+        selection_range: doc.range(selection),
+        children,
+    }
+}
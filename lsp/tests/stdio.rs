@@ -0,0 +1,361 @@
+//! Runs the server binary and talks to it over stdio
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use indoc::indoc;
+use serde_json::{json, Value};
+
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i64,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cooklang-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("start server");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut client = Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 1,
+        };
+        let res = client.request("initialize", json!({ "capabilities": {} }));
+        assert!(res["capabilities"]["hoverProvider"].as_bool().unwrap());
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn send(&mut self, msg: Value) {
+        let body = msg.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn recv(&mut self) -> Value {
+        let mut len = None;
+        loop {
+            let mut line = String::new();
+            self.stdout.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(l) = line.strip_prefix("Content-Length: ") {
+                len = Some(l.parse::<usize>().unwrap());
+            }
+        }
+        let mut buf = vec![0; len.expect("missing content length")];
+        self.stdout.read_exact(&mut buf).unwrap();
+        serde_json::from_slice(&buf).unwrap()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        loop {
+            let msg = self.recv();
+            if msg["id"] == id {
+                assert!(msg.get("error").is_none(), "error response: {msg}");
+                return msg["result"].clone();
+            }
+        }
+    }
+
+    fn expect_notification(&mut self, method: &str) -> Value {
+        loop {
+            let msg = self.recv();
+            if msg["method"] == method {
+                return msg["params"].clone();
+            }
+        }
+    }
+
+    fn open(&mut self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": URI, "languageId": "cooklang", "version": 1, "text": text }
+            }),
+        );
+        self.expect_notification("textDocument/publishDiagnostics")
+    }
+
+    fn at(&mut self, method: &str, line: u32, character: u32) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character }
+            }),
+        )
+    }
+
+    fn stop(mut self) {
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+const URI: &str = "file:///recipes/bread.cook";
+
+const RECIPE: &str = indoc! {"
+    = Dough
+    Mix @flour{500%g} and @water{300%ml} in a #bowl.
+
+    Knead the @&(~1)dough{}.
+
+    = Bake
+    Bake the @&(=1)dough{} with more @&flour{20%g}.
+"};
+
+#[test]
+fn diagnostics() {
+    let mut client = Client::start();
+    let params = client.open("Add @flour{100%g} and @&flour{1%l}.");
+    assert_eq!(params["uri"], URI);
+    let diags = params["diagnostics"].as_array().unwrap();
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0]["severity"], 2);
+    assert_eq!(diags[0]["code"], "incompatible-units");
+    assert_eq!(diags[0]["range"]["start"]["line"], 0);
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "Add @flour{100%g}." }]
+        }),
+    );
+    let params = client.expect_notification("textDocument/publishDiagnostics");
+    assert!(params["diagnostics"].as_array().unwrap().is_empty());
+    client.stop();
+}
+
+#[test]
+fn incremental_changes() {
+    let mut client = Client::start();
+    client.open("Add @flour{100%g}.\n\nBake.");
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [
+                {
+                    "range": { "start": { "line": 2, "character": 0 }, "end": { "line": 2, "character": 4 } },
+                    "text": "Add @&flour{1%l} and"
+                },
+                {
+                    "range": { "start": { "line": 2, "character": 20 }, "end": { "line": 2, "character": 20 } },
+                    "text": " bake"
+                }
+            ]
+        }),
+    );
+    let params = client.expect_notification("textDocument/publishDiagnostics");
+    let diags = params["diagnostics"].as_array().unwrap();
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0]["range"]["start"]["line"], 2);
+
+    let hover = client.at("textDocument/hover", 2, 6);
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(text.contains("defined in line 1"), "{text}");
+    client.stop();
+}
+
+#[test]
+fn invalid_change_range() {
+    let mut client = Client::start();
+    client.open("Add @flour{100%g}.");
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [
+                {
+                    "range": { "start": { "line": 0, "character": 8 }, "end": { "line": 0, "character": 2 } },
+                    "text": "x"
+                },
+                {
+                    "range": { "start": { "line": 0, "character": 17 }, "end": { "line": 0, "character": 17 } },
+                    "text": " and @&flour{1%l}"
+                }
+            ]
+        }),
+    );
+    let params = client.expect_notification("textDocument/publishDiagnostics");
+    let diags = params["diagnostics"].as_array().unwrap();
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0]["code"], "incompatible-units");
+    client.stop();
+}
+
+#[test]
+fn hover() {
+    let mut client = Client::start();
+    client.open(RECIPE);
+
+    let hover = client.at("textDocument/hover", 1, 6);
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(text.contains("**flour**"), "{text}");
+    assert!(text.contains("Unit: gram"), "{text}");
+    assert!(text.contains("mass, metric"), "{text}");
+    assert!(text.contains("Referenced 1 time"), "{text}");
+
+    let hover = client.at("textDocument/hover", 3, 12);
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(text.contains("Output of step 1"), "{text}");
+
+    let hover = client.at("textDocument/hover", 0, 2);
+    assert!(hover.is_null());
+    client.stop();
+}
+
+#[test]
+fn definition() {
+    let mut client = Client::start();
+    client.open(RECIPE);
+
+    // intermediate preparation from a step
+    let def = client.at("textDocument/definition", 3, 12);
+    assert_eq!(def["uri"], URI);
+    assert_eq!(def["range"]["start"], json!({ "line": 1, "character": 0 }));
+
+    // intermediate preparation from a section
+    let def = client.at("textDocument/definition", 6, 10);
+    assert_eq!(def["range"]["start"], json!({ "line": 0, "character": 1 }));
+
+    // regular reference
+    let def = client.at("textDocument/definition", 6, 35);
+    assert_eq!(def["range"]["start"], json!({ "line": 1, "character": 4 }));
+    assert_eq!(def["range"]["end"], json!({ "line": 1, "character": 17 }));
+
+    // a definition has no definition
+    let def = client.at("textDocument/definition", 1, 6);
+    assert!(def.is_null());
+    client.stop();
+}
+
+#[test]
+fn completion() {
+    let mut client = Client::start();
+    client.open("Add @flour{100%g} and @water{}. Use a #big bowl{}.\nAdd @");
+
+    let items = client.at("textDocument/completion", 1, 5);
+    let labels = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["label"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["flour", "water"]);
+
+    let items = client.at("textDocument/completion", 0, 15);
+    let labels = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["label"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert!(labels.contains(&"g"));
+    assert!(labels.contains(&"tbsp"));
+    client.stop();
+}
+
+#[test]
+fn document_symbols() {
+    let mut client = Client::start();
+    client.open(RECIPE);
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let symbols = symbols.as_array().unwrap();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0]["name"], "Dough");
+    assert_eq!(symbols[0]["children"].as_array().unwrap().len(), 2);
+    assert_eq!(symbols[1]["name"], "Bake");
+    assert_eq!(symbols[1]["children"][0]["name"], "Step 1");
+    client.stop();
+}
+
+#[test]
+fn code_actions() {
+    let mut client = Client::start();
+    client.open("Wait ~{10minutes}.");
+
+    let range =
+        json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 18 } });
+    let actions = client.request(
+        "textDocument/codeAction",
+        json!({
+            "textDocument": { "uri": URI },
+            "range": range,
+            "context": { "diagnostics": [] }
+        }),
+    );
+    let actions = actions.as_array().unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0]["kind"], "quickfix");
+    let edits = &actions[0]["edit"]["changes"][URI];
+    assert_eq!(edits[0]["newText"], "%");
+    assert_eq!(
+        edits[0]["range"]["start"],
+        json!({ "line": 0, "character": 9 })
+    );
+    client.stop();
+}
+
+#[test]
+fn semantic_tokens() {
+    let mut client = Client::start();
+    client.open("[- a\ncomment -]\nAdd @flour{100%g}.");
+
+    let tokens = client.request(
+        "textDocument/semanticTokens/full",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let data = tokens["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n.as_u64().unwrap())
+        .collect::<Vec<_>>();
+    // (delta line, delta start, length, type)
+    let tokens = data
+        .chunks(5)
+        .map(|t| (t[0], t[1], t[2], t[3]))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        [
+            (0, 0, 4, 10),  // comment
+            (1, 0, 10, 10), // comment
+            (1, 4, 1, 7),   // @
+            (0, 1, 5, 0),   // flour
+            (0, 5, 1, 7),   // {
+            (0, 1, 3, 3),   // 100
+            (0, 3, 1, 7),   // %
+            (0, 1, 1, 4),   // g
+            (0, 1, 1, 7),   // }
+        ]
+    );
+    client.stop();
+}
@@ -138,7 +138,9 @@ async function run(): Promise<void> {
     ["INLINE_QUANTITIES", 1 << 7],
     ["RANGE_VALUES", 1 << 9],
     ["TIMER_REQUIRES_TIME", 1 << 10],
-    ["INTERMEDIATE_PREPARATIONS", 1 << 11 | 1 << 1]
+    ["INTERMEDIATE_PREPARATIONS", 1 << 11 | 1 << 1],
+    ["OPTIONAL_SECTIONS", 1 << 12],
+    ["TEMPERATURES", 1 << 13]
   ];
 
   extensions.forEach(([e, bits]) => {
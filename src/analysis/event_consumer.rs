@@ -2,26 +2,36 @@ use std::collections::HashMap;
 use std::str::FromStr;
 
 use crate::convert::{Converter, PhysicalQuantity};
-use crate::error::{label, CowStr, PassResult, SourceDiag, SourceReport};
+use crate::error::{
+    label, CowStr, DiagCode, LintLevel, Lints, PassResult, SourceDiag, SourceReport, Suggestion,
+};
 use crate::located::Located;
-use crate::metadata::{check_std_entry, StdKey};
+use crate::metadata::{check_std_entry, MetadataError, StdKey, Vocabulary};
 use crate::parser::{
     self, BlockKind, Event, IntermediateData, IntermediateRefMode, IntermediateTargetKind,
     Modifiers,
 };
 use crate::quantity::{Quantity, QuantityValue, ScalableValue, Value};
 use crate::span::Span;
-use crate::text::Text;
+use crate::temperature::{OvenMode, Temperature, TemperatureScale, GAS_MARK_UNIT};
+use crate::text::{Text, TextFragmentKind};
+use crate::yaml_location;
 use crate::{model::*, Extensions, ParseOptions};
 
-use super::{AnalysisResult, CheckOptions, DefineMode, DuplicateMode};
+use super::{AnalysisResult, CheckOptions, DefineMode, DuplicateMode, SectionSpans, SourceMap};
 
 macro_rules! error {
-    ($msg:expr, $label:expr $(,)?) => {
-        $crate::error::SourceDiag::error($msg, $label, $crate::error::Stage::Analysis)
+    ($code:ident, $msg:expr, $label:expr $(,)?) => {
+        $crate::error::SourceDiag::error(
+            $crate::error::DiagCode::$code,
+            $msg,
+            $label,
+            $crate::error::Stage::Analysis,
+        )
     };
-    ($msg:expr) => {
+    ($code:ident, $msg:expr) => {
         $crate::error::SourceDiag::unlabeled(
+            $crate::error::DiagCode::$code,
             $msg,
             $crate::error::Severity::Error,
             $crate::error::Stage::Analysis,
@@ -30,11 +40,17 @@ macro_rules! error {
 }
 
 macro_rules! warning {
-    ($msg:expr, $label:expr $(,)?) => {
-        $crate::error::SourceDiag::warning($msg, $label, $crate::error::Stage::Analysis)
+    ($code:ident, $msg:expr, $label:expr $(,)?) => {
+        $crate::error::SourceDiag::warning(
+            $crate::error::DiagCode::$code,
+            $msg,
+            $label,
+            $crate::error::Stage::Analysis,
+        )
     };
-    ($msg:expr) => {
+    ($code:ident, $msg:expr) => {
         $crate::error::SourceDiag::unlabeled(
+            $crate::error::DiagCode::$code,
             $msg,
             $crate::error::Severity::Warning,
             $crate::error::Stage::Analysis,
@@ -55,36 +71,14 @@ pub fn parse_events<'i, 'c>(
     converter: &Converter,
     parse_options: ParseOptions,
 ) -> AnalysisResult {
-    let col = RecipeCollector {
-        input,
-        extensions,
-        converter,
-        parse_options,
-
-        content: ScalableRecipe {
-            metadata: Default::default(),
-            sections: Default::default(),
-            ingredients: Default::default(),
-            cookware: Default::default(),
-            timers: Default::default(),
-            inline_quantities: Default::default(),
-            data: crate::scale::Servings(None),
-        },
-        current_section: Section::default(),
-
-        define_mode: DefineMode::All,
-        duplicate_mode: DuplicateMode::New,
-        old_style_metadata: true,
-        old_style_metadata_used: vec![],
-        ctx: SourceReport::empty(),
-
-        locations: Default::default(),
-        step_counter: 1,
-    };
-    col.parse_events(events)
+    let mut col = RecipeCollector::new(input, extensions, converter, parse_options);
+    for event in events {
+        col.event(event);
+    }
+    col.finish()
 }
 
-struct RecipeCollector<'i, 'c> {
+pub(crate) struct RecipeCollector<'i, 'c> {
     input: &'i str,
     extensions: Extensions,
     converter: &'c Converter,
@@ -92,123 +86,324 @@ struct RecipeCollector<'i, 'c> {
 
     content: ScalableRecipe,
     current_section: Section,
+    current_section_spans: SectionSpans,
+    source_map: SourceMap,
+    /// Location of the metadata keys, in the order they are found
+    metadata_spans: Vec<(serde_yaml::Value, Span)>,
 
     define_mode: DefineMode,
     duplicate_mode: DuplicateMode,
     old_style_metadata: bool,
     old_style_metadata_used: Vec<Span>,
+    /// Lint levels from config keys
+    lints: Lints,
     ctx: SourceReport,
+    /// A parser error was found, only parser diagnostics are collected
+    failed: bool,
 
-    locations: Locations<'i>,
+    current_block: Option<BlockBuffer>,
+    current_block_span: Option<Span>,
+    locations: Locations,
     step_counter: u32,
 }
 
-#[derive(Default)]
-struct Locations<'i> {
-    ingredients: Vec<Located<parser::Ingredient<'i>>>,
-    cookware: Vec<Located<parser::Cookware<'i>>>,
-    metadata: HashMap<StdKey, (Text<'i>, Text<'i>)>,
+/// State of a [`RecipeCollector`] between two blocks
+///
+/// It does not borrow the input, so it can be kept while the input changes.
+#[derive(Debug, Clone)]
+pub(crate) struct Checkpoint {
+    content: ScalableRecipe,
+    current_section: Section,
+    current_section_spans: SectionSpans,
+    source_map: SourceMap,
+    metadata_spans: Vec<(serde_yaml::Value, Span)>,
+    define_mode: DefineMode,
+    duplicate_mode: DuplicateMode,
+    old_style_metadata: bool,
+    old_style_metadata_used: Vec<Span>,
+    lints: Lints,
+    ctx: SourceReport,
+    failed: bool,
+    locations: Locations,
+    step_counter: u32,
+}
+
+enum BlockBuffer {
+    Step(Vec<Item>),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Default)]
+struct Locations {
+    ingredients: Vec<ComponentLocation>,
+    cookware: Vec<ComponentLocation>,
+    /// From the start of the key to the end of the value
+    metadata: HashMap<StdKey, Span>,
+}
+
+/// Location of a component and its parts
+#[derive(Debug, Clone)]
+struct ComponentLocation {
+    span: Span,
+    quantity: Option<Span>,
+    /// Unit of the quantity
+    unit: Option<Span>,
+    note: Option<Span>,
 }
 
 const IMPLICIT_REF_WARN: &str = "The reference (&) is implicit";
 
+impl<'i, 'c> RecipeCollector<'i, 'c> {
+    pub(crate) fn new(
+        input: &'i str,
+        extensions: Extensions,
+        converter: &'c Converter,
+        parse_options: ParseOptions<'c>,
+    ) -> Self {
+        Self::from_checkpoint(
+            input,
+            extensions,
+            converter,
+            parse_options,
+            Checkpoint {
+                content: ScalableRecipe {
+                    metadata: Default::default(),
+                    sections: Default::default(),
+                    ingredients: Default::default(),
+                    cookware: Default::default(),
+                    timers: Default::default(),
+                    inline_quantities: Default::default(),
+                    temperatures: Default::default(),
+                    data: crate::scale::Servings(None),
+                },
+                current_section: Section::default(),
+                current_section_spans: SectionSpans::default(),
+                source_map: SourceMap::default(),
+                metadata_spans: Vec::new(),
+
+                define_mode: DefineMode::All,
+                duplicate_mode: DuplicateMode::New,
+                old_style_metadata: true,
+                old_style_metadata_used: vec![],
+                lints: Lints::new(),
+                ctx: SourceReport::empty(),
+                failed: false,
+
+                locations: Default::default(),
+                step_counter: 1,
+            },
+        )
+    }
+
+    /// Continues the analysis from a [`Checkpoint`]
+    ///
+    /// The input before the checkpoint, the extensions, converter and options
+    /// must be the same as when it was created.
+    pub(crate) fn from_checkpoint(
+        input: &'i str,
+        extensions: Extensions,
+        converter: &'c Converter,
+        parse_options: ParseOptions<'c>,
+        checkpoint: Checkpoint,
+    ) -> Self {
+        Self {
+            input,
+            extensions,
+            converter,
+            parse_options,
+
+            content: checkpoint.content,
+            current_section: checkpoint.current_section,
+            current_section_spans: checkpoint.current_section_spans,
+            source_map: checkpoint.source_map,
+            metadata_spans: checkpoint.metadata_spans,
+
+            define_mode: checkpoint.define_mode,
+            duplicate_mode: checkpoint.duplicate_mode,
+            old_style_metadata: checkpoint.old_style_metadata,
+            old_style_metadata_used: checkpoint.old_style_metadata_used,
+            lints: checkpoint.lints,
+            ctx: checkpoint.ctx,
+            failed: checkpoint.failed,
+
+            current_block: None,
+            current_block_span: None,
+            locations: checkpoint.locations,
+            step_counter: checkpoint.step_counter,
+        }
+    }
+
+    /// Current state, only valid between blocks
+    pub(crate) fn checkpoint(&self) -> Checkpoint {
+        debug_assert!(self.current_block.is_none(), "checkpoint inside a block");
+        Checkpoint {
+            content: self.content.clone(),
+            current_section: self.current_section.clone(),
+            current_section_spans: self.current_section_spans.clone(),
+            source_map: self.source_map.clone(),
+            metadata_spans: self.metadata_spans.clone(),
+            define_mode: self.define_mode,
+            duplicate_mode: self.duplicate_mode,
+            old_style_metadata: self.old_style_metadata,
+            old_style_metadata_used: self.old_style_metadata_used.clone(),
+            lints: self.lints.clone(),
+            ctx: self.ctx.clone(),
+            failed: self.failed,
+            locations: self.locations.clone(),
+            step_counter: self.step_counter,
+        }
+    }
+}
+
 impl<'i> RecipeCollector<'i, '_> {
-    fn parse_events(mut self, mut events: impl Iterator<Item = Event<'i>>) -> AnalysisResult {
-        enum BlockBuffer {
-            Step(Vec<Item>),
-            Text(String),
+    pub(crate) fn event(&mut self, event: Event<'i>) {
+        if self.failed {
+            // after a parser error, only collect other parser errors and
+            // warnings
+            if let Event::Error(e) | Event::Warning(e) = event {
+                self.ctx.push(e);
+            }
+            return;
         }
-        let mut current_block = None;
-
-        let events = events.by_ref();
-        while let Some(event) = events.next() {
-            match event {
-                Event::YAMLFrontMatter(yaml_text) => {
-                    self.old_style_metadata = true;
-                    self.process_frontmatter(yaml_text);
-                }
-                Event::Metadata { key, value } => self.metadata(key, value),
-                Event::Section { name } => {
-                    self.step_counter = 1;
-                    if !self.current_section.is_empty() {
-                        self.content.sections.push(self.current_section);
+        match event {
+            Event::YAMLFrontMatter(yaml_text) => {
+                self.old_style_metadata = true;
+                self.process_frontmatter(yaml_text);
+            }
+            Event::Metadata { key, value } => self.metadata(key, value),
+            Event::Section { name, optional } => {
+                self.step_counter = 1;
+                self.push_current_section();
+                self.current_section_spans = SectionSpans {
+                    name: name.as_ref().map(|t| t.span()),
+                    content: Vec::new(),
+                };
+                self.current_section =
+                    Section::new(name.map(|t| t.text_trimmed().into_owned()), optional);
+            }
+            Event::Start(kind) => {
+                let buffer = if self.define_mode == DefineMode::Text {
+                    BlockBuffer::Text(String::new())
+                } else {
+                    match kind {
+                        BlockKind::Step => BlockBuffer::Step(Vec::new()),
+                        BlockKind::Text => BlockBuffer::Text(String::new()),
                     }
-                    self.current_section =
-                        Section::new(name.map(|t| t.text_trimmed().into_owned()));
-                }
-                Event::Start(kind) => {
-                    let buffer = if self.define_mode == DefineMode::Text {
-                        BlockBuffer::Text(String::new())
-                    } else {
-                        match kind {
-                            BlockKind::Step => BlockBuffer::Step(Vec::new()),
-                            BlockKind::Text => BlockBuffer::Text(String::new()),
-                        }
-                    };
-                    current_block = Some(buffer)
-                }
-                Event::End(kind) => {
-                    let new_content = match current_block {
-                        Some(BlockBuffer::Step(items)) => {
-                            assert_eq!(kind, BlockKind::Step);
-                            Content::Step(Step {
-                                items,
-                                number: self.step_counter,
-                            })
-                        }
-                        Some(BlockBuffer::Text(text)) => {
-                            assert!(
-                                kind == BlockKind::Text || self.define_mode == DefineMode::Text,
-                            );
-                            Content::Text(text)
-                        }
-                        None => panic!("End event without Start"),
-                    };
-
-                    // If define mode is ingredients, don't add the
-                    // step to the section. The components should have been
-                    // added to their lists
-                    if self.define_mode != DefineMode::Components || new_content.is_text() {
-                        if new_content.is_step() {
-                            self.step_counter += 1;
-                        }
-                        self.current_section.content.push(new_content);
+                };
+                self.current_block = Some(buffer);
+                self.current_block_span = None;
+            }
+            Event::End(kind) => {
+                let new_content = match self.current_block.take() {
+                    Some(BlockBuffer::Step(items)) => {
+                        assert_eq!(kind, BlockKind::Step);
+                        Content::Step(Step {
+                            items,
+                            number: self.step_counter,
+                            images: Vec::new(),
+                        })
+                    }
+                    Some(BlockBuffer::Text(text)) => {
+                        assert!(kind == BlockKind::Text || self.define_mode == DefineMode::Text,);
+                        Content::Text(text)
                     }
+                    None => panic!("End event without Start"),
+                };
 
-                    current_block = None;
+                // If define mode is ingredients, don't add the
+                // step to the section. The components should have been
+                // added to their lists
+                if self.define_mode != DefineMode::Components || new_content.is_text() {
+                    if new_content.is_step() {
+                        self.step_counter += 1;
+                    }
+                    self.current_section.content.push(new_content);
+                    // blocks are never empty, but just in case
+                    let span = self.current_block_span.unwrap_or(Span::pos(0));
+                    self.current_section_spans.content.push(span);
                 }
-                item @ (Event::Text(_)
-                | Event::Ingredient(_)
-                | Event::Cookware(_)
-                | Event::Timer(_)) => match &mut current_block {
-                    Some(BlockBuffer::Step(items)) => self.in_step(item, items),
-                    Some(BlockBuffer::Text(text)) => self.in_text(item, text),
+            }
+            item @ (Event::Text(_)
+            | Event::Ingredient(_)
+            | Event::Cookware(_)
+            | Event::Timer(_)
+            | Event::Temperature(_)) => {
+                let span = match &item {
+                    Event::Text(t) => t.span(),
+                    Event::Ingredient(i) => i.span(),
+                    Event::Cookware(c) => c.span(),
+                    Event::Timer(t) => t.span(),
+                    Event::Temperature(t) => t.span(),
+                    _ => unreachable!(),
+                };
+                self.current_block_span = Some(match self.current_block_span {
+                    Some(s) => Span::new(s.start(), span.end()),
+                    None => span,
+                });
+                match self.current_block.take() {
+                    Some(BlockBuffer::Step(mut items)) => {
+                        self.in_step(item, &mut items);
+                        self.current_block = Some(BlockBuffer::Step(items));
+                    }
+                    Some(BlockBuffer::Text(mut text)) => {
+                        self.in_text(item, &mut text);
+                        self.current_block = Some(BlockBuffer::Text(text));
+                    }
                     None => panic!("Content outside block"),
-                },
-
-                Event::Error(e) => {
-                    // on a parser error, collect all other parser errors and
-                    // warnings
-                    self.ctx.error(e);
-                    events.for_each(|e| match e {
-                        Event::Error(e) | Event::Warning(e) => self.ctx.push(e),
-                        _ => {}
-                    });
-                    // discard non parser errors/warnings
-                    self.ctx.retain(|e| e.stage == crate::error::Stage::Parse);
-                    // return no output
-                    return PassResult::new(None, self.ctx);
                 }
-                Event::Warning(w) => self.ctx.warn(w),
             }
+
+            Event::Error(e) => {
+                self.ctx.error(e);
+                self.failed = true;
+                self.current_block = None;
+            }
+            Event::Warning(w) => self.ctx.warn(w),
         }
+    }
+
+    fn push_current_section(&mut self) {
         if !self.current_section.is_empty() {
-            self.content.sections.push(self.current_section);
+            self.content
+                .sections
+                .push(std::mem::take(&mut self.current_section));
+            self.source_map
+                .sections
+                .push(std::mem::take(&mut self.current_section_spans));
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> AnalysisResult {
+        if self.failed {
+            // discard non parser errors/warnings
+            self.ctx.retain(|e| e.stage == crate::error::Stage::Parse);
+            apply_lints(&mut self.ctx, &self.parse_options.lints, &self.lints);
+            // return no output
+            return PassResult::new(None, self.ctx);
+        }
+        self.push_current_section();
+        if let Some(source_map) = self.parse_options.source_map.as_deref_mut() {
+            // the last entry of a key is the one in the map
+            self.source_map.metadata = self
+                .content
+                .metadata
+                .map
+                .keys()
+                .map(|key| {
+                    self.metadata_spans
+                        .iter()
+                        .rfind(|(k, _)| k == key)
+                        .map(|(_, span)| *span)
+                })
+                .collect();
+            *source_map = self.source_map;
         }
 
         if !self.old_style_metadata_used.is_empty() {
-            let mut diag =
-                warning!("The '>>' syntax for metadata is deprecated, use a YAML frontmatter");
+            let mut diag = warning!(
+                DeprecatedMetadataSyntax,
+                "The '>>' syntax for metadata is deprecated, use a YAML frontmatter"
+            );
             for span in self.old_style_metadata_used {
                 diag.add_label(label!(span));
             }
@@ -218,6 +413,7 @@ impl<'i> RecipeCollector<'i, '_> {
             self.ctx.warn(diag);
         }
 
+        apply_lints(&mut self.ctx, &self.parse_options.lints, &self.lints);
         PassResult::new(Some(self.content), self.ctx)
     }
 
@@ -229,7 +425,7 @@ impl<'i> RecipeCollector<'i, '_> {
             Err(err) => {
                 // ! This message (can) contains line and column number, but line numbers
                 // ! are off by one thanks to the starting `---`
-                let mut diag = error!(err.to_string());
+                let mut diag = error!(InvalidFrontmatter, err.to_string());
                 let err_span = err
                     .location()
                     .map(|loc| Span::pos(yaml_text.span().start() + loc.index()));
@@ -247,7 +443,9 @@ impl<'i> RecipeCollector<'i, '_> {
             // run custom validator if any
             if let Some(validator) = self.parse_options.metadata_validator.as_mut() {
                 let res = validator(key, value, &mut action);
-                if let Some(mut diag) = res.into_source_diag(|| "Invalid metadata entry") {
+                if let Some(mut diag) =
+                    res.into_source_diag(DiagCode::MetadataCheck, || "Invalid metadata entry")
+                {
                     if let Some(key_s) = key.as_str() {
                         if let Some(pos) = yaml_find_key_position(&yaml_str, key_s) {
                             diag.add_label(label!(Span::pos(yaml_text.span().start() + pos)));
@@ -265,14 +463,29 @@ impl<'i> RecipeCollector<'i, '_> {
                 continue;
             }
             if let Some(sk) = key.as_str().and_then(|s| StdKey::from_str(s).ok()) {
-                match check_std_entry(sk, value, self.converter) {
+                match check_std_entry(sk, value, self.converter, self.parse_options.vocabulary) {
                     Ok(Some(servings)) => self.content.data = servings,
                     Ok(None) => {}
+                    Err(MetadataError::NotInVocabulary { key: sk, values }) => {
+                        let key_s = key.as_str().unwrap();
+                        let mut diag =
+                            not_in_vocabulary(key_s, sk, &values, self.parse_options.vocabulary);
+                        let path = [yaml_location::PathSegment::Key(key_s.to_string())];
+                        let location = yaml_location::locate(&yaml_str, &path);
+                        if let Some(r) = location.value {
+                            let start = yaml_text.span().start();
+                            diag.add_label(label!(
+                                Span::new(start + r.start, start + r.end),
+                                "this value"
+                            ));
+                        }
+                        self.ctx.warn(diag);
+                    }
                     Err(err) => {
-                        let mut diag = warning!(format!(
-                            "Unsupported value for key: '{}'",
-                            key.as_str().unwrap()
-                        ))
+                        let mut diag = warning!(
+                            UnsupportedMetadataValue,
+                            format!("Unsupported value for key: '{}'", key.as_str().unwrap())
+                        )
                         .set_source(err);
                         if let Some(key_s) = key.as_str() {
                             if let Some(pos) = yaml_find_key_position(&yaml_str, key_s) {
@@ -288,6 +501,31 @@ impl<'i> RecipeCollector<'i, '_> {
             yaml_map.shift_remove(key);
         }
 
+        #[cfg(feature = "schema")]
+        if let Some(schema) = self.parse_options.metadata_schema {
+            let offset = yaml_text.span().start();
+            let span = |r: std::ops::Range<usize>| Span::new(offset + r.start, offset + r.end);
+            for violation in schema.validate(&yaml_map) {
+                let location = yaml_location::locate(&yaml_str, &violation.path);
+                let label = if violation.path.is_empty() {
+                    location.value.map(|r| label!(span(r)))
+                } else if violation.is_key {
+                    location.key.map(|r| label!(span(r), "this key"))
+                } else {
+                    location
+                        .value
+                        .map(|r| label!(span(r), "this value"))
+                        .or_else(|| location.key.map(|r| label!(span(r), "this key")))
+                };
+                let message = format!("Invalid metadata: {violation}");
+                let diag = match label {
+                    Some(label) => warning!(MetadataSchema, message, label),
+                    None => warning!(MetadataSchema, message),
+                };
+                self.ctx.warn(diag);
+            }
+        }
+
         if yaml_map.contains_key(StdKey::Time.as_ref()) {
             // ? I guess I could group calls to `yaml_find_key_pos` into a single
             // iteration of yaml_str but doesn't really matter
@@ -306,7 +544,7 @@ impl<'i> RecipeCollector<'i, '_> {
             const OVERRIDES: &str = "this entry has preference";
 
             if prep.is_some() || cook.is_some() {
-                let mut w = warning!("Time overriden");
+                let mut w = warning!(TimeOverridden, "Time overriden");
                 if let Some(p) = prep {
                     w.add_label(label!(Span::pos(yaml_text.span().start() + p), OVERRIDEN));
                 }
@@ -323,6 +561,15 @@ impl<'i> RecipeCollector<'i, '_> {
             }
         }
 
+        for key in yaml_map.keys() {
+            if let Some(key_s) = key.as_str() {
+                if let Some(pos) = yaml_find_key_position(&yaml_str, key_s) {
+                    let start = yaml_text.span().start() + pos;
+                    self.metadata_spans
+                        .push((key.clone(), Span::new(start, start + key_s.len())));
+                }
+            }
+        }
         self.content.metadata.map = yaml_map;
     }
 
@@ -331,6 +578,7 @@ impl<'i> RecipeCollector<'i, '_> {
         let value_t = value.text_outer_trimmed();
         let invalid_value = |possible| {
             error!(
+                InvalidConfigValue,
                 format!("Invalid value for config key '{key_t}': {value_t}"),
                 label!(value.span(), "this value")
             )
@@ -358,17 +606,26 @@ impl<'i> RecipeCollector<'i, '_> {
                     "reference" | "ref" => self.duplicate_mode = DuplicateMode::Reference,
                     _ => self.ctx.error(invalid_value(vec!["new", "reference"])),
                 },
+                "allow" | "warn" | "deny" => {
+                    let level = LintLevel::from_str(config_key).unwrap();
+                    self.lint_levels(level, value);
+                }
                 _ => {
                     self.ctx.warn(
                         warning!(
+                            UnknownConfigKey,
                             format!("Unknown config metadata key: {key_t}"),
                             label!(key.span())
                         )
                         .hint(
-                            "Possible config keys are '[mode]' and '[duplicate]''",
+                            "Possible config keys are '[mode]', '[duplicate]', '[allow]', '[warn]' and '[deny]'",
                         ),
                     );
                     if self.old_style_metadata {
+                        self.metadata_spans.push((
+                            serde_yaml::Value::String(key_t.to_string()),
+                            trimmed_span(&key),
+                        ));
                         self.content.metadata.map.insert(
                             serde_yaml::Value::String(key_t.into_owned()),
                             serde_yaml::Value::String(value_t.into_owned()),
@@ -389,7 +646,9 @@ impl<'i> RecipeCollector<'i, '_> {
         let mut action = CheckOptions::default();
         if let Some(validator) = self.parse_options.metadata_validator.as_mut() {
             let res = validator(&yaml_key, &yaml_value, &mut action);
-            if let Some(mut diag) = res.into_source_diag(|| "Invalid metadata entry") {
+            if let Some(mut diag) =
+                res.into_source_diag(DiagCode::MetadataCheck, || "Invalid metadata entry")
+            {
                 diag.add_label(label!(key.span()));
                 diag.add_label(label!(value.span()));
                 self.ctx.push(diag);
@@ -400,6 +659,8 @@ impl<'i> RecipeCollector<'i, '_> {
         }
 
         // insert the value into the map
+        self.metadata_spans
+            .push((yaml_key.clone(), trimmed_span(&key)));
         self.content.metadata.map.insert(yaml_key, yaml_value);
 
         // check if it's a std key
@@ -411,14 +672,25 @@ impl<'i> RecipeCollector<'i, '_> {
                 sp_key,
                 self.content.metadata.map.get(key_t.as_ref()).unwrap(),
                 self.converter,
+                self.parse_options.vocabulary,
             );
 
             match check_result {
                 Ok(Some(servings)) => self.content.data = servings,
                 Ok(None) => {}
+                Err(MetadataError::NotInVocabulary {
+                    key: sp_key,
+                    values,
+                }) => {
+                    let diag =
+                        not_in_vocabulary(&key_t, sp_key, &values, self.parse_options.vocabulary);
+                    self.ctx
+                        .warn(diag.label(label!(value.span(), "this value")));
+                }
                 Err(err) => {
                     self.ctx.warn(
                         warning!(
+                            UnsupportedMetadataValue,
                             format!("Unsupported value for key: '{}'", key.text_trimmed()),
                             label!(value.span(), "this value"),
                         )
@@ -433,7 +705,7 @@ impl<'i> RecipeCollector<'i, '_> {
             // store it's location if it was inserted
             self.locations
                 .metadata
-                .insert(sp_key, (key.clone(), value.clone()));
+                .insert(sp_key, Span::new(key.span().start(), value.span().end()));
 
             if matches!(sp_key, StdKey::Time | StdKey::PrepTime | StdKey::CookTime) {
                 self.time_override_check(sp_key)
@@ -441,17 +713,28 @@ impl<'i> RecipeCollector<'i, '_> {
         }
     }
 
+    /// Sets the level of a comma separated list of codes
+    fn lint_levels(&mut self, level: LintLevel, value: Text<'i>) {
+        let span = value.span();
+        for range in self.lints.set_list(level, &self.input[span.range()]) {
+            let code_span = Span::new(span.start() + range.start, span.start() + range.end);
+            self.ctx.warn(
+                warning!(
+                    UnknownLint,
+                    format!("Unknown lint code: {}", &self.input[code_span.range()]),
+                    label!(code_span, "this code does not exist")
+                )
+                .hint("Codes are in kebab case, like 'time-overridden', or 'warnings' for all"),
+            );
+        }
+    }
+
     fn time_override_check(&mut self, new: StdKey) {
         let locs = |keys: &[StdKey]| {
             assert!(!keys.is_empty());
             let mut v = keys
                 .iter()
-                .filter_map(|k| {
-                    self.locations
-                        .metadata
-                        .get(k)
-                        .map(|e| Span::new(e.0.span().start(), e.1.span().end()))
-                })
+                .filter_map(|k| self.locations.metadata.get(k).copied())
                 .collect::<Vec<_>>();
             v.sort_unstable();
             v
@@ -476,6 +759,7 @@ impl<'i> RecipeCollector<'i, '_> {
         const OVERRIDES: &str = "by this entry";
 
         let mut warn = warning!(
+            TimeOverridden,
             "Time overridden",
             label!(overriden.next().unwrap(), OVERRIDEN)
         );
@@ -497,6 +781,7 @@ impl<'i> RecipeCollector<'i, '_> {
                     // hypens or whatever.
                     if t.contains(|c: char| c.is_alphanumeric()) {
                         self.ctx.warn(warning!(
+                            IgnoredText,
                             "Ignoring text in define components mode",
                             label!(text.span())
                         ));
@@ -506,9 +791,17 @@ impl<'i> RecipeCollector<'i, '_> {
 
                 if self.extensions.contains(Extensions::INLINE_QUANTITIES) {
                     let mut haystack = t.as_ref();
+                    let mut offset = 0;
                     while let Some((before, temperature, after)) =
                         find_inline_quantity(haystack, self.converter)
                     {
+                        let start = offset + before.len();
+                        offset += haystack.len() - after.len();
+                        self.source_map.inline_quantities.push(Span::new(
+                            text_source_offset(&text, start),
+                            text_source_offset(&text, offset),
+                        ));
+
                         if !before.is_empty() {
                             items.push(Item::Text {
                                 value: before.to_string(),
@@ -543,6 +836,9 @@ impl<'i> RecipeCollector<'i, '_> {
             Event::Timer(i) => items.push(Item::Timer {
                 index: self.timer(i),
             }),
+            Event::Temperature(i) => items.push(Item::Temperature {
+                index: self.temperature(i),
+            }),
 
             _ => panic!("Unexpected event in step: {item:?}"),
         };
@@ -551,7 +847,7 @@ impl<'i> RecipeCollector<'i, '_> {
     fn in_text(&mut self, ev: Event<'i>, s: &mut String) {
         match ev {
             Event::Text(t) => s.push_str(t.text().as_ref()),
-            Event::Ingredient(_) | Event::Cookware(_) | Event::Timer(_) => {
+            Event::Ingredient(_) | Event::Cookware(_) | Event::Timer(_) | Event::Temperature(_) => {
                 assert_eq!(
                     self.define_mode,
                     DefineMode::Text,
@@ -563,10 +859,14 @@ impl<'i> RecipeCollector<'i, '_> {
                     Event::Ingredient(i) => ("ingredient", i.span()),
                     Event::Cookware(c) => ("cookware", c.span()),
                     Event::Timer(t) => ("timer", t.span()),
+                    Event::Temperature(t) => ("temperature", t.span()),
                     _ => unreachable!(),
                 };
-                self.ctx
-                    .warn(warning!(format!("Ignoring {c} in text mode"), label!(span)));
+                self.ctx.warn(warning!(
+                    IgnoredComponent,
+                    format!("Ignoring {c} in text mode"),
+                    label!(span)
+                ));
                 s.push_str(&self.input[span.range()]);
             }
             _ => panic!("Unexpected event in text block: {ev:?}"),
@@ -603,6 +903,7 @@ impl<'i> RecipeCollector<'i, '_> {
             if new_igr.modifiers().intersects(invalid_modifiers) {
                 self.ctx.error(
                     error!(
+                        ConflictingModifiers,
                         "Conflicting modifiers with intermediate preparation reference",
                         label!(ingredient.modifiers.span())
                     )
@@ -637,13 +938,8 @@ impl<'i> RecipeCollector<'i, '_> {
                         });
                     for (index, q) in all_quantities {
                         if let Err(e) = q.compatible_unit(new_quantity, self.converter) {
-                            let old_q_loc =
-                                self.locations.ingredients[index].quantity.as_ref().unwrap();
-                            let old = old_q_loc
-                                .unit
-                                .as_ref()
-                                .map(|l| l.span())
-                                .unwrap_or(old_q_loc.span());
+                            let old_loc = &self.locations.ingredients[index];
+                            let old = old_loc.unit.or(old_loc.quantity).unwrap();
                             let new_q_loc = located_ingredient.quantity.as_ref().unwrap();
                             let new = new_q_loc
                                 .unit
@@ -676,6 +972,7 @@ impl<'i> RecipeCollector<'i, '_> {
 
                             self.ctx.warn(
                                 warning!(
+                                    IncompatibleUnits,
                                     "Incompatible units prevent calculating total amount",
                                     main_label
                                 )
@@ -691,8 +988,8 @@ impl<'i> RecipeCollector<'i, '_> {
                 self.ctx.error(note_reference_error(
                     note.span(),
                     implicit,
-                    definition_location.span(),
-                    definition_location.note.as_ref().map(|n| n.span()),
+                    definition_location.span,
+                    definition_location.note,
                 ));
             }
 
@@ -715,7 +1012,7 @@ impl<'i> RecipeCollector<'i, '_> {
             {
                 self.ctx.error(conflicting_reference_quantity_error(
                     ingredient.quantity.unwrap().span(),
-                    definition_location.span(),
+                    definition_location.span,
                     implicit,
                 ));
             }
@@ -729,7 +1026,7 @@ impl<'i> RecipeCollector<'i, '_> {
 
                 if ref_is_text != def_is_text {
                     let ref_q_loc = located_ingredient.quantity.as_ref().unwrap().span();
-                    let def_q_loc = definition_location.quantity.as_ref().unwrap().span();
+                    let def_q_loc = definition_location.quantity.unwrap();
 
                     let (text_quantity_span, number_quantity_span) = if ref_is_text {
                         (ref_q_loc, def_q_loc)
@@ -753,16 +1050,26 @@ impl<'i> RecipeCollector<'i, '_> {
         {
             if let Some(checker) = self.parse_options.recipe_ref_check.as_mut() {
                 let res = checker(&new_igr.name);
-                if let Some(mut diag) = res
-                    .into_source_diag(|| format!("Referenced recipe not found: {}", new_igr.name))
-                {
+                if let Some(mut diag) = res.into_source_diag(DiagCode::RecipeNotFound, || {
+                    format!("Referenced recipe not found: {}", new_igr.name)
+                }) {
                     diag.add_label(label!(location));
                     self.ctx.push(diag);
                 }
             }
         }
 
-        self.locations.ingredients.push(located_ingredient);
+        self.source_map.ingredients.push(located_ingredient.span());
+        self.locations.ingredients.push(ComponentLocation {
+            span: located_ingredient.span(),
+            quantity: located_ingredient.quantity.as_ref().map(|q| q.span()),
+            unit: located_ingredient
+                .quantity
+                .as_ref()
+                .and_then(|q| q.unit.as_ref())
+                .map(|u| u.span()),
+            note: located_ingredient.note.as_ref().map(|n| n.span()),
+        });
         self.content.ingredients.push(new_igr);
         self.content.ingredients.len() - 1
     }
@@ -782,6 +1089,7 @@ impl<'i> RecipeCollector<'i, '_> {
             match inter_data.ref_mode {
                 Mode::Number => {
                     return Err(error!(
+                        InvalidIntermediateRef,
                         format!("{INVALID}: number is 0"),
                         label!(inter_data.span())
                     )
@@ -789,6 +1097,7 @@ impl<'i> RecipeCollector<'i, '_> {
                 }
                 Mode::Relative => {
                     return Err(error!(
+                        InvalidIntermediateRef,
                         format!("{INVALID}: relative reference to self"),
                         label!(inter_data.span())
                     )
@@ -799,6 +1108,7 @@ impl<'i> RecipeCollector<'i, '_> {
 
         let bounds = |help: String| {
             Err(error!(
+                InvalidIntermediateRef,
                 format!("{INVALID}: value out of bounds"),
                 label!(inter_data.span())
             )
@@ -916,8 +1226,8 @@ impl<'i> RecipeCollector<'i, '_> {
                 self.ctx.error(note_reference_error(
                     note.span(),
                     implicit,
-                    definition_location.span(),
-                    definition_location.note.as_ref().map(|n| n.span()),
+                    definition_location.span,
+                    definition_location.note,
                 ));
             }
 
@@ -931,7 +1241,7 @@ impl<'i> RecipeCollector<'i, '_> {
             {
                 self.ctx.error(conflicting_reference_quantity_error(
                     located_cookware.quantity.as_ref().unwrap().span(),
-                    definition_location.span(),
+                    definition_location.span,
                     implicit,
                 ));
             }
@@ -945,7 +1255,7 @@ impl<'i> RecipeCollector<'i, '_> {
 
                 if ref_is_text != def_is_text {
                     let ref_q_loc = located_cookware.quantity.as_ref().unwrap().span();
-                    let def_q_loc = definition_location.quantity.as_ref().unwrap().span();
+                    let def_q_loc = definition_location.quantity.unwrap();
 
                     let (text_quantity_span, number_quantity_span) = if ref_is_text {
                         (ref_q_loc, def_q_loc)
@@ -964,30 +1274,39 @@ impl<'i> RecipeCollector<'i, '_> {
             Cookware::set_referenced_from(&mut self.content.cookware, references_to);
         }
 
-        self.locations.cookware.push(located_cookware);
+        self.source_map.cookware.push(located_cookware.span());
+        self.locations.cookware.push(ComponentLocation {
+            span: located_cookware.span(),
+            quantity: located_cookware.quantity.as_ref().map(|q| q.span()),
+            unit: None,
+            note: located_cookware.note.as_ref().map(|n| n.span()),
+        });
         self.content.cookware.push(new_cw);
         self.content.cookware.len() - 1
     }
 
     fn timer(&mut self, timer: Located<parser::Timer<'i>>) -> usize {
         let located_timer = timer.clone();
-        let (timer, _span) = timer.take_pair();
+        let (timer, span) = timer.take_pair();
         let quantity = timer.quantity.map(|q| {
             let quantity = self.quantity(q, false);
             if self.extensions.contains(Extensions::ADVANCED_UNITS) {
                 let located_quantity = located_timer.quantity.as_ref().unwrap();
                 if quantity.value().is_text() {
                     self.ctx.error(error!(
+                        TimerTextValue,
                         format!("Timer value is text: {}", quantity.value()),
                         label!(located_quantity.value.span(), "expected a number here")
                     ));
                 }
                 if let Some(unit_text) = quantity.unit() {
                     let unit_span = located_quantity.unit.as_ref().unwrap().span();
+                    let unit_span = trim_span(self.input, unit_span);
                     match quantity.unit_info(self.converter) {
                         Some(unit) => {
                             if unit.physical_quantity != PhysicalQuantity::Time {
                                 self.ctx.error(error!(
+                                    TimerUnitNotTime,
                                     format!("Timer unit is not time: {unit}"),
                                     label!(
                                         unit_span,
@@ -997,10 +1316,23 @@ impl<'i> RecipeCollector<'i, '_> {
                                 ));
                             }
                         }
-                        None => self.ctx.error(error!(
-                            format!("Unknown timer unit: {unit_text}"),
-                            label!(unit_span, "expected time unit")
-                        )),
+                        None => {
+                            let mut e = error!(
+                                UnknownTimerUnit,
+                                format!("Unknown timer unit: {unit_text}"),
+                                label!(unit_span, "expected time unit")
+                            );
+                            if let Some(closest) =
+                                closest_unit(self.converter, unit_text, PhysicalQuantity::Time)
+                            {
+                                e.add_suggestion(Suggestion::new(
+                                    format!("Change the unit to `{closest}`"),
+                                    unit_span,
+                                    closest,
+                                ));
+                            }
+                            self.ctx.error(e);
+                        }
                     }
                 }
             }
@@ -1012,10 +1344,83 @@ impl<'i> RecipeCollector<'i, '_> {
             quantity,
         };
 
+        self.source_map.timers.push(span);
         self.content.timers.push(new_timer);
         self.content.timers.len() - 1
     }
 
+    fn temperature(&mut self, temperature: Located<parser::Temperature<'i>>) -> usize {
+        let (temperature, span) = temperature.take_pair();
+        let value_span = temperature.quantity.value.span();
+        let unit = temperature
+            .quantity
+            .unit
+            .as_ref()
+            .expect("temperature without unit");
+        let unit_span = trim_span(self.input, unit.span());
+        let unit_text = unit.text_trimmed();
+
+        let value = match self.value(temperature.quantity.into_inner().value, false) {
+            ScalableValue::Fixed(value) | ScalableValue::Linear(value) => value,
+        };
+        if value.is_text() {
+            self.ctx.error(error!(
+                TemperatureTextValue,
+                format!("Temperature value is text: {value}"),
+                label!(value_span, "expected a number here")
+            ));
+        }
+
+        let scale = match TemperatureScale::from_unit(&unit_text, self.converter) {
+            Some(scale) => scale,
+            None => {
+                let mut e = error!(
+                    UnknownTemperatureUnit,
+                    format!("Unknown temperature unit: {unit_text}"),
+                    label!(unit_span, "expected °C, °F or gas mark")
+                );
+                if let Some(closest) =
+                    closest_unit(self.converter, &unit_text, PhysicalQuantity::Temperature)
+                {
+                    e.add_suggestion(Suggestion::new(
+                        format!("Change the unit to `{closest}`"),
+                        unit_span,
+                        closest,
+                    ));
+                }
+                self.ctx.error(e);
+                TemperatureScale::Celsius
+            }
+        };
+
+        let mode = temperature.mode.and_then(|note| {
+            let text = note.text_trimmed();
+            match OvenMode::from_str(&text) {
+                Ok(mode) => Some(mode),
+                Err(_) => {
+                    self.ctx.warn(
+                        warning!(
+                            UnknownOvenMode,
+                            format!("Unknown oven mode: {text}"),
+                            label!(note.span(), "expected an oven mode")
+                        )
+                        .hint(format!(
+                            "The oven modes are: {}",
+                            <OvenMode as strum::VariantNames>::VARIANTS.join(", ")
+                        )),
+                    );
+                    None
+                }
+            }
+        });
+
+        self.source_map.temperatures.push(span);
+        self.content
+            .temperatures
+            .push(Temperature { value, scale, mode });
+        self.content.temperatures.len() - 1
+    }
+
     fn quantity(
         &mut self,
         quantity: Located<parser::Quantity<'i>>,
@@ -1029,7 +1434,10 @@ impl<'i> RecipeCollector<'i, '_> {
     }
 
     fn value(&mut self, value: parser::QuantityValue, is_ingredient: bool) -> ScalableValue {
-        let parser::QuantityValue { value, scaling_lock } = value;
+        let parser::QuantityValue {
+            value,
+            scaling_lock,
+        } = value;
         let has_scaling_lock = scaling_lock.is_some();
         let is_text = value.is_text();
 
@@ -1041,6 +1449,7 @@ impl<'i> RecipeCollector<'i, '_> {
         // Warn if scaling lock is used unnecessarily (on non-ingredients or text values)
         if has_scaling_lock {
             let mut warning = warning!(
+                UnnecessaryScalingLock,
                 "Unnecessary scaling lock modifier",
                 label!(value.span(), "this scaling lock has no effect")
             );
@@ -1080,6 +1489,7 @@ impl<'i> RecipeCollector<'i, '_> {
 
         let conflicing_modifiers = |conflict: Modifiers, help: CowStr, implicit: bool| {
             let mut e = error!(
+                ConflictingModifiers,
                 format!("Unsupported modifier combination with reference: {conflict}"),
                 label!(modifiers_location)
             )
@@ -1092,6 +1502,7 @@ impl<'i> RecipeCollector<'i, '_> {
 
         let redundant_modifier = |redundant: &'static str, help: String| {
             warning!(
+                RedundantModifier,
                 format!("Redundant {redundant} modifier"),
                 label!(modifiers_location)
             )
@@ -1200,6 +1611,7 @@ impl<'i> RecipeCollector<'i, '_> {
         } else {
             self.ctx.error({
                 let mut e = error!(
+                    ReferenceNotFound,
                     format!("Reference not found: {}", new.name()),
                     label!(location)
                 )
@@ -1209,6 +1621,16 @@ impl<'i> RecipeCollector<'i, '_> {
                 ));
                 if implicit {
                     e.add_hint(IMPLICIT_REF_WARN);
+                } else {
+                    let modifiers = &self.input[modifiers_location.range()];
+                    e.add_suggestion(Suggestion::new(
+                        format!(
+                            "Remove the reference (&) to define the {} here",
+                            C::container()
+                        ),
+                        modifiers_location,
+                        modifiers.replacen('&', "", 1),
+                    ));
                 }
                 e
             });
@@ -1327,6 +1749,23 @@ impl RefComponent for Cookware<ScalableValue> {
     }
 }
 
+/// Maps a byte offset in [`Text::text`] to an offset in the source
+fn text_source_offset(text: &Text, pos: usize) -> usize {
+    let mut rendered = 0;
+    for fragment in text.fragments() {
+        let len = if fragment.kind() == TextFragmentKind::SoftBreak {
+            1
+        } else {
+            fragment.text().len()
+        };
+        if pos < rendered + len {
+            return fragment.start() + (pos - rendered).min(fragment.text().len());
+        }
+        rendered += len;
+    }
+    text.span().end()
+}
+
 fn find_inline_quantity<'a>(
     text: &'a str,
     converter: &Converter,
@@ -1371,6 +1810,21 @@ fn find_inline_quantity<'a>(
             neg = false;
         }
 
+        // "gas mark 4", the unit goes before the number
+        if let Some(before) = strip_gas_mark(before) {
+            if !neg {
+                let num_len = text[i..]
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .unwrap_or(text.len() - i);
+                let number_str = text[i..i + num_len].trim_end_matches('.');
+                if let Ok(number) = number_str.parse::<f64>() {
+                    i += number_str.len();
+                    let q = Quantity::new(Value::from(number), Some(GAS_MARK_UNIT.to_string()));
+                    return Some((before, q, &text[i..]));
+                }
+            }
+        }
+
         let w1 = eat_word(text, &mut i)?; // if no words, no more quantities
         let first_non_digit =
             w1.find(|c: char| !c.is_ascii_digit() && c != '.' && !c.is_whitespace());
@@ -1393,13 +1847,22 @@ fn find_inline_quantity<'a>(
             prev = i;
         }
 
-        let after = &text[i..];
         let Ok(mut number) = number.parse::<f64>() else {
             continue;
         };
         if converter.find_unit(unit).is_none() {
-            continue;
+            // a temperature may be at the end of a sentence, like "180 °C."
+            let trimmed = unit.trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+            let is_temperature = converter
+                .find_unit(trimmed)
+                .is_some_and(|u| u.physical_quantity == PhysicalQuantity::Temperature);
+            if trimmed.len() == unit.len() || !is_temperature {
+                continue;
+            }
+            i -= unit.len() - trimmed.len();
+            unit = trimmed;
         };
+        let after = &text[i..];
 
         if neg {
             number = -number;
@@ -1412,6 +1875,18 @@ fn find_inline_quantity<'a>(
     None
 }
 
+/// Removes a trailing "gas mark " if present
+fn strip_gas_mark(before: &str) -> Option<&str> {
+    let trimmed = before.trim_end();
+    if trimmed.len() == before.len() {
+        return None; // needs whitespace before the number
+    }
+    let start = trimmed.len().checked_sub(GAS_MARK_UNIT.len())?;
+    let suffix = trimmed.get(start..)?;
+    let word_start = start == 0 || trimmed[..start].ends_with(|c: char| !c.is_alphanumeric());
+    (word_start && suffix.eq_ignore_ascii_case(GAS_MARK_UNIT)).then(|| &trimmed[..start])
+}
+
 fn note_reference_error(
     span: Span,
     implicit: bool,
@@ -1420,7 +1895,11 @@ fn note_reference_error(
 ) -> SourceDiag {
     let span = Span::new(span.start().saturating_sub(1), span.end() + 1);
 
-    let mut e = error!("Note not allowed in reference", label!(span, "remove this"));
+    let mut e = error!(
+        NoteInReference,
+        "Note not allowed in reference",
+        label!(span, "remove this")
+    );
 
     if let Some(sp) = def_note_span {
         e.add_label(label!(sp, "the definition already has a note"));
@@ -1441,6 +1920,7 @@ fn conflicting_reference_quantity_error(
     implicit: bool,
 ) -> SourceDiag {
     let mut e = error!(
+        ConflictingReferenceQuantity,
         "Conflicting component reference quantities",
         label!(ref_quantity_span, "reference with quantity")
     )
@@ -1461,6 +1941,7 @@ fn text_val_in_ref_warn(
     implicit: bool,
 ) -> SourceDiag {
     let mut w = warning!(
+        TextValueInReference,
         "Text value may prevent calculating total amount",
         label!(text_quantity_span, "can't operate with text value")
     )
@@ -1472,6 +1953,101 @@ fn text_val_in_ref_warn(
     w
 }
 
+/// Span without the surrounding whitespace
+/// Applies the lint levels of the options and then the ones in the recipe
+fn apply_lints(ctx: &mut SourceReport, options: &Lints, in_file: &Lints) {
+    let mut lints = options.clone();
+    lints.extend(in_file);
+    ctx.apply_lints(&lints);
+}
+
+fn trim_span(input: &str, span: Span) -> Span {
+    let text = input[span.range()].trim_start();
+    let start = span.end() - text.len();
+    Span::new(start, start + text.trim_end().len())
+}
+
+/// Warning for values of a [`StdKey`] that are not in the vocabulary
+fn not_in_vocabulary(
+    key: &str,
+    std_key: StdKey,
+    values: &[String],
+    vocabulary: Option<&Vocabulary>,
+) -> SourceDiag {
+    let default;
+    let vocabulary = match vocabulary {
+        Some(vocabulary) => vocabulary,
+        None => {
+            default = Vocabulary::default();
+            &default
+        }
+    };
+    let quoted = values
+        .iter()
+        .map(|v| format!("'{v}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut diag = warning!(
+        UnknownMetadataValue,
+        format!("Unknown value for key '{key}': {quoted}")
+    );
+    let terms = vocabulary.terms(std_key);
+    for value in values {
+        let value = value.to_lowercase();
+        let max_distance = (value.chars().count() / 3).max(1);
+        let closest = terms
+            .iter()
+            .map(|t| (edit_distance(&value, &t.name), &t.name))
+            .filter(|(d, _)| *d <= max_distance)
+            .min_by_key(|(d, _)| *d);
+        if let Some((_, name)) = closest {
+            diag.add_hint(format!("Did you mean '{name}'?"));
+        }
+    }
+    if diag.hints.is_empty() && !terms.is_empty() {
+        let names = terms.iter().map(|t| t.name.as_str()).collect::<Vec<_>>();
+        diag.add_hint(format!("Known values are: {}", names.join(", ")));
+    }
+    diag
+}
+
+/// Most similar unit name or symbol to a misspelled unit
+fn closest_unit(converter: &Converter, unit: &str, quantity: PhysicalQuantity) -> Option<String> {
+    let unit = unit.to_lowercase();
+    let max_distance = (unit.chars().count() / 3).max(1);
+    converter
+        .all_units()
+        .filter(|u| u.physical_quantity == quantity)
+        .flat_map(|u| u.names.iter().chain(&u.symbols).chain(&u.aliases))
+        .map(|key| (edit_distance(&unit, &key.to_lowercase()), key))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, key)| key.to_string())
+}
+
+/// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev + usize::from(ca != *cb);
+            prev = row[j + 1];
+            row[j + 1] = substitution.min(prev + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Span of a single line text without the surrounding whitespace
+fn trimmed_span(text: &Text) -> Span {
+    let raw = text.text();
+    let start = text.span().start() + raw.len() - raw.trim_start().len();
+    Span::new(start, start + raw.trim().len())
+}
+
 fn yaml_find_key_position(text: &str, key: &str) -> Option<usize> {
     // This is a bit of a hack, but it will work almost always and if it doesn't
     // it only tells the user a bad position
@@ -1496,13 +2072,17 @@ fn yaml_find_key_position(text: &str, key: &str) -> Option<usize> {
 }
 
 fn parse_reference(name: &str) -> Option<RecipeReference> {
-    if name.starts_with("./") || name.starts_with("../") || name.starts_with(".\\") || name.starts_with("..\\") {
+    if name.starts_with("./")
+        || name.starts_with("../")
+        || name.starts_with(".\\")
+        || name.starts_with("..\\")
+    {
         let path = name.replace('\\', "/");
         let mut components: Vec<String> = path.split('/').map(String::from).skip(1).collect();
         let file_stem = components.pop().unwrap();
         Some(RecipeReference {
             components,
-            name: file_stem.into()
+            name: file_stem.into(),
         })
     } else {
         None
@@ -1553,7 +2133,11 @@ mod tests {
         assert_eq!(
             parse_reference("./recipes/italian/pasta/spaghetti"),
             Some(RecipeReference {
-                components: vec!["recipes".to_string(), "italian".to_string(), "pasta".to_string()],
+                components: vec![
+                    "recipes".to_string(),
+                    "italian".to_string(),
+                    "pasta".to_string()
+                ],
                 name: "spaghetti".into()
             })
         );
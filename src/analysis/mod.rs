@@ -3,12 +3,17 @@
 //! This is just if for some reason you want to split the parsing from the
 //! analysis.
 
-use crate::error::{CowStr, PassResult, SourceDiag};
-use crate::ScalableRecipe;
+use serde::Serialize;
+
+use crate::error::{CowStr, DiagCode, Lints, PassResult, SourceDiag};
+#[cfg(feature = "schema")]
+use crate::metadata_schema::MetadataSchema;
+use crate::{metadata::Vocabulary, ScalableRecipe, Span};
 
 mod event_consumer;
 
 pub use event_consumer::parse_events;
+pub(crate) use event_consumer::{Checkpoint, RecipeCollector};
 
 pub type AnalysisResult = PassResult<ScalableRecipe>;
 
@@ -38,6 +43,76 @@ pub struct ParseOptions<'a> {
     /// can customize what happens to the key, including not running the default
     /// checks.
     pub metadata_validator: Option<MetadataValidator<'a>>,
+    /// Validate the YAML frontmatter with a JSON Schema
+    ///
+    /// Every violation is a warning, see [`crate::metadata_schema`].
+    #[cfg(feature = "schema")]
+    pub metadata_schema: Option<&'a MetadataSchema>,
+    /// Known values of the `course`, `cuisine` and `diet` keys
+    ///
+    /// Other values are warnings. If `None`, [`Vocabulary::default`] is used.
+    pub vocabulary: Option<&'a Vocabulary>,
+    /// Fill a [`SourceMap`] with the location of the recipe elements
+    ///
+    /// If the recipe has errors and there is no output, the map is left empty.
+    pub source_map: Option<&'a mut SourceMap>,
+    /// Level of the warnings by their code
+    ///
+    /// The `[allow]`, `[warn]` and `[deny]` config keys in the recipe have
+    /// preference.
+    pub lints: Lints,
+}
+
+/// Location in the source of the elements of a recipe
+///
+/// The recipe model has no spans, so this can be requested with
+/// [`ParseOptions::source_map`]. Every vector uses the same indices as the
+/// corresponding one in the [`Recipe`](crate::Recipe).
+///
+/// ```
+/// # use cooklang::{CooklangParser, ParseOptions, analysis::SourceMap};
+/// let input = "Add @salt{} to the #pot{}.";
+/// let mut source_map = SourceMap::default();
+/// let options = ParseOptions {
+///     source_map: Some(&mut source_map),
+///     ..Default::default()
+/// };
+/// let recipe = CooklangParser::default()
+///     .parse_with_options(input, options)
+///     .into_output()
+///     .unwrap();
+/// assert_eq!(&input[source_map.ingredients[0].range()], "@salt{}");
+/// assert_eq!(&input[source_map.cookware[0].range()], "#pot{}");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SourceMap {
+    /// Ingredients, same index as [`Recipe::ingredients`](crate::Recipe::ingredients)
+    pub ingredients: Vec<Span>,
+    /// Cookware, same index as [`Recipe::cookware`](crate::Recipe::cookware)
+    pub cookware: Vec<Span>,
+    /// Timers, same index as [`Recipe::timers`](crate::Recipe::timers)
+    pub timers: Vec<Span>,
+    /// Inline quantities, same index as [`Recipe::inline_quantities`](crate::Recipe::inline_quantities)
+    pub inline_quantities: Vec<Span>,
+    /// Temperatures, same index as [`Recipe::temperatures`](crate::Recipe::temperatures)
+    pub temperatures: Vec<Span>,
+    /// Sections, same index as [`Recipe::sections`](crate::Recipe::sections)
+    pub sections: Vec<SectionSpans>,
+    /// Metadata keys, same order as [`Metadata::map`](crate::metadata::Metadata::map)
+    ///
+    /// `None` if the key location is not known.
+    pub metadata: Vec<Option<Span>>,
+}
+
+/// Location of a section and its content
+///
+/// Part of a [`SourceMap`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SectionSpans {
+    /// Name of the section, if any
+    pub name: Option<Span>,
+    /// Content, same index as [`Section::content`](crate::Section::content)
+    pub content: Vec<Span>,
 }
 
 /// Return type for check functions in [`ParseOptions`]
@@ -53,7 +128,7 @@ pub enum CheckResult {
 }
 
 impl CheckResult {
-    pub(crate) fn into_source_diag<F, O>(self, message: F) -> Option<SourceDiag>
+    pub(crate) fn into_source_diag<F, O>(self, code: DiagCode, message: F) -> Option<SourceDiag>
     where
         F: FnOnce() -> O,
         O: Into<CowStr>,
@@ -63,7 +138,8 @@ impl CheckResult {
             CheckResult::Warning(hints) => (crate::error::Severity::Warning, hints),
             CheckResult::Error(hints) => (crate::error::Severity::Error, hints),
         };
-        let mut diag = SourceDiag::unlabeled(message(), severity, crate::error::Stage::Analysis);
+        let mut diag =
+            SourceDiag::unlabeled(code, message(), severity, crate::error::Stage::Analysis);
         for hint in hints {
             diag.add_hint(hint);
         }
@@ -34,7 +34,7 @@ pub fn build_ast<'i>(events: impl Iterator<Item = Event<'i>>) -> PassResult<Ast<
         match event {
             Event::YAMLFrontMatter(_) => todo!(),
             Event::Metadata { key, value } => blocks.push(Block::Metadata { key, value }),
-            Event::Section { name } => blocks.push(Block::Section { name }),
+            Event::Section { name, optional } => blocks.push(Block::Section { name, optional }),
             Event::Start(_kind) => items.clear(),
             Event::End(kind) => {
                 match kind {
@@ -64,6 +64,7 @@ pub fn build_ast<'i>(events: impl Iterator<Item = Event<'i>>) -> PassResult<Ast<
             Event::Ingredient(c) => items.push(Item::Ingredient(Box::new(c))),
             Event::Cookware(c) => items.push(Item::Cookware(Box::new(c))),
             Event::Timer(c) => items.push(Item::Timer(Box::new(c))),
+            Event::Temperature(c) => items.push(Item::Temperature(Box::new(c))),
             Event::Error(e) => ctx.push(e),
             Event::Warning(w) => ctx.push(w),
         }
@@ -13,6 +13,7 @@ use thiserror::Error;
 
 use crate::{
     quantity::{Number, Quantity, ScaledQuantity, Value},
+    temperature::TemperatureScale,
     ScaledRecipe,
 };
 
@@ -422,6 +423,21 @@ impl ScaledRecipe {
     pub fn convert(&mut self, to: System, converter: &Converter) -> Vec<ConvertError> {
         let mut errors = Vec::new();
 
+        // gas marks stay, like in the inline quantities
+        let scale = match to {
+            System::Metric => TemperatureScale::Celsius,
+            System::Imperial => TemperatureScale::Fahrenheit,
+        };
+        for temp in &mut self.temperatures {
+            if temp.scale == TemperatureScale::GasMark || temp.scale == scale {
+                continue;
+            }
+            match temp.convert(scale, converter) {
+                Ok(converted) => *temp = converted,
+                Err(e) => errors.push(e),
+            }
+        }
+
         let to = ConvertTo::from(to);
 
         let mut conv = |q: &mut ScaledQuantity| {
@@ -444,8 +460,11 @@ impl ScaledRecipe {
             }
         }
 
+        // gas marks are not a unit here, see the temperature module
         for q in &mut self.inline_quantities {
-            conv(q);
+            if q.unit() != Some(crate::temperature::GAS_MARK_UNIT) {
+                conv(q);
+            }
         }
 
         errors
@@ -1,7 +1,10 @@
 //! Error type, formatting and utilities.
 
-use std::{borrow::Cow, panic::RefUnwindSafe};
+use std::{borrow::Cow, collections::HashMap, ops::Range, panic::RefUnwindSafe};
 
+use serde::{Deserialize, Serialize};
+
+use crate::line_index::{ColumnUnit, LineCol, LineIndex};
 use crate::Span;
 
 /// Handy label creation for [`SourceDiag`]
@@ -23,12 +26,50 @@ pub type CowStr = Cow<'static, str>;
 /// A label is a pair of a code location and an optional hint at that location
 pub type Label = (Span, Option<CowStr>);
 
+/// A machine applicable fix for a [`SourceDiag`]
+///
+/// Applying it replaces the code in [`Self::span`] with [`Self::replacement`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// What the fix does, like "Add the unit separator"
+    pub message: CowStr,
+    /// Code to replace. If empty, the replacement is inserted there.
+    pub span: Span,
+    /// New code
+    pub replacement: CowStr,
+}
+
+impl Suggestion {
+    pub(crate) fn new(
+        message: impl Into<CowStr>,
+        span: Span,
+        replacement: impl Into<CowStr>,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            span,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Applies the fix to the code
+    ///
+    /// The code has to be the same the diagnostic comes from.
+    pub fn apply(&self, source_code: &str) -> String {
+        let mut s = source_code.to_string();
+        s.replace_range(self.span.range(), &self.replacement);
+        s
+    }
+}
+
 /// A diagnostic of source code
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct SourceDiag {
     /// If the diagnostic is an error or warning
     pub severity: Severity,
+    /// What kind of problem it is
+    pub code: DiagCode,
     /// In which parsing stage did this origined
     pub stage: Stage,
     /// Report message describing the problem
@@ -44,6 +85,8 @@ pub struct SourceDiag {
     ///
     /// It should be ordered from high to low importance.
     pub hints: Vec<CowStr>,
+    /// Fixes that can be applied automatically
+    pub suggestions: Vec<Suggestion>,
 }
 
 impl std::fmt::Display for SourceDiag {
@@ -61,9 +104,17 @@ impl RichError for SourceDiag {
         self.hints.as_slice().into()
     }
 
+    fn suggestions(&self) -> Cow<'_, [Suggestion]> {
+        self.suggestions.as_slice().into()
+    }
+
     fn severity(&self) -> Severity {
         self.severity
     }
+
+    fn code(&self) -> Option<DiagCode> {
+        Some(self.code)
+    }
 }
 
 impl std::error::Error for SourceDiag {
@@ -78,30 +129,44 @@ impl std::error::Error for SourceDiag {
 
 impl PartialEq for SourceDiag {
     fn eq(&self, other: &Self) -> bool {
-        self.severity == other.severity && self.message == other.message
+        self.severity == other.severity && self.code == other.code && self.message == other.message
     }
 }
 
 impl SourceDiag {
     /// Creates a new error
-    pub(crate) fn error(message: impl Into<CowStr>, label: Label, stage: Stage) -> Self {
+    pub(crate) fn error(
+        code: DiagCode,
+        message: impl Into<CowStr>,
+        label: Label,
+        stage: Stage,
+    ) -> Self {
         Self {
             severity: Severity::Error,
+            code,
             message: message.into(),
             labels: vec![label],
             hints: vec![],
+            suggestions: vec![],
             source: None,
             stage,
         }
     }
 
     /// Creates a new warning
-    pub(crate) fn warning(message: impl Into<CowStr>, label: Label, stage: Stage) -> Self {
+    pub(crate) fn warning(
+        code: DiagCode,
+        message: impl Into<CowStr>,
+        label: Label,
+        stage: Stage,
+    ) -> Self {
         Self {
             severity: Severity::Warning,
+            code,
             message: message.into(),
             labels: vec![label],
             hints: vec![],
+            suggestions: vec![],
             source: None,
             stage,
         }
@@ -110,14 +175,21 @@ impl SourceDiag {
     /// Creates a new unlabeled diagnostic
     ///
     /// This means there's no error location
-    pub(crate) fn unlabeled(message: impl Into<CowStr>, severity: Severity, stage: Stage) -> Self {
+    pub(crate) fn unlabeled(
+        code: DiagCode,
+        message: impl Into<CowStr>,
+        severity: Severity,
+        stage: Stage,
+    ) -> Self {
         Self {
             severity,
+            code,
             stage,
             message: message.into(),
             source: None,
             labels: vec![],
             hints: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -131,6 +203,29 @@ impl SourceDiag {
         self.severity == Severity::Warning
     }
 
+    /// Start and end of the main label, if any
+    ///
+    /// The `index` has to be built from the source code of the diagnostic.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, line_index::*};
+    /// let input = "Chop the 🧅.\nAdd @flour{100%}.";
+    /// let report = CooklangParser::default().parse(input).into_report();
+    /// let index = LineIndex::new(input);
+    /// let diag = report.iter().next().unwrap();
+    /// let (start, _) = diag.location(input, &index, ColumnUnit::Char).unwrap();
+    /// assert_eq!(start, LineCol::new(1, 14));
+    /// ```
+    pub fn location(
+        &self,
+        source_code: &str,
+        index: &LineIndex,
+        unit: ColumnUnit,
+    ) -> Option<(LineCol, LineCol)> {
+        let (span, _) = self.labels.first()?;
+        Some(index.range(source_code, *span, unit))
+    }
+
     /// Adds a new label
     pub(crate) fn label(mut self, label: Label) -> Self {
         self.add_label(label);
@@ -152,6 +247,17 @@ impl SourceDiag {
         self.hints.push(hint.into());
         self
     }
+
+    /// Adds a new suggestion
+    pub(crate) fn suggest(mut self, suggestion: Suggestion) -> Self {
+        self.add_suggestion(suggestion);
+        self
+    }
+    /// Adds a new suggestion
+    pub(crate) fn add_suggestion(&mut self, suggestion: Suggestion) -> &mut Self {
+        self.suggestions.push(suggestion);
+        self
+    }
     /// Sets the error source
     ///
     /// This is where [`std::error::Error::source`] get's the information
@@ -165,7 +271,8 @@ impl SourceDiag {
 }
 
 /// Diagnostic severity
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum Severity {
     /// Fatal error
     Error,
@@ -174,12 +281,279 @@ pub enum Severity {
 }
 
 /// Stage where the diagnostic origined
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum Stage {
     /// Parse stage
     Parse,
     /// Analysis stage
     Analysis,
+    /// Linter, see [`crate::lint`]
+    Lint,
+}
+
+/// Stable identifier of the problem a [`SourceDiag`] reports
+///
+/// Codes don't change between versions, unlike messages, so they can be
+/// used to allow or deny warnings with [`Lints`]. As a string, a code is the
+/// variant name in kebab case, like `time-overridden`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+    strum::IntoStaticStr,
+    strum::EnumIter,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[non_exhaustive]
+pub enum DiagCode {
+    // Parse stage
+    /// A metadata line without the `:` separator
+    InvalidMetadata,
+    /// A metadata entry without key
+    EmptyMetadataKey,
+    /// A metadata entry without value
+    EmptyMetadataValue,
+    /// A section line with text after the closing `=`
+    InvalidSection,
+    /// A component without a name, like `@ `
+    InvalidSingleWordName,
+    /// A component name that is empty
+    EmptyName,
+    /// The same modifier more than once
+    DuplicateModifier,
+    /// A modifier that the component does not support
+    InvalidModifier,
+    /// An alias that is empty, repeated or not supported
+    InvalidAlias,
+    /// A wrong intermediate preparation reference, like `@&(~0)dough`
+    InvalidIntermediateRef,
+    /// A quantity value that is empty
+    EmptyValue,
+    /// A quantity unit that is empty, like `{2%}`
+    EmptyUnit,
+    /// A number that can't be parsed
+    InvalidNumber,
+    /// A fraction with a zero denominator
+    DivisionByZero,
+    /// A cookware quantity with unit
+    CookwareUnit,
+    /// A timer quantity without unit
+    TimerMissingUnit,
+    /// A named timer without quantity
+    TimerMissingQuantity,
+    /// A timer without name nor quantity
+    EmptyTimer,
+    /// A temperature quantity without unit
+    TemperatureMissingUnit,
+    /// A note in a component that does not support it
+    NoteNotAllowed,
+
+    // Analysis stage
+    /// Metadata with the `>>` syntax instead of a YAML frontmatter
+    DeprecatedMetadataSyntax,
+    /// A YAML frontmatter that can't be parsed
+    InvalidFrontmatter,
+    /// A value that is not valid for a [`StdKey`](crate::metadata::StdKey)
+    UnsupportedMetadataValue,
+    /// A `course`, `cuisine` or `diet` not in the [`Vocabulary`](crate::metadata::Vocabulary)
+    UnknownMetadataValue,
+    /// `time` together with `prep time` or `cook time`
+    TimeOverridden,
+    /// An invalid value for a config key, like `[mode]`
+    InvalidConfigValue,
+    /// An unknown config key
+    UnknownConfigKey,
+    /// A code in a lint config key that does not exist
+    UnknownLint,
+    /// Text ignored in components mode
+    IgnoredText,
+    /// A component ignored in text mode
+    IgnoredComponent,
+    /// Modifiers that can't be used together
+    ConflictingModifiers,
+    /// A modifier that does nothing in the current mode
+    RedundantModifier,
+    /// A scaling lock that has no effect
+    UnnecessaryScalingLock,
+    /// A reference to a component that is not defined
+    ReferenceNotFound,
+    /// A note in a reference
+    NoteInReference,
+    /// A quantity in a reference and its definition that can't be combined
+    ConflictingReferenceQuantity,
+    /// Quantities of the same ingredient with units that can't be added
+    IncompatibleUnits,
+    /// A text value together with numbers in the same ingredient
+    TextValueInReference,
+    /// A timer value that is text
+    TimerTextValue,
+    /// A timer unit that is not time
+    TimerUnitNotTime,
+    /// A timer unit that is not known
+    UnknownTimerUnit,
+    /// A temperature value that is text
+    TemperatureTextValue,
+    /// A temperature unit that is not °C, °F or gas mark
+    UnknownTemperatureUnit,
+    /// A temperature note that is not an [`OvenMode`](crate::temperature::OvenMode)
+    UnknownOvenMode,
+    /// A recipe reference rejected by [`ParseOptions::recipe_ref_check`](crate::ParseOptions::recipe_ref_check)
+    RecipeNotFound,
+    /// A metadata entry rejected by [`ParseOptions::metadata_validator`](crate::ParseOptions::metadata_validator)
+    MetadataCheck,
+    /// A metadata entry that does not match the schema in [`ParseOptions`](crate::ParseOptions)
+    MetadataSchema,
+    /// A metadata entry that does not match the type given to [`Metadata::deserialize_into`](crate::metadata::Metadata::deserialize_into)
+    MetadataDeserialize,
+    /// Cookware needed by two steps at the same time, see [`crate::schedule`]
+    CookwareConflict,
+
+    // Linter, see [`crate::lint`]
+    /// An ingredient that is not used in any step
+    UnusedIngredient,
+    /// Cookware that is not used in any step
+    UnusedCookware,
+    /// A timer without name
+    UnnamedTimer,
+    /// Metric and imperial units in the same recipe
+    MixedUnitSystems,
+    /// A step with too many words
+    LongStep,
+    /// A recommended metadata key that is missing
+    MissingMetadata,
+    /// An ingredient defined again instead of referenced
+    DuplicateIngredient,
+}
+
+impl DiagCode {
+    /// Code as a string, like `time-overridden`
+    pub fn as_str(self) -> &'static str {
+        self.into()
+    }
+}
+
+/// How a warning is reported
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum LintLevel {
+    /// Remove it from the report
+    Allow,
+    /// Keep it as a warning
+    Warn,
+    /// Turn it into an error
+    Deny,
+}
+
+/// Level of each warning by its [`DiagCode`]
+///
+/// Like clippy lints, warnings can be allowed, so they are not reported, or
+/// denied, so they are errors. Errors are always errors.
+///
+/// A recipe can set the levels too with the `[allow]`, `[warn]` and `[deny]`
+/// config keys, see [`Extensions::MODES`](crate::Extensions::MODES).
+///
+/// ```
+/// # use cooklang::{CooklangParser, ParseOptions, error::{DiagCode, LintLevel, Lints}};
+/// let mut lints = Lints::new();
+/// lints.set(DiagCode::EmptyUnit, LintLevel::Deny);
+/// let options = ParseOptions {
+///     lints,
+///     ..Default::default()
+/// };
+/// let result = CooklangParser::default().parse_with_options("Add @salt{1%}.", options);
+/// let error = result.report().errors().next().unwrap();
+/// assert_eq!(error.code, DiagCode::EmptyUnit);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lints {
+    warnings: Option<LintLevel>,
+    levels: HashMap<DiagCode, LintLevel>,
+}
+
+impl Lints {
+    /// Creates an empty config, every warning is reported as a warning
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the level of a code
+    pub fn set(&mut self, code: DiagCode, level: LintLevel) -> &mut Self {
+        self.levels.insert(code, level);
+        self
+    }
+
+    /// Sets the level of every warning without its own level
+    ///
+    /// This is the `warnings` group in the config keys.
+    pub fn set_warnings(&mut self, level: LintLevel) -> &mut Self {
+        self.warnings = Some(level);
+        self
+    }
+
+    /// Level of a code
+    pub fn level(&self, code: DiagCode) -> LintLevel {
+        self.levels
+            .get(&code)
+            .copied()
+            .or(self.warnings)
+            .unwrap_or(LintLevel::Warn)
+    }
+
+    /// Sets the level of a comma separated list of codes, like in the config
+    /// keys. `warnings` is every warning.
+    ///
+    /// Returns the byte ranges in the list of the codes that don't exist.
+    pub(crate) fn set_list(&mut self, level: LintLevel, list: &str) -> Vec<Range<usize>> {
+        let mut unknown = Vec::new();
+        let mut offset = 0;
+        for item in list.split(',') {
+            let start = offset + item.len() - item.trim_start().len();
+            offset += item.len() + 1;
+            let code = item.trim();
+            if code.is_empty() {
+                continue;
+            }
+            if code == "warnings" {
+                self.set_warnings(level);
+            } else if let Ok(code) = code.parse() {
+                self.set(code, level);
+            } else {
+                unknown.push(start..start + code.len());
+            }
+        }
+        unknown
+    }
+
+    /// Adds the levels of another config, which have preference
+    pub(crate) fn extend(&mut self, other: &Lints) {
+        if other.warnings.is_some() {
+            self.warnings = other.warnings;
+        }
+        self.levels.extend(&other.levels);
+    }
 }
 
 /// Errors and warnings container with fancy formatting
@@ -295,6 +669,26 @@ impl SourceReport {
         self.buf.retain(SourceDiag::is_error)
     }
 
+    /// Removes the allowed warnings and turns the denied ones into errors
+    ///
+    /// The parser already does this with [`ParseOptions::lints`](crate::ParseOptions::lints),
+    /// this is for reports from elsewhere.
+    pub fn apply_lints(&mut self, lints: &Lints) {
+        self.buf.retain_mut(|diag| {
+            if diag.is_warning() {
+                match lints.level(diag.code) {
+                    LintLevel::Allow => return false,
+                    LintLevel::Warn => {}
+                    LintLevel::Deny => diag.severity = Severity::Error,
+                }
+            }
+            true
+        });
+        if self.severity == Some(Severity::Warning) && self.buf.iter().any(SourceDiag::is_error) {
+            self.severity = None;
+        }
+    }
+
     /// Consumes the report and returns [`Vec`] of [`SourceDiag`]
     pub fn into_vec(self) -> Vec<SourceDiag> {
         self.buf
@@ -326,6 +720,42 @@ impl SourceReport {
     pub fn eprint(&self, file_name: &str, source_code: &str, color: bool) -> std::io::Result<()> {
         self.write(file_name, source_code, color, &mut std::io::stderr().lock())
     }
+
+    /// Diagnostics with line and column positions, see [`crate::report`]
+    pub fn to_diagnostics(&self, source_code: &str) -> Vec<crate::report::Diagnostic> {
+        crate::report::diagnostics(self, source_code)
+    }
+
+    /// Same as [`Self::to_diagnostics`] with columns in the given unit
+    pub fn to_diagnostics_with_unit(
+        &self,
+        source_code: &str,
+        unit: ColumnUnit,
+    ) -> Vec<crate::report::Diagnostic> {
+        crate::report::diagnostics_with_unit(self, source_code, unit)
+    }
+
+    /// Write the report as JSON, see [`crate::report`]
+    pub fn write_json(
+        &self,
+        file_name: &str,
+        source_code: &str,
+        w: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        crate::report::write_json(self, file_name, source_code, w)
+    }
+
+    /// Write the report as a SARIF log, see [`crate::report`]
+    pub fn write_sarif(
+        &self,
+        file_name: &str,
+        source_code: &str,
+        w: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        let mut sarif = crate::report::Sarif::new();
+        sarif.add(file_name, source_code, self);
+        sarif.write(w)
+    }
 }
 
 impl std::fmt::Display for SourceReport {
@@ -438,9 +868,15 @@ pub trait RichError: std::error::Error {
     fn hints(&self) -> Cow<[CowStr]> {
         Cow::Borrowed(&[])
     }
+    fn suggestions(&self) -> Cow<'_, [Suggestion]> {
+        Cow::Borrowed(&[])
+    }
     fn severity(&self) -> Severity {
         Severity::Error
     }
+    fn code(&self) -> Option<DiagCode> {
+        None
+    }
 }
 
 /// Writes a rich error report
@@ -498,10 +934,15 @@ fn write_report(
         Severity::Error => yansi::Color::Red,
         Severity::Warning => yansi::Color::Yellow,
     };
-    match err.severity() {
-        Severity::Error => writeln!(w, "{} {err}", "Error:".paint(sev_color).whenever(cond))?,
-        Severity::Warning => writeln!(w, "{} {err}", "Warning:".paint(sev_color).whenever(cond))?,
-    }
+    let title = match err.severity() {
+        Severity::Error => "Error",
+        Severity::Warning => "Warning",
+    };
+    let title = match err.code() {
+        Some(code) => format!("{title}[{code}]:"),
+        None => format!("{title}:"),
+    };
+    writeln!(w, "{} {err}", title.paint(sev_color).whenever(cond))?;
     if let Some(source) = err.source() {
         writeln!(w, "  {} {source}", "╰▶ ".paint(sev_color).whenever(cond))?;
     }
@@ -567,6 +1008,15 @@ fn write_report(
             "the report builder only supports 2 hints, more will be ignored",
         );
     }
+
+    for suggestion in err.suggestions().iter() {
+        writeln!(
+            w,
+            "{} {}",
+            "Fix:".green().whenever(cond),
+            suggestion.message
+        )?;
+    }
     Ok(())
 }
 
@@ -0,0 +1,407 @@
+//! HTML export
+//!
+//! Renders a [`ScaledRecipe`] as an HTML fragment, an `<article>` element
+//! with the same parts as the [`markdown`](super::markdown) export. It needs
+//! no JavaScript and has no styles, only class names to add them.
+//!
+//! Every component in a step is wrapped in a `<span>` with the class
+//! `ingredient`, `cookware`, `timer`, `inline-quantity` or `temperature` and
+//! data attributes, so front-ends can make them interactive:
+//!
+//! - `data-index`. Index in the corresponding vec of the recipe, like
+//!   [`Recipe::ingredients`](crate::Recipe::ingredients).
+//! - `data-quantity`. The value: a number, a range like `1-2` or text.
+//! - `data-unit`. The unit, if any. For temperatures, the
+//!   [`unit_name`](crate::temperature::TemperatureScale::unit_name).
+//! - `data-mode`. The [`OvenMode`](crate::temperature::OvenMode) of a
+//!   temperature, if any.
+//!
+//! The quantity text is in a nested `<span class="quantity">`.
+//!
+//! [`Step::images`](crate::Step::images) are added at the end of the step as
+//! `<img class="step-image">`.
+//!
+//! ```
+//! # use cooklang::{CooklangParser, export::html};
+//! let parser = CooklangParser::default();
+//! let recipe = parser.parse("Add @salt{1/2%tsp}.").into_output().unwrap().default_scale();
+//! let html = html::to_string(&recipe, parser.converter());
+//! assert!(html.contains(
+//!     r#"<span class="ingredient" data-index="0" data-quantity="0.5" data-unit="tsp">"#
+//! ));
+//! ```
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use crate::{
+    convert::Converter, Content, IngredientReferenceTarget, Item, Quantity, ScaledRecipe, Section,
+    Value,
+};
+
+use super::{write_document, DocumentFormat, List, ListEntry};
+
+/// Writes a recipe as HTML
+pub fn write(recipe: &ScaledRecipe, converter: &Converter, mut w: impl Write) -> io::Result<()> {
+    write_document::<Html>(recipe, converter, &mut w)
+}
+
+/// Renders a recipe as HTML
+pub fn to_string(recipe: &ScaledRecipe, converter: &Converter) -> String {
+    let mut buf = Vec::new();
+    write(recipe, converter, &mut buf).expect("write to vec");
+    String::from_utf8(buf).expect("utf8 html")
+}
+
+struct Html;
+
+impl DocumentFormat for Html {
+    fn escape(text: &str) -> Cow<str> {
+        escape(text)
+    }
+
+    fn write_start(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, r#"<article class="recipe">"#)
+    }
+
+    fn write_title(w: &mut impl Write, title: &str) -> io::Result<()> {
+        writeln!(w, "<h1>{title}</h1>")
+    }
+
+    fn write_metadata(w: &mut impl Write, entries: &[(String, String)]) -> io::Result<()> {
+        writeln!(w, r#"<dl class="metadata">"#)?;
+        for (key, value) in entries {
+            writeln!(w, "<dt>{key}</dt><dd>{value}</dd>")?;
+        }
+        writeln!(w, "</dl>")
+    }
+
+    fn write_list(w: &mut impl Write, list: List, entries: &[ListEntry]) -> io::Result<()> {
+        let title = list.title();
+        writeln!(w, r#"<section class="{}">"#, title.to_lowercase())?;
+        writeln!(w, "<h2>{title}</h2>")?;
+        writeln!(w, "<ul>")?;
+        for entry in entries {
+            write!(
+                w,
+                r#"<li data-index="{}"><span class="name">{}</span>"#,
+                entry.index, entry.name
+            )?;
+            if let Some(quantity) = &entry.quantity {
+                write!(w, r#": <span class="quantity">{quantity}</span>"#)?;
+            }
+            if let Some(note) = &entry.note {
+                write!(w, r#" <span class="note">({note})</span>"#)?;
+            }
+            writeln!(w, "</li>")?;
+        }
+        writeln!(w, "</ul>")?;
+        writeln!(w, "</section>")
+    }
+
+    fn write_steps_start(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, r#"<section class="steps">"#)?;
+        writeln!(w, "<h2>Steps</h2>")
+    }
+
+    fn write_section(
+        w: &mut impl Write,
+        recipe: &ScaledRecipe,
+        _converter: &Converter,
+        index: usize,
+        heading: Option<&str>,
+        section: &Section,
+    ) -> io::Result<()> {
+        writeln!(w, r#"<section class="section" data-index="{index}">"#)?;
+        if let Some(heading) = heading {
+            writeln!(w, "<h3>{heading}</h3>")?;
+        }
+        let mut in_list = false;
+        for content in &section.content {
+            match content {
+                Content::Step(step) => {
+                    if !in_list {
+                        writeln!(w, "<ol>")?;
+                        in_list = true;
+                    }
+                    write!(w, r#"<li value="{}">"#, step.number)?;
+                    for item in &step.items {
+                        write_item(w, recipe, section, item)?;
+                    }
+                    for image in &step.images {
+                        write!(
+                            w,
+                            r#"<img class="step-image" src="{}" alt="Step {}">"#,
+                            escape(image),
+                            step.number
+                        )?;
+                    }
+                    writeln!(w, "</li>")?;
+                }
+                Content::Text(text) => {
+                    if in_list {
+                        writeln!(w, "</ol>")?;
+                        in_list = false;
+                    }
+                    writeln!(w, "<p>{}</p>", escape(text))?;
+                }
+            }
+        }
+        if in_list {
+            writeln!(w, "</ol>")?;
+        }
+        writeln!(w, "</section>")
+    }
+
+    fn write_steps_end(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "</section>")
+    }
+
+    fn write_end(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "</article>")
+    }
+}
+
+fn write_item(
+    w: &mut impl Write,
+    recipe: &ScaledRecipe,
+    section: &Section,
+    item: &Item,
+) -> io::Result<()> {
+    match item {
+        Item::Text { value } => write!(w, "{}", escape(value)),
+        Item::Ingredient { index } => {
+            let igr = &recipe.ingredients[*index];
+            write_start(w, "ingredient", *index, igr.quantity.as_ref())?;
+            write!(w, "{}", escape(&igr.display_name()))?;
+            if let Some(q) = &igr.quantity {
+                write!(
+                    w,
+                    r#" <span class="quantity">({})</span>"#,
+                    escape(&q.to_string())
+                )?;
+            }
+            match igr.relation.references_to() {
+                Some((index, IngredientReferenceTarget::Step)) => {
+                    let step = section.content[index].unwrap_step();
+                    write!(w, " (from step {})", step.number)?;
+                }
+                Some((index, IngredientReferenceTarget::Section)) => {
+                    write!(w, " (from section {})", index + 1)?;
+                }
+                _ => {}
+            }
+            write!(w, "</span>")
+        }
+        Item::Cookware { index } => {
+            let cw = &recipe.cookware[*index];
+            write!(w, r#"<span class="cookware" data-index="{index}""#)?;
+            if let Some(value) = &cw.quantity {
+                write!(w, r#" data-quantity="{}""#, escape(&value_attr(value)))?;
+            }
+            write!(w, ">{}", escape(cw.display_name()))?;
+            if let Some(value) = &cw.quantity {
+                write!(
+                    w,
+                    r#" <span class="quantity">({})</span>"#,
+                    escape(&value.to_string())
+                )?;
+            }
+            write!(w, "</span>")
+        }
+        Item::Timer { index } => {
+            let tm = &recipe.timers[*index];
+            write_start(w, "timer", *index, tm.quantity.as_ref())?;
+            match (&tm.name, &tm.quantity) {
+                (Some(name), Some(q)) => write!(
+                    w,
+                    r#"{} <span class="quantity">({})</span>"#,
+                    escape(name),
+                    escape(&q.to_string())
+                )?,
+                (Some(name), None) => write!(w, "{}", escape(name))?,
+                (None, Some(q)) => write!(
+                    w,
+                    r#"<span class="quantity">{}</span>"#,
+                    escape(&q.to_string())
+                )?,
+                (None, None) => {}
+            }
+            write!(w, "</span>")
+        }
+        Item::InlineQuantity { index } => {
+            let q = &recipe.inline_quantities[*index];
+            write_start(w, "inline-quantity", *index, Some(q))?;
+            write!(w, "{}</span>", escape(&q.to_string()))
+        }
+        Item::Temperature { index } => {
+            let temp = &recipe.temperatures[*index];
+            write!(w, r#"<span class="temperature" data-index="{index}""#)?;
+            write!(
+                w,
+                r#" data-quantity="{}" data-unit="{}""#,
+                escape(&value_attr(&temp.value)),
+                temp.scale.unit_name()
+            )?;
+            if let Some(mode) = temp.mode {
+                write!(w, r#" data-mode="{mode}""#)?;
+            }
+            write!(w, ">{}</span>", escape(&temp.to_string()))
+        }
+    }
+}
+
+/// Opening tag of a component with its data attributes
+fn write_start(
+    w: &mut impl Write,
+    class: &str,
+    index: usize,
+    quantity: Option<&Quantity<Value>>,
+) -> io::Result<()> {
+    write!(w, r#"<span class="{class}" data-index="{index}""#)?;
+    if let Some(q) = quantity {
+        write!(w, r#" data-quantity="{}""#, escape(&value_attr(q.value())))?;
+        if let Some(unit) = q.unit() {
+            write!(w, r#" data-unit="{}""#, escape(unit))?;
+        }
+    }
+    write!(w, ">")
+}
+
+/// Value for the `data-quantity` attribute
+///
+/// Numbers are written in full, not as fractions, so they are easy to parse.
+fn value_attr(value: &Value) -> Cow<str> {
+    match value {
+        Value::Number(n) => Cow::Owned(n.value().to_string()),
+        Value::Range { start, end } => Cow::Owned(format!("{}-{}", start.value(), end.value())),
+        Value::Text(t) => Cow::Borrowed(t),
+    }
+}
+
+/// Escapes text for HTML content and attribute values
+fn escape(text: &str) -> Cow<str> {
+    const SPECIAL: &[char] = &['&', '<', '>', '"', '\''];
+    if !text.contains(SPECIAL) {
+        return Cow::Borrowed(text);
+    }
+    let mut s = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '&' => s.push_str("&amp;"),
+            '<' => s.push_str("&lt;"),
+            '>' => s.push_str("&gt;"),
+            '"' => s.push_str("&quot;"),
+            '\'' => s.push_str("&#39;"),
+            c => s.push(c),
+        }
+    }
+    Cow::Owned(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::{Image, ImageTarget};
+    use crate::CooklangParser;
+    use indoc::indoc;
+
+    fn html(input: &str) -> String {
+        let parser = CooklangParser::default();
+        let recipe = parser.parse(input).into_output().unwrap().default_scale();
+        to_string(&recipe, parser.converter())
+    }
+
+    #[test]
+    fn full_recipe() {
+        let out = html(indoc! {"
+            ---
+            title: Pancakes
+            servings: 2
+            ---
+            Mix @flour{200%g} and @eggs{2} in a #bowl.
+
+            > Let it rest.
+
+            == Cook ==
+            Cook in a #pan{} for ~{1-2%min} at 180 °C.
+        "});
+        assert_eq!(
+            out,
+            indoc! {r#"
+                <article class="recipe">
+                <h1>Pancakes</h1>
+                <dl class="metadata">
+                <dt>servings</dt><dd>2</dd>
+                </dl>
+                <section class="ingredients">
+                <h2>Ingredients</h2>
+                <ul>
+                <li data-index="0"><span class="name">flour</span>: <span class="quantity">200 g</span></li>
+                <li data-index="1"><span class="name">eggs</span>: <span class="quantity">2</span></li>
+                </ul>
+                </section>
+                <section class="cookware">
+                <h2>Cookware</h2>
+                <ul>
+                <li data-index="0"><span class="name">bowl</span></li>
+                <li data-index="1"><span class="name">pan</span></li>
+                </ul>
+                </section>
+                <section class="steps">
+                <h2>Steps</h2>
+                <section class="section" data-index="0">
+                <h3>Section 1</h3>
+                <ol>
+                <li value="1">Mix <span class="ingredient" data-index="0" data-quantity="200" data-unit="g">flour <span class="quantity">(200 g)</span></span> and <span class="ingredient" data-index="1" data-quantity="2">eggs <span class="quantity">(2)</span></span> in a <span class="cookware" data-index="0">bowl</span>.</li>
+                </ol>
+                <p>Let it rest.</p>
+                </section>
+                <section class="section" data-index="1">
+                <h3>Cook</h3>
+                <ol>
+                <li value="1">Cook in a <span class="cookware" data-index="1">pan</span> for <span class="timer" data-index="0" data-quantity="1-2" data-unit="min"><span class="quantity">1-2 min</span></span> at <span class="inline-quantity" data-index="0" data-quantity="180" data-unit="°C">180 °C</span>.</li>
+                </ol>
+                </section>
+                </section>
+                </article>
+            "#}
+        );
+    }
+
+    #[test]
+    fn step_images() {
+        let parser = CooklangParser::default();
+        let mut recipe = parser.parse("Mix.\n\nBake.").into_output().unwrap();
+        recipe.attach_images(&[Image {
+            target: ImageTarget::Step {
+                section: 0,
+                step: 2,
+            },
+            path: r#"bake "1".jpg"#.into(),
+        }]);
+        let out = to_string(&recipe.default_scale(), parser.converter());
+        assert!(out.contains(r#"<li value="1">Mix.</li>"#));
+        assert!(out.contains(
+            r#"<li value="2">Bake.<img class="step-image" src="bake &quot;1&quot;.jpg" alt="Step 2"></li>"#
+        ));
+    }
+
+    #[test]
+    fn temperature() {
+        let out = html("Bake at ^{180%°C}(fan).");
+        assert!(out.contains(concat!(
+            r#"<span class="temperature" data-index="0" data-quantity="180" data-unit="celsius""#,
+            r#" data-mode="fan">180 °C (fan)</span>"#
+        )));
+    }
+
+    #[test]
+    fn escaped() {
+        let out = html(r#"Add <b>"salt"</b> & @p&pper{1%<i>}."#);
+        assert!(out.contains(
+            r#"<li value="1">Add &lt;b&gt;&quot;salt&quot;&lt;/b&gt; &amp; <span class="ingredient" data-index="0" data-quantity="1" data-unit="&lt;i&gt;">"#
+        ));
+        assert!(!out.contains("<i>"));
+    }
+}
@@ -0,0 +1,471 @@
+//! schema.org JSON-LD export
+//!
+//! Renders a [`ScaledRecipe`] as a [schema.org `Recipe`](https://schema.org/Recipe),
+//! ready to be embedded in a web page in a
+//! `<script type="application/ld+json">` tag.
+//!
+//! The standard metadata keys are mapped to their schema.org properties:
+//!
+//! | Metadata           | Property                                  |
+//! |--------------------|-------------------------------------------|
+//! | `title`            | `name`                                    |
+//! | `description`      | `description`                             |
+//! | `author`           | `author`, a `Person`                      |
+//! | `source`           | `isBasedOn`, a `CreativeWork`             |
+//! | `time`             | `prepTime`, `cookTime` and `totalTime`    |
+//! | `servings`         | `recipeYield`                             |
+//! | `tags`             | `keywords`                                |
+//! | `cuisine`          | `recipeCuisine`                           |
+//! | `course`           | `recipeCategory`                          |
+//! | `image`            | `image`                                   |
+//!
+//! Times are ISO 8601 durations. The ingredients are grouped with
+//! [`ScaledRecipe::group_ingredients`] into `recipeIngredient` and the steps
+//! are `HowToStep`s in `recipeInstructions`, with the
+//! [`Step::images`](crate::Step::images) as their `image`. When the recipe has named
+//! sections, each is a `HowToSection`.
+//!
+//! ```
+//! # use cooklang::{CooklangParser, export::jsonld};
+//! let parser = CooklangParser::default();
+//! let input = "---\ntitle: Toast\n---\nToast the @bread{2%slices}.";
+//! let recipe = parser.parse(input).into_output().unwrap().default_scale();
+//! let value = jsonld::to_value(&recipe, parser.converter());
+//! assert_eq!(value["@type"], "Recipe");
+//! assert_eq!(value["name"], "Toast");
+//! assert_eq!(value["recipeIngredient"][0], "2 slices bread");
+//! ```
+
+use std::io;
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    convert::Converter,
+    metadata::{CooklangValueExt, NameAndUrl, RecipeTime, StdKey},
+    Content, Item, ScaledRecipe, Section,
+};
+
+/// Builds the JSON-LD value of a recipe
+pub fn to_value(recipe: &ScaledRecipe, converter: &Converter) -> Value {
+    let meta = &recipe.metadata;
+    let mut obj = Map::new();
+    obj.insert("@context".into(), "https://schema.org".into());
+    obj.insert("@type".into(), "Recipe".into());
+
+    if let Some(title) = meta.title() {
+        obj.insert("name".into(), title.into());
+    }
+    if let Some(description) = meta.description() {
+        obj.insert("description".into(), description.into());
+    }
+    if let Some(author) = meta.author() {
+        obj.insert("author".into(), name_and_url("Person", &author));
+    }
+    if let Some(source) = meta.source() {
+        obj.insert("isBasedOn".into(), name_and_url("CreativeWork", &source));
+    }
+    match meta.time(converter) {
+        Some(RecipeTime::Total(total)) => {
+            obj.insert("totalTime".into(), duration(total).into());
+        }
+        Some(
+            time @ RecipeTime::Composed {
+                prep_time,
+                cook_time,
+            },
+        ) => {
+            if let Some(prep_time) = prep_time {
+                obj.insert("prepTime".into(), duration(prep_time).into());
+            }
+            if let Some(cook_time) = cook_time {
+                obj.insert("cookTime".into(), duration(cook_time).into());
+            }
+            obj.insert("totalTime".into(), duration(time.total()).into());
+        }
+        None => {}
+    }
+    if let Some(servings) = meta.servings().and_then(|s| s.first().copied()) {
+        let factor = recipe.scaled_data().map_or(1.0, |d| d.target.factor());
+        let servings = (servings as f64 * factor).round();
+        obj.insert("recipeYield".into(), servings.to_string().into());
+    }
+    if let Some(tags) = meta.tags() {
+        obj.insert("keywords".into(), tags.join(", ").into());
+    }
+    let lists = [
+        (StdKey::Cuisine, "recipeCuisine"),
+        (StdKey::Course, "recipeCategory"),
+    ];
+    for (key, property) in lists {
+        if let Some(value) = meta.get(key).and_then(text_list) {
+            obj.insert(property.into(), value);
+        }
+    }
+    match meta.images().as_deref() {
+        Some([image]) => {
+            obj.insert("image".into(), image.as_ref().into());
+        }
+        Some(images) if !images.is_empty() => {
+            obj.insert("image".into(), images.into());
+        }
+        _ => {}
+    }
+
+    let ingredients = recipe
+        .group_ingredients(converter)
+        .into_iter()
+        .filter(|e| e.ingredient.modifiers().should_be_listed())
+        .map(|e| {
+            let mut s = String::new();
+            if !e.quantity.is_empty() {
+                s.push_str(&e.quantity.to_string());
+                s.push(' ');
+            }
+            s.push_str(&e.ingredient.display_name());
+            if let Some(note) = &e.ingredient.note {
+                s.push_str(", ");
+                s.push_str(note);
+            }
+            Value::String(s)
+        })
+        .collect::<Vec<_>>();
+    if !ingredients.is_empty() {
+        obj.insert("recipeIngredient".into(), ingredients.into());
+    }
+
+    let sections = recipe
+        .sections
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| !recipe.is_section_skipped(index));
+    let instructions = if sections.clone().any(|(_, s)| s.name.is_some()) {
+        sections
+            .filter(|(_, s)| !s.is_empty())
+            .map(|(index, section)| {
+                let name = section
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("Section {}", index + 1));
+                json!({
+                    "@type": "HowToSection",
+                    "name": name,
+                    "itemListElement": section_items(recipe, section),
+                })
+            })
+            .collect()
+    } else {
+        sections
+            .flat_map(|(_, s)| section_items(recipe, s))
+            .collect::<Vec<_>>()
+    };
+    if !instructions.is_empty() {
+        obj.insert("recipeInstructions".into(), instructions.into());
+    }
+
+    Value::Object(obj)
+}
+
+/// Writes a recipe as JSON-LD
+///
+/// `<`, `>` and `&` are escaped as `\u003c`, `\u003e` and `\u0026`, so the
+/// output is safe inside a `<script>` tag. [`to_value`] does not do this.
+pub fn write(
+    recipe: &ScaledRecipe,
+    converter: &Converter,
+    mut w: impl io::Write,
+) -> io::Result<()> {
+    w.write_all(to_string(recipe, converter).as_bytes())
+}
+
+/// Renders a recipe as JSON-LD
+///
+/// Escaped like [`write`].
+pub fn to_string(recipe: &ScaledRecipe, converter: &Converter) -> String {
+    let json =
+        serde_json::to_string_pretty(&to_value(recipe, converter)).expect("serialize json-ld");
+    escape_html(&json)
+}
+
+/// Escapes the characters that can close the `<script>` tag or open a comment
+///
+/// They can only be in JSON strings, where the escape means the same.
+fn escape_html(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        match c {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// ISO 8601 duration from minutes
+fn duration(minutes: u32) -> String {
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    match (hours, minutes) {
+        (0, m) => format!("PT{m}M"),
+        (h, 0) => format!("PT{h}H"),
+        (h, m) => format!("PT{h}H{m}M"),
+    }
+}
+
+fn name_and_url(ty: &str, value: &NameAndUrl) -> Value {
+    let mut obj = Map::new();
+    obj.insert("@type".into(), ty.into());
+    if let Some(name) = value.name() {
+        obj.insert("name".into(), name.into());
+    }
+    if let Some(url) = value.url() {
+        obj.insert("url".into(), url.into());
+    }
+    Value::Object(obj)
+}
+
+/// A single text or a list of them
+fn text_list(value: &serde_yaml::Value) -> Option<Value> {
+    if let Some(s) = value.as_str_like() {
+        return Some(s.into_owned().into());
+    }
+    let list = value
+        .as_sequence()?
+        .iter()
+        .filter_map(|v| v.as_str_like())
+        .map(|s| Value::String(s.into_owned()))
+        .collect::<Vec<_>>();
+    (!list.is_empty()).then_some(Value::Array(list))
+}
+
+fn section_items(recipe: &ScaledRecipe, section: &Section) -> Vec<Value> {
+    section
+        .content
+        .iter()
+        .map(|content| match content {
+            Content::Step(step) if !step.images.is_empty() => json!({
+                "@type": "HowToStep",
+                "text": step_text(recipe, &step.items),
+                "image": step.images,
+            }),
+            Content::Step(step) => json!({
+                "@type": "HowToStep",
+                "text": step_text(recipe, &step.items),
+            }),
+            Content::Text(text) => json!({
+                "@type": "HowToTip",
+                "text": text,
+            }),
+        })
+        .collect()
+}
+
+fn step_text(recipe: &ScaledRecipe, items: &[Item]) -> String {
+    let mut s = String::new();
+    for item in items {
+        match item {
+            Item::Text { value } => s.push_str(value),
+            Item::Ingredient { index } => {
+                let igr = &recipe.ingredients[*index];
+                s.push_str(&igr.display_name());
+                if let Some(q) = &igr.quantity {
+                    s.push_str(&format!(" ({q})"));
+                }
+            }
+            Item::Cookware { index } => s.push_str(recipe.cookware[*index].display_name()),
+            Item::Timer { index } => {
+                let tm = &recipe.timers[*index];
+                match (&tm.name, &tm.quantity) {
+                    (Some(name), Some(q)) => s.push_str(&format!("{name} ({q})")),
+                    (Some(name), None) => s.push_str(name),
+                    (None, Some(q)) => s.push_str(&q.to_string()),
+                    (None, None) => {}
+                }
+            }
+            Item::InlineQuantity { index } => {
+                s.push_str(&recipe.inline_quantities[*index].to_string())
+            }
+            Item::Temperature { index } => s.push_str(&recipe.temperatures[*index].to_string()),
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::{Image, ImageTarget};
+    use crate::scale::SectionScale;
+    use crate::CooklangParser;
+    use indoc::indoc;
+
+    #[test]
+    fn full_recipe() {
+        let parser = CooklangParser::default();
+        let input = indoc! {"
+            ---
+            title: Pancakes
+            description: Fluffy pancakes
+            author: Jane Doe <https://jane.example.com>
+            source: https://example.com/pancakes
+            prep time: 10 min
+            cook time: 1h 5min
+            servings: 2
+            tags: [breakfast, sweet]
+            cuisine: American
+            course: [breakfast, dessert]
+            image: https://example.com/pancakes.jpg
+            ---
+            Mix @flour{200%g}, @milk{300%ml} and @eggs{2}(beaten).
+
+            > Let it rest.
+
+            == Cook ==
+            Cook in a #pan for ~{2%min}.
+        "};
+        let recipe = parser
+            .parse(input)
+            .into_output()
+            .unwrap()
+            .scale_to_servings(4, parser.converter());
+        let value = to_value(&recipe, parser.converter());
+        assert_eq!(
+            value,
+            json!({
+                "@context": "https://schema.org",
+                "@type": "Recipe",
+                "name": "Pancakes",
+                "description": "Fluffy pancakes",
+                "author": {
+                    "@type": "Person",
+                    "name": "Jane Doe",
+                    "url": "https://jane.example.com",
+                },
+                "isBasedOn": {
+                    "@type": "CreativeWork",
+                    "url": "https://example.com/pancakes",
+                },
+                "prepTime": "PT10M",
+                "cookTime": "PT1H5M",
+                "totalTime": "PT1H15M",
+                "recipeYield": "4",
+                "keywords": "breakfast, sweet",
+                "recipeCuisine": "American",
+                "recipeCategory": ["breakfast", "dessert"],
+                "image": "https://example.com/pancakes.jpg",
+                "recipeIngredient": ["400 g flour", "600 ml milk", "4 eggs, beaten"],
+                "recipeInstructions": [
+                    {
+                        "@type": "HowToSection",
+                        "name": "Section 1",
+                        "itemListElement": [
+                            {
+                                "@type": "HowToStep",
+                                "text": "Mix flour (400 g), milk (600 ml) and eggs (4).",
+                            },
+                            { "@type": "HowToTip", "text": "Let it rest." },
+                        ],
+                    },
+                    {
+                        "@type": "HowToSection",
+                        "name": "Cook",
+                        "itemListElement": [
+                            { "@type": "HowToStep", "text": "Cook in a pan for 2 min." },
+                        ],
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn plain_steps() {
+        let parser = CooklangParser::default();
+        let input = ">> time: 90 min\nAdd @salt.\n\nServe.";
+        let recipe = parser.parse(input).into_output().unwrap().default_scale();
+        let value = to_value(&recipe, parser.converter());
+        assert_eq!(value["totalTime"], "PT1H30M");
+        assert_eq!(value["recipeIngredient"], json!(["salt"]));
+        assert_eq!(
+            value["recipeInstructions"],
+            json!([
+                { "@type": "HowToStep", "text": "Add salt." },
+                { "@type": "HowToStep", "text": "Serve." },
+            ])
+        );
+    }
+
+    #[test]
+    fn images() {
+        let parser = CooklangParser::default();
+        let input = "---\npictures: cover.jpg\n---\nAdd @salt.\n\nServe.";
+        let mut recipe = parser.parse(input).into_output().unwrap();
+        recipe.attach_images(&[Image {
+            target: ImageTarget::Step {
+                section: 0,
+                step: 2,
+            },
+            path: "serve.jpg".into(),
+        }]);
+        let value = to_value(&recipe.default_scale(), parser.converter());
+        assert_eq!(value["image"], "cover.jpg");
+        assert_eq!(
+            value["recipeInstructions"],
+            json!([
+                { "@type": "HowToStep", "text": "Add salt." },
+                { "@type": "HowToStep", "text": "Serve.", "image": ["serve.jpg"] },
+            ])
+        );
+    }
+
+    #[test]
+    fn skipped_sections() {
+        let parser = CooklangParser::default();
+        let input = "Cook @pasta{200%g}.\n\n==? Sauce ==\nAdd @tomato{100%g}.";
+        let recipe = parser.parse(input).into_output().unwrap().scale_sections(
+            1.0,
+            &[(1, SectionScale::Skip)],
+            parser.converter(),
+        );
+        let value = to_value(&recipe, parser.converter());
+        assert_eq!(value["recipeIngredient"], json!(["200 g pasta"]));
+        assert_eq!(
+            value["recipeInstructions"],
+            json!([{ "@type": "HowToStep", "text": "Cook pasta (200 g)." }])
+        );
+    }
+
+    #[test]
+    fn script_safe() {
+        let parser = CooklangParser::default();
+        let input = indoc! {"
+            ---
+            title: </script><script>alert(1)</script>
+            ---
+            Salt & pepper <b>now</b>.
+        "};
+        let recipe = parser.parse(input).into_output().unwrap();
+        let recipe = recipe.default_scale();
+        let text = to_string(&recipe, parser.converter());
+        assert!(!text.contains('<'));
+        assert!(!text.contains('>'));
+        assert!(!text.contains('&'));
+        assert!(text.contains(r#""name": "\u003c/script\u003e\u003cscript\u003ealert(1)"#));
+        assert!(text.contains(r#""text": "Salt \u0026 pepper \u003cb\u003enow\u003c/b\u003e.""#));
+        let parsed: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed, to_value(&recipe, parser.converter()));
+
+        let mut written = Vec::new();
+        write(&recipe, parser.converter(), &mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), text);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(duration(0), "PT0M");
+        assert_eq!(duration(45), "PT45M");
+        assert_eq!(duration(120), "PT2H");
+        assert_eq!(duration(135), "PT2H15M");
+    }
+}
@@ -0,0 +1,279 @@
+//! LaTeX export
+//!
+//! Renders a [`ScaledRecipe`] as a standalone LaTeX document, ready to build
+//! a PDF with `pdflatex` and only the base classes:
+//!
+//! - The `title` metadata as the heading.
+//! - The rest of the metadata in a `description` list.
+//! - The ingredients and cookware lists.
+//! - The steps of each section in two columns: the ingredients of the step,
+//!   with their quantities, on the left and the step text on the right.
+//!
+//! Each step is a `\recipestep{number}{ingredients}{text}` command, defined in
+//! the preamble, so the layout can be changed there.
+//!
+//! ```
+//! # use cooklang::{CooklangParser, export::latex};
+//! let parser = CooklangParser::default();
+//! let input = "---\ntitle: Toast\n---\nToast the @bread{2%slices}.";
+//! let recipe = parser.parse(input).into_output().unwrap().default_scale();
+//! let tex = latex::to_string(&recipe, parser.converter());
+//! assert!(tex.contains(r"\section*{Toast}"));
+//! assert!(tex.contains(r"\recipestep{1}{\textbf{2 slices} bread}{Toast the bread.}"));
+//! ```
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use crate::{convert::Converter, Content, Item, ScaledRecipe, Section};
+
+use super::{write_document, DocumentFormat, List, ListEntry};
+
+const PREAMBLE: &str = r"\documentclass{article}
+\usepackage[T1]{fontenc}
+\usepackage[utf8]{inputenc}
+
+\newcommand{\recipestep}[3]{%
+  \par\noindent
+  \begin{minipage}[t]{0.3\linewidth}\raggedright\small #2\end{minipage}\hfill
+  \begin{minipage}[t]{0.65\linewidth}\textbf{#1.}~#3\end{minipage}\par\medskip}
+";
+
+/// Writes a recipe as a LaTeX document
+pub fn write(recipe: &ScaledRecipe, converter: &Converter, mut w: impl Write) -> io::Result<()> {
+    write_document::<Latex>(recipe, converter, &mut w)
+}
+
+/// Renders a recipe as a LaTeX document
+pub fn to_string(recipe: &ScaledRecipe, converter: &Converter) -> String {
+    let mut buf = Vec::new();
+    write(recipe, converter, &mut buf).expect("write to vec");
+    String::from_utf8(buf).expect("utf8 latex")
+}
+
+struct Latex;
+
+impl DocumentFormat for Latex {
+    fn escape(text: &str) -> Cow<str> {
+        escape(text)
+    }
+
+    fn write_start(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "{PREAMBLE}")?;
+        writeln!(w, r"\begin{{document}}")
+    }
+
+    fn write_title(w: &mut impl Write, title: &str) -> io::Result<()> {
+        writeln!(w, "\n\\section*{{{title}}}")
+    }
+
+    fn write_metadata(w: &mut impl Write, entries: &[(String, String)]) -> io::Result<()> {
+        writeln!(w, "\n\\begin{{description}}")?;
+        for (key, value) in entries {
+            // braces so a `]` in the key doesn't end the optional argument
+            writeln!(w, "  \\item[{{{key}}}] {value}")?;
+        }
+        writeln!(w, "\\end{{description}}")
+    }
+
+    fn write_list(w: &mut impl Write, list: List, entries: &[ListEntry]) -> io::Result<()> {
+        writeln!(w, "\n\\subsection*{{{}}}\n\\begin{{itemize}}", list.title())?;
+        for entry in entries {
+            write!(w, "  \\item ")?;
+            if let Some(quantity) = &entry.quantity {
+                write!(w, "\\textbf{{{quantity}}} ")?;
+            }
+            write!(w, "{}", entry.name)?;
+            if let Some(note) = &entry.note {
+                write!(w, " ({note})")?;
+            }
+            writeln!(w)?;
+        }
+        writeln!(w, "\\end{{itemize}}")
+    }
+
+    fn write_steps_start(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "\n\\subsection*{{Steps}}")
+    }
+
+    fn write_section(
+        w: &mut impl Write,
+        recipe: &ScaledRecipe,
+        converter: &Converter,
+        _index: usize,
+        heading: Option<&str>,
+        section: &Section,
+    ) -> io::Result<()> {
+        if let Some(heading) = heading {
+            writeln!(w, "\n\\subsubsection*{{{heading}}}")?;
+        }
+        for content in &section.content {
+            match content {
+                Content::Step(step) => {
+                    let callout = recipe
+                        .group_step_ingredients(step, converter)
+                        .iter()
+                        .filter(|e| !e.ingredient.modifiers().is_hidden())
+                        .map(|e| {
+                            let name = escape(&e.ingredient.display_name()).into_owned();
+                            if e.quantity.is_empty() {
+                                name
+                            } else {
+                                format!("\\textbf{{{}}} {name}", escape(&e.quantity.to_string()))
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(r"\\ ");
+                    write!(w, "\n\\recipestep{{{}}}{{{callout}}}{{", step.number)?;
+                    for item in &step.items {
+                        write_item(w, recipe, item)?;
+                    }
+                    writeln!(w, "}}")?;
+                }
+                Content::Text(text) => writeln!(
+                    w,
+                    "\n\\par\\noindent\\emph{{{}}}\\par\\medskip",
+                    escape(text)
+                )?,
+            }
+        }
+        Ok(())
+    }
+
+    fn write_end(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "\n\\end{{document}}")
+    }
+}
+
+/// Step text, the quantities of the ingredients are in the callout
+fn write_item(w: &mut impl Write, recipe: &ScaledRecipe, item: &Item) -> io::Result<()> {
+    match item {
+        Item::Text { value } => write!(w, "{}", escape(value)),
+        Item::Ingredient { index } => {
+            write!(w, "{}", escape(&recipe.ingredients[*index].display_name()))
+        }
+        Item::Cookware { index } => write!(w, "{}", escape(recipe.cookware[*index].display_name())),
+        Item::Timer { index } => {
+            let tm = &recipe.timers[*index];
+            match (&tm.name, &tm.quantity) {
+                (_, Some(q)) => write!(w, "\\textbf{{{}}}", escape(&q.to_string())),
+                (Some(name), None) => write!(w, "{}", escape(name)),
+                (None, None) => Ok(()),
+            }
+        }
+        Item::InlineQuantity { index } => {
+            let q = &recipe.inline_quantities[*index];
+            write!(w, "{}", escape(&q.to_string()))
+        }
+        Item::Temperature { index } => {
+            let temp = &recipe.temperatures[*index];
+            write!(w, "{}", escape(&temp.to_string()))
+        }
+    }
+}
+
+/// Escapes the characters with meaning in LaTeX
+fn escape(text: &str) -> Cow<str> {
+    const SPECIAL: &[char] = &['\\', '{', '}', '$', '&', '#', '%', '_', '~', '^'];
+    if !text.contains(SPECIAL) {
+        return Cow::Borrowed(text);
+    }
+    let mut s = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '\\' => s.push_str(r"\textbackslash{}"),
+            '~' => s.push_str(r"\textasciitilde{}"),
+            '^' => s.push_str(r"\textasciicircum{}"),
+            c if SPECIAL.contains(&c) => {
+                s.push('\\');
+                s.push(c);
+            }
+            c => s.push(c),
+        }
+    }
+    Cow::Owned(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CooklangParser;
+    use indoc::indoc;
+
+    fn tex(input: &str) -> String {
+        let parser = CooklangParser::default();
+        let recipe = parser.parse(input).into_output().unwrap().default_scale();
+        to_string(&recipe, parser.converter())
+    }
+
+    #[test]
+    fn full_recipe() {
+        let out = tex(indoc! {"
+            ---
+            title: Pancakes
+            servings: 2
+            ---
+            Mix @flour{200%g}, @milk{300%ml} and @eggs{2} in a #bowl.
+            Add a bit of @-salt.
+
+            > Let it rest if you have time.
+
+            == Cook ==
+            Cook in a #pan{} for ~{2%min}.
+            Serve with @honey{}(optional).
+        "});
+        let body = out.split_once("\\begin{document}\n").unwrap().1;
+        assert_eq!(
+            body,
+            indoc! {r"
+
+                \section*{Pancakes}
+
+                \begin{description}
+                  \item[{servings}] 2
+                \end{description}
+
+                \subsection*{Ingredients}
+                \begin{itemize}
+                  \item \textbf{200 g} flour
+                  \item \textbf{300 ml} milk
+                  \item \textbf{2} eggs
+                  \item honey (optional)
+                \end{itemize}
+
+                \subsection*{Cookware}
+                \begin{itemize}
+                  \item bowl
+                  \item pan
+                \end{itemize}
+
+                \subsection*{Steps}
+
+                \subsubsection*{Section 1}
+
+                \recipestep{1}{\textbf{200 g} flour\\ \textbf{300 ml} milk\\ \textbf{2} eggs}{Mix flour, milk and eggs in a bowl. Add a bit of salt.}
+
+                \par\noindent\emph{Let it rest if you have time.}\par\medskip
+
+                \subsubsection*{Cook}
+
+                \recipestep{1}{honey}{Cook in a pan for \textbf{2 min}. Serve with honey.}
+
+                \end{document}
+            "}
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        let out = tex("Add 100% of the @salt & pepper{} with ~{1%min}.");
+        assert!(out.contains(r"\item salt \& pepper"));
+        assert!(out.contains(r"{Add 100\% of the salt \& pepper with \textbf{1 min}.}"));
+        let out = tex(">> see [1]: page 2\nToast.");
+        assert!(out.contains(r"\item[{see [1]}] page 2"));
+        assert_eq!(
+            escape(r"a_b^c~\"),
+            r"a\_b\textasciicircum{}c\textasciitilde{}\textbackslash{}"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Markdown export
+//!
+//! Renders a [`ScaledRecipe`] as a Markdown document:
+//!
+//! - The `title` metadata as the heading.
+//! - The rest of the metadata in a table.
+//! - The ingredients, grouped with [`ScaledRecipe::group_ingredients`].
+//! - The cookware, grouped with [`ScaledRecipe::group_cookware`].
+//! - The numbered steps of each section, with the components and their
+//!   quantities inline, and the [`Step::images`](crate::Step::images) below.
+//!
+//! ```
+//! # use cooklang::{CooklangParser, export::markdown};
+//! let parser = CooklangParser::default();
+//! let input = "---\ntitle: Toast\n---\nToast the @bread{2%slices}.";
+//! let recipe = parser.parse(input).into_output().unwrap().default_scale();
+//! let md = markdown::to_string(&recipe, parser.converter());
+//! assert!(md.starts_with("# Toast\n"));
+//! assert!(md.contains("1. Toast the bread (2 slices).\n"));
+//! ```
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use crate::{convert::Converter, Content, IngredientReferenceTarget, Item, ScaledRecipe, Section};
+
+use super::{write_document, DocumentFormat, List, ListEntry};
+
+/// Writes a recipe as Markdown
+pub fn write(recipe: &ScaledRecipe, converter: &Converter, mut w: impl Write) -> io::Result<()> {
+    write_document::<Markdown>(recipe, converter, &mut w)
+}
+
+/// Renders a recipe as Markdown
+pub fn to_string(recipe: &ScaledRecipe, converter: &Converter) -> String {
+    let mut buf = Vec::new();
+    write(recipe, converter, &mut buf).expect("write to vec");
+    String::from_utf8(buf).expect("utf8 markdown")
+}
+
+struct Markdown;
+
+impl DocumentFormat for Markdown {
+    fn escape(text: &str) -> Cow<str> {
+        escape(text)
+    }
+
+    fn write_title(w: &mut impl Write, title: &str) -> io::Result<()> {
+        writeln!(w, "# {title}\n")
+    }
+
+    fn write_metadata(w: &mut impl Write, entries: &[(String, String)]) -> io::Result<()> {
+        writeln!(w, "| Key | Value |")?;
+        writeln!(w, "| --- | --- |")?;
+        for (key, value) in entries {
+            writeln!(w, "| {key} | {} |", value.replace('\n', "<br>"))?;
+        }
+        writeln!(w)
+    }
+
+    fn write_list(w: &mut impl Write, list: List, entries: &[ListEntry]) -> io::Result<()> {
+        writeln!(w, "## {}\n", list.title())?;
+        for entry in entries {
+            write!(w, "- {}", entry.name)?;
+            if let Some(quantity) = &entry.quantity {
+                write!(w, ": {quantity}")?;
+            }
+            if let Some(note) = &entry.note {
+                write!(w, " ({note})")?;
+            }
+            writeln!(w)?;
+        }
+        writeln!(w)
+    }
+
+    fn write_steps_start(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "## Steps")
+    }
+
+    fn write_section(
+        w: &mut impl Write,
+        recipe: &ScaledRecipe,
+        _converter: &Converter,
+        _index: usize,
+        heading: Option<&str>,
+        section: &Section,
+    ) -> io::Result<()> {
+        if let Some(heading) = heading {
+            writeln!(w, "\n### {heading}")?;
+        }
+        let mut in_list = false;
+        for content in &section.content {
+            match content {
+                Content::Step(step) => {
+                    if !in_list {
+                        writeln!(w)?;
+                        in_list = true;
+                    }
+                    write!(w, "{}. ", step.number)?;
+                    for item in &step.items {
+                        write_item(w, recipe, section, item)?;
+                    }
+                    writeln!(w)?;
+                    let indent = step.number.to_string().len() + 2;
+                    for image in &step.images {
+                        writeln!(w, "\n{:indent$}![Step {}](<{image}>)", "", step.number)?;
+                    }
+                }
+                Content::Text(text) => {
+                    in_list = false;
+                    writeln!(w, "\n{}", escape_block(text))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_item(
+    w: &mut impl Write,
+    recipe: &ScaledRecipe,
+    section: &Section,
+    item: &Item,
+) -> io::Result<()> {
+    match item {
+        Item::Text { value } => write!(w, "{}", escape(value)),
+        Item::Ingredient { index } => {
+            let igr = &recipe.ingredients[*index];
+            write!(w, "{}", escape(&igr.display_name()))?;
+            if let Some(q) = &igr.quantity {
+                write!(w, " ({})", escape(&q.to_string()))?;
+            }
+            match igr.relation.references_to() {
+                Some((index, IngredientReferenceTarget::Step)) => {
+                    let step = section.content[index].unwrap_step();
+                    write!(w, " (from step {})", step.number)
+                }
+                Some((index, IngredientReferenceTarget::Section)) => {
+                    write!(w, " (from section {})", index + 1)
+                }
+                _ => Ok(()),
+            }
+        }
+        Item::Cookware { index } => {
+            let cw = &recipe.cookware[*index];
+            write!(w, "{}", escape(cw.display_name()))?;
+            if let Some(q) = &cw.quantity {
+                write!(w, " ({})", escape(&q.to_string()))?;
+            }
+            Ok(())
+        }
+        Item::Timer { index } => {
+            let tm = &recipe.timers[*index];
+            match (&tm.name, &tm.quantity) {
+                (Some(name), Some(q)) => {
+                    write!(w, "{} ({})", escape(name), escape(&q.to_string()))
+                }
+                (Some(name), None) => write!(w, "{}", escape(name)),
+                (None, Some(q)) => write!(w, "{}", escape(&q.to_string())),
+                (None, None) => Ok(()),
+            }
+        }
+        Item::InlineQuantity { index } => {
+            let q = &recipe.inline_quantities[*index];
+            write!(w, "{}", escape(&q.to_string()))
+        }
+        Item::Temperature { index } => {
+            let temp = &recipe.temperatures[*index];
+            write!(w, "{}", escape(&temp.to_string()))
+        }
+    }
+}
+
+/// Escapes the characters with meaning in Markdown
+fn escape(text: &str) -> Cow<str> {
+    const SPECIAL: &[char] = &['\\', '`', '*', '_', '[', ']', '<', '>', '#', '|', '~'];
+    if !text.contains(SPECIAL) {
+        return Cow::Borrowed(text);
+    }
+    let mut s = String::with_capacity(text.len() + 4);
+    for c in text.chars() {
+        if SPECIAL.contains(&c) {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    Cow::Owned(s)
+}
+
+/// Escapes a paragraph so it's not read as a list
+fn escape_block(text: &str) -> Cow<str> {
+    let text = escape(text);
+    if text.starts_with(['-', '+']) {
+        Cow::Owned(format!("\\{text}"))
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::{Image, ImageTarget};
+    use crate::scale::SectionScale;
+    use crate::CooklangParser;
+    use indoc::indoc;
+
+    fn md(input: &str) -> String {
+        let parser = CooklangParser::default();
+        let recipe = parser.parse(input).into_output().unwrap().default_scale();
+        to_string(&recipe, parser.converter())
+    }
+
+    #[test]
+    fn full_recipe() {
+        let out = md(indoc! {"
+            ---
+            title: Pancakes
+            servings: 2
+            tags: [breakfast, sweet]
+            ---
+            Mix @flour{200%g}, @milk{300%ml} and @eggs{2} in a #bowl.
+            Add more @&flour{50%g} and a bit of @-salt.
+
+            > Let it rest if you have time.
+
+            == Cook ==
+            Cook in a #pan{} for ~{2%min} (flip it after ~flip{1%min}).
+            Serve with @honey{}(optional).
+        "});
+        assert_eq!(
+            out,
+            indoc! {"
+                # Pancakes
+
+                | Key | Value |
+                | --- | --- |
+                | servings | 2 |
+                | tags | breakfast, sweet |
+
+                ## Ingredients
+
+                - flour: 250 g
+                - milk: 300 ml
+                - eggs: 2
+                - honey (optional)
+
+                ## Cookware
+
+                - bowl
+                - pan
+
+                ## Steps
+
+                ### Section 1
+
+                1. Mix flour (200 g), milk (300 ml) and eggs (2) in a bowl. Add more flour (50 g) and a bit of salt.
+
+                Let it rest if you have time.
+
+                ### Cook
+
+                1. Cook in a pan for 2 min (flip it after flip (1 min)). Serve with honey.
+            "}
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        let out = md("Add *lots* of @[salt]{} and #pipe|tube{}.");
+        assert_eq!(
+            out,
+            indoc! {r"
+                ## Ingredients
+
+                - \[salt\]
+
+                ## Cookware
+
+                - tube
+
+                ## Steps
+
+                1. Add \*lots\* of \[salt\] and tube.
+            "}
+        );
+    }
+
+    #[test]
+    fn step_images() {
+        let parser = CooklangParser::default();
+        let mut recipe = parser.parse("Mix.\n\nBake.").into_output().unwrap();
+        recipe.attach_images(&[Image {
+            target: ImageTarget::Step {
+                section: 0,
+                step: 1,
+            },
+            path: "images/Bread 1.jpg".into(),
+        }]);
+        let out = to_string(&recipe.default_scale(), parser.converter());
+        assert_eq!(
+            out,
+            indoc! {"
+                ## Steps
+
+                1. Mix.
+
+                   ![Step 1](<images/Bread 1.jpg>)
+                2. Bake.
+            "}
+        );
+    }
+
+    #[test]
+    fn skipped_sections() {
+        let parser = CooklangParser::default();
+        let input = "Cook @pasta{200%g}.\n\n==? Sauce ==\nAdd @tomato{100%g}.";
+        let recipe = parser.parse(input).into_output().unwrap().scale_sections(
+            1.0,
+            &[(1, SectionScale::Skip)],
+            parser.converter(),
+        );
+        let out = to_string(&recipe, parser.converter());
+        assert!(out.contains("1. Cook pasta (200 g).\n"));
+        assert!(!out.contains("Sauce"));
+        assert!(!out.contains("tomato"));
+    }
+
+    #[test]
+    fn references() {
+        let out = md(indoc! {"
+            Mix @flour{} and @water{}.
+
+            Knead the @&(~1)dough{}.
+        "});
+        assert!(out.contains("2. Knead the dough (from step 1).\n"));
+    }
+}
@@ -0,0 +1,209 @@
+//! Export recipes to other formats
+//!
+//! - [`markdown`] for static sites and notes.
+//! - [`html`] for web pages, with markup to make the components interactive.
+//! - [`jsonld`] for schema.org `Recipe` metadata in web pages.
+//! - [`latex`] and [`typst`] for printable documents.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use crate::{
+    convert::Converter,
+    metadata::{CooklangValueExt, StdKey},
+    ScaledRecipe, Section,
+};
+
+pub mod html;
+pub mod jsonld;
+pub mod latex;
+pub mod markdown;
+pub mod typst;
+
+/// Metadata value as plain text
+///
+/// Lists are joined with commas and maps written as `key: value` pairs.
+pub(crate) fn metadata_value_text(value: &serde_yaml::Value) -> Cow<str> {
+    use serde_yaml::Value;
+    if let Some(s) = value.as_str_like() {
+        return s;
+    }
+    match value {
+        Value::Null => Cow::Borrowed(""),
+        Value::Bool(b) => Cow::Owned(b.to_string()),
+        Value::Sequence(seq) => Cow::Owned(
+            seq.iter()
+                .map(metadata_value_text)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Value::Mapping(map) => Cow::Owned(
+            map.iter()
+                .map(|(k, v)| format!("{}: {}", metadata_value_text(k), metadata_value_text(v)))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Value::Tagged(tagged) => metadata_value_text(&tagged.value),
+        Value::String(_) | Value::Number(_) => unreachable!("str like"),
+    }
+}
+
+/// A document format, the parts that change between the exports
+///
+/// The walk over the recipe is in [`write_document`]. The text given to the
+/// `write_*` functions is already escaped with [`DocumentFormat::escape`].
+pub(crate) trait DocumentFormat {
+    /// Escapes text for the format
+    fn escape(text: &str) -> Cow<str>;
+
+    /// Start of the document
+    fn write_start(_w: &mut impl Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// The `title` metadata
+    fn write_title(w: &mut impl Write, title: &str) -> io::Result<()>;
+
+    /// The rest of the metadata, never empty
+    fn write_metadata(w: &mut impl Write, entries: &[(String, String)]) -> io::Result<()>;
+
+    /// Ingredients or cookware list, never empty
+    fn write_list(w: &mut impl Write, list: List, entries: &[ListEntry]) -> io::Result<()>;
+
+    /// Start of the steps, only if there is a section to write
+    fn write_steps_start(w: &mut impl Write) -> io::Result<()>;
+
+    /// A section, `heading` is the escaped name or `Section N` if the recipe
+    /// has more than one
+    fn write_section(
+        w: &mut impl Write,
+        recipe: &ScaledRecipe,
+        converter: &Converter,
+        index: usize,
+        heading: Option<&str>,
+        section: &Section,
+    ) -> io::Result<()>;
+
+    /// End of the steps
+    fn write_steps_end(_w: &mut impl Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// End of the document
+    fn write_end(_w: &mut impl Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Which list a [`ListEntry`] is from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum List {
+    Ingredients,
+    Cookware,
+}
+
+impl List {
+    /// Heading of the list
+    pub fn title(self) -> &'static str {
+        match self {
+            List::Ingredients => "Ingredients",
+            List::Cookware => "Cookware",
+        }
+    }
+}
+
+/// An entry of the ingredients or cookware list, with the text escaped
+pub(crate) struct ListEntry {
+    /// Index in the recipe ingredients or cookware
+    pub index: usize,
+    pub name: String,
+    pub quantity: Option<String>,
+    pub note: Option<String>,
+}
+
+/// Writes the parts of a recipe shared by the document exports
+///
+/// The title, the rest of the metadata, the grouped ingredients and cookware
+/// and the non empty sections. Sections skipped when scaling are left out.
+pub(crate) fn write_document<F: DocumentFormat>(
+    recipe: &ScaledRecipe,
+    converter: &Converter,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let escape = |text: &str| F::escape(text).into_owned();
+
+    F::write_start(w)?;
+
+    if let Some(title) = recipe.metadata.title() {
+        F::write_title(w, &F::escape(title))?;
+    }
+
+    let metadata = recipe
+        .metadata
+        .map
+        .iter()
+        .filter(|(key, _)| key.as_str() != Some(StdKey::Title.as_ref()))
+        .map(|(key, value)| {
+            (
+                escape(&metadata_value_text(key)),
+                escape(&metadata_value_text(value)),
+            )
+        })
+        .collect::<Vec<_>>();
+    if !metadata.is_empty() {
+        F::write_metadata(w, &metadata)?;
+    }
+
+    let grouped = recipe.group_ingredients(converter);
+    let ingredients = grouped
+        .iter()
+        .filter(|e| e.ingredient.modifiers().should_be_listed())
+        .map(|e| ListEntry {
+            index: e.index,
+            name: escape(&e.ingredient.display_name()),
+            quantity: (!e.quantity.is_empty()).then(|| escape(&e.quantity.to_string())),
+            note: e.ingredient.note.as_deref().map(escape),
+        })
+        .collect::<Vec<_>>();
+    if !ingredients.is_empty() {
+        F::write_list(w, List::Ingredients, &ingredients)?;
+    }
+
+    let grouped = recipe.group_cookware();
+    let cookware = grouped
+        .iter()
+        .filter(|e| e.cookware.modifiers().should_be_listed())
+        .map(|e| ListEntry {
+            index: e.index,
+            name: escape(e.cookware.display_name()),
+            quantity: (!e.amount.is_empty()).then(|| escape(&e.amount.to_string())),
+            note: e.cookware.note.as_deref().map(escape),
+        })
+        .collect::<Vec<_>>();
+    if !cookware.is_empty() {
+        F::write_list(w, List::Cookware, &cookware)?;
+    }
+
+    let sections = recipe
+        .sections
+        .iter()
+        .enumerate()
+        .filter(|&(index, section)| !section.is_empty() && !recipe.is_section_skipped(index))
+        .collect::<Vec<_>>();
+    if !sections.is_empty() {
+        F::write_steps_start(w)?;
+        for (index, section) in sections {
+            let heading = match &section.name {
+                Some(name) => Some(F::escape(name)),
+                None if recipe.sections.len() > 1 => {
+                    Some(Cow::Owned(format!("Section {}", index + 1)))
+                }
+                None => None,
+            };
+            F::write_section(w, recipe, converter, index, heading.as_deref(), section)?;
+        }
+        F::write_steps_end(w)?;
+    }
+
+    F::write_end(w)
+}
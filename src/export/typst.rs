@@ -0,0 +1,255 @@
+//! Typst export
+//!
+//! Renders a [`ScaledRecipe`] as a [Typst](https://typst.app/) document, with
+//! the same parts and layout as the [`latex`](super::latex) export:
+//!
+//! - The `title` metadata as the heading.
+//! - The rest of the metadata in a term list.
+//! - The ingredients and cookware lists.
+//! - The steps of each section in two columns: the ingredients of the step,
+//!   with their quantities, on the left and the step text on the right.
+//!
+//! Each step is a `#recipe-step(number, ingredients, text)` call, a function
+//! defined at the start of the document, so the layout can be changed there.
+//!
+//! ```
+//! # use cooklang::{CooklangParser, export::typst};
+//! let parser = CooklangParser::default();
+//! let input = "---\ntitle: Toast\n---\nToast the @bread{2%slices}.";
+//! let recipe = parser.parse(input).into_output().unwrap().default_scale();
+//! let typ = typst::to_string(&recipe, parser.converter());
+//! assert!(typ.contains("= Toast\n"));
+//! assert!(typ.contains("#recipe-step(1, [*2 slices* bread], [Toast the bread.])"));
+//! ```
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use crate::{convert::Converter, Content, Item, ScaledRecipe, Section};
+
+use super::{write_document, DocumentFormat, List, ListEntry};
+
+const PREAMBLE: &str = "#let recipe-step(number, ingredients, body) = block(
+  below: 1em,
+  grid(
+    columns: (1fr, 2fr),
+    column-gutter: 1em,
+    text(size: 0.9em, ingredients),
+    [*#number.* #body],
+  ),
+)
+";
+
+/// Writes a recipe as a Typst document
+pub fn write(recipe: &ScaledRecipe, converter: &Converter, mut w: impl Write) -> io::Result<()> {
+    write_document::<Typst>(recipe, converter, &mut w)
+}
+
+/// Renders a recipe as a Typst document
+pub fn to_string(recipe: &ScaledRecipe, converter: &Converter) -> String {
+    let mut buf = Vec::new();
+    write(recipe, converter, &mut buf).expect("write to vec");
+    String::from_utf8(buf).expect("utf8 typst")
+}
+
+struct Typst;
+
+impl DocumentFormat for Typst {
+    fn escape(text: &str) -> Cow<str> {
+        escape(text)
+    }
+
+    fn write_start(w: &mut impl Write) -> io::Result<()> {
+        write!(w, "{PREAMBLE}")
+    }
+
+    fn write_title(w: &mut impl Write, title: &str) -> io::Result<()> {
+        writeln!(w, "\n= {title}")
+    }
+
+    fn write_metadata(w: &mut impl Write, entries: &[(String, String)]) -> io::Result<()> {
+        writeln!(w)?;
+        for (key, value) in entries {
+            writeln!(w, "/ {key}: {value}")?;
+        }
+        Ok(())
+    }
+
+    fn write_list(w: &mut impl Write, list: List, entries: &[ListEntry]) -> io::Result<()> {
+        writeln!(w, "\n== {}\n", list.title())?;
+        for entry in entries {
+            write!(w, "- ")?;
+            if let Some(quantity) = &entry.quantity {
+                write!(w, "*{quantity}* ")?;
+            }
+            write!(w, "{}", entry.name)?;
+            if let Some(note) = &entry.note {
+                write!(w, " ({note})")?;
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+
+    fn write_steps_start(w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "\n== Steps")
+    }
+
+    fn write_section(
+        w: &mut impl Write,
+        recipe: &ScaledRecipe,
+        converter: &Converter,
+        _index: usize,
+        heading: Option<&str>,
+        section: &Section,
+    ) -> io::Result<()> {
+        if let Some(heading) = heading {
+            writeln!(w, "\n=== {heading}")?;
+        }
+        for content in &section.content {
+            match content {
+                Content::Step(step) => {
+                    let callout = recipe
+                        .group_step_ingredients(step, converter)
+                        .iter()
+                        .filter(|e| !e.ingredient.modifiers().is_hidden())
+                        .map(|e| {
+                            let name = escape(&e.ingredient.display_name()).into_owned();
+                            if e.quantity.is_empty() {
+                                name
+                            } else {
+                                format!("*{}* {name}", escape(&e.quantity.to_string()))
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(r" \ ");
+                    write!(w, "\n#recipe-step({}, [{callout}], [", step.number)?;
+                    for item in &step.items {
+                        write_item(w, recipe, item)?;
+                    }
+                    writeln!(w, "])")?;
+                }
+                Content::Text(text) => writeln!(w, "\n#emph[{}]", escape(text))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Step text, the quantities of the ingredients are in the callout
+fn write_item(w: &mut impl Write, recipe: &ScaledRecipe, item: &Item) -> io::Result<()> {
+    match item {
+        Item::Text { value } => write!(w, "{}", escape(value)),
+        Item::Ingredient { index } => {
+            write!(w, "{}", escape(&recipe.ingredients[*index].display_name()))
+        }
+        Item::Cookware { index } => write!(w, "{}", escape(recipe.cookware[*index].display_name())),
+        Item::Timer { index } => {
+            let tm = &recipe.timers[*index];
+            match (&tm.name, &tm.quantity) {
+                (_, Some(q)) => write!(w, "*{}*", escape(&q.to_string())),
+                (Some(name), None) => write!(w, "{}", escape(name)),
+                (None, None) => Ok(()),
+            }
+        }
+        Item::InlineQuantity { index } => {
+            let q = &recipe.inline_quantities[*index];
+            write!(w, "{}", escape(&q.to_string()))
+        }
+        Item::Temperature { index } => {
+            let temp = &recipe.temperatures[*index];
+            write!(w, "{}", escape(&temp.to_string()))
+        }
+    }
+}
+
+/// Escapes the characters with meaning in Typst markup
+fn escape(text: &str) -> Cow<str> {
+    const SPECIAL: &[char] = &[
+        '\\', '#', '*', '_', '`', '$', '<', '>', '@', '[', ']', '~', '/', '=', '-', '+',
+    ];
+    if !text.contains(SPECIAL) {
+        return Cow::Borrowed(text);
+    }
+    let mut s = String::with_capacity(text.len() + 4);
+    for c in text.chars() {
+        if SPECIAL.contains(&c) {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    Cow::Owned(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CooklangParser;
+    use indoc::indoc;
+
+    fn typ(input: &str) -> String {
+        let parser = CooklangParser::default();
+        let recipe = parser.parse(input).into_output().unwrap().default_scale();
+        to_string(&recipe, parser.converter())
+    }
+
+    #[test]
+    fn full_recipe() {
+        let out = typ(indoc! {"
+            ---
+            title: Pancakes
+            servings: 2
+            ---
+            Mix @flour{200%g}, @milk{300%ml} and @eggs{2} in a #bowl.
+            Add a bit of @-salt.
+
+            > Let it rest if you have time.
+
+            == Cook ==
+            Cook in a #pan{} for ~{2%min}.
+            Serve with @honey{}(optional).
+        "});
+        let body = out.strip_prefix(PREAMBLE).unwrap();
+        assert_eq!(
+            body,
+            indoc! {r"
+
+                = Pancakes
+
+                / servings: 2
+
+                == Ingredients
+
+                - *200 g* flour
+                - *300 ml* milk
+                - *2* eggs
+                - honey (optional)
+
+                == Cookware
+
+                - bowl
+                - pan
+
+                == Steps
+
+                === Section 1
+
+                #recipe-step(1, [*200 g* flour \ *300 ml* milk \ *2* eggs], [Mix flour, milk and eggs in a bowl. Add a bit of salt.])
+
+                #emph[Let it rest if you have time.]
+
+                === Cook
+
+                #recipe-step(1, [honey], [Cook in a pan for *2 min*. Serve with honey.])
+            "}
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        let out = typ("Add the @[salt]_{} with a *spoon*.");
+        assert!(out.contains(r"- \[salt\]\_"));
+        assert!(out.contains(r"[Add the \[salt\]\_ with a \*spoon\*.]"));
+        assert_eq!(escape("1/2 - #3"), r"1\/2 \- \#3");
+    }
+}
@@ -0,0 +1,377 @@
+//! Syntax highlighting
+//!
+//! [`highlight`] classifies the parts of a recipe, so every editor highlights
+//! it the same way.
+//!
+//! ```
+//! use cooklang::{highlight::{highlight, HighlightKind}, Extensions};
+//!
+//! let input = "Add @flour{100%g}. -- comment";
+//! let highlights = highlight(input, Extensions::all());
+//! let kinds = highlights
+//!     .iter()
+//!     .map(|h| (&input[h.span.range()], h.kind))
+//!     .collect::<Vec<_>>();
+//! assert_eq!(
+//!     kinds,
+//!     [
+//!         ("@", HighlightKind::Marker),
+//!         ("flour", HighlightKind::Ingredient),
+//!         ("{", HighlightKind::Marker),
+//!         ("100", HighlightKind::Quantity),
+//!         ("%", HighlightKind::Marker),
+//!         ("g", HighlightKind::Unit),
+//!         ("}", HighlightKind::Marker),
+//!         ("-- comment", HighlightKind::Comment),
+//!     ]
+//! );
+//! ```
+
+use serde::Serialize;
+
+use crate::{
+    lexer::TokenKind,
+    located::Located,
+    parser::{self, Event, PullParser, Quantity, Token},
+    span::Span,
+    Extensions,
+};
+
+/// What a [`Highlight`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, strum::Display)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+#[non_exhaustive]
+pub enum HighlightKind {
+    /// Ingredient name or alias
+    Ingredient,
+    /// Cookware name or alias
+    Cookware,
+    /// Timer name
+    Timer,
+    /// Value of a quantity
+    Quantity,
+    /// Unit of a quantity
+    Unit,
+    /// Note of a component, without the parentheses
+    Note,
+    /// Component modifiers, like `&` or `?`, and intermediate references
+    Modifier,
+    /// Syntax of a component or a metadata entry, like `@`, `{`, `%` or `>>`
+    Marker,
+    /// Metadata key
+    MetadataKey,
+    /// Metadata value
+    MetadataValue,
+    /// YAML frontmatter, without the fences
+    Frontmatter,
+    /// Section header, the `=` and the name
+    Section,
+    /// Line and block comments
+    Comment,
+}
+
+/// A highlighted part of the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Highlight {
+    pub span: Span,
+    pub kind: HighlightKind,
+}
+
+/// Classifies the parts of a recipe for highlighting
+///
+/// The highlights are in order and do not overlap. Plain text is not
+/// highlighted.
+///
+/// Only the syntax is highlighted, so it works with invalid recipes and is
+/// cheaper than a full parse. Inline quantities are not highlighted, as
+/// finding them needs the units of a [`Converter`](crate::Converter).
+pub fn highlight(input: &str, extensions: Extensions) -> Vec<Highlight> {
+    let mut hl = Highlighter {
+        input,
+        highlights: Vec::new(),
+        components: Vec::new(),
+    };
+    for event in PullParser::new(input, extensions) {
+        hl.event(event);
+    }
+    hl.highlights.retain(|h| !h.span.is_empty());
+    hl.highlights.sort_unstable_by_key(|h| h.span.start());
+
+    let tokens = parser::token_stream(input).collect::<Vec<_>>();
+    let mut syntax = Vec::new();
+    for line in tokens.split_inclusive(|t| t.kind == TokenKind::Newline) {
+        hl.line(line, &mut syntax);
+    }
+
+    hl.highlights.extend(syntax);
+    hl.highlights.sort_unstable_by_key(|h| h.span.start());
+    hl.highlights
+}
+
+struct Highlighter<'i> {
+    input: &'i str,
+    highlights: Vec<Highlight>,
+    /// Spans of components, to highlight the markers inside
+    components: Vec<Span>,
+}
+
+impl Highlighter<'_> {
+    /// Adds a highlight without the surrounding whitespace
+    fn push(&mut self, span: Span, kind: HighlightKind) {
+        let text = self.input[span.range()].trim_start();
+        let start = span.end() - text.len();
+        let span = Span::new(start, start + text.trim_end().len());
+        self.highlights.push(Highlight { span, kind });
+    }
+
+    fn quantity(&mut self, quantity: Option<&Located<Quantity>>) {
+        if let Some(q) = quantity {
+            self.push(q.value.span(), HighlightKind::Quantity);
+            if let Some(unit) = &q.unit {
+                self.push(unit.span(), HighlightKind::Unit);
+            }
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::YAMLFrontMatter(text) => self.push(text.span(), HighlightKind::Frontmatter),
+            Event::Metadata { key, value } => {
+                self.push(key.span(), HighlightKind::MetadataKey);
+                self.push(value.span(), HighlightKind::MetadataValue);
+            }
+            Event::Section {
+                name: Some(name), ..
+            } => self.push(name.span(), HighlightKind::Section),
+            Event::Ingredient(igr) => {
+                self.components.push(igr.span());
+                // includes the intermediate data
+                self.push(igr.modifiers.span(), HighlightKind::Modifier);
+                self.push(igr.name.span(), HighlightKind::Ingredient);
+                if let Some(alias) = &igr.alias {
+                    self.push(alias.span(), HighlightKind::Ingredient);
+                }
+                self.quantity(igr.quantity.as_ref());
+                if let Some(note) = &igr.note {
+                    self.push(note.span(), HighlightKind::Note);
+                }
+            }
+            Event::Cookware(cw) => {
+                self.components.push(cw.span());
+                self.push(cw.modifiers.span(), HighlightKind::Modifier);
+                self.push(cw.name.span(), HighlightKind::Cookware);
+                if let Some(alias) = &cw.alias {
+                    self.push(alias.span(), HighlightKind::Cookware);
+                }
+                if let Some(q) = &cw.quantity {
+                    self.push(q.value.span(), HighlightKind::Quantity);
+                }
+                if let Some(note) = &cw.note {
+                    self.push(note.span(), HighlightKind::Note);
+                }
+            }
+            Event::Timer(tm) => {
+                self.components.push(tm.span());
+                if let Some(name) = &tm.name {
+                    self.push(name.span(), HighlightKind::Timer);
+                }
+                self.quantity(tm.quantity.as_ref());
+            }
+            Event::Temperature(temp) => {
+                self.components.push(temp.span());
+                self.quantity(Some(&temp.quantity));
+                if let Some(mode) = &temp.mode {
+                    self.push(mode.span(), HighlightKind::Note);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn is_highlighted(&self, span: Span) -> bool {
+        let i = self
+            .highlights
+            .partition_point(|h| h.span.end() <= span.start());
+        self.highlights
+            .get(i)
+            .is_some_and(|h| h.span.start() < span.end())
+    }
+
+    fn in_component(&self, span: Span) -> bool {
+        // components are in order and do not overlap
+        let i = self
+            .components
+            .partition_point(|c| c.start() <= span.start());
+        i > 0 && span.end() <= self.components[i - 1].end()
+    }
+
+    /// Highlights the comments and markers of a line
+    fn line(&self, line: &[Token], syntax: &mut Vec<Highlight>) {
+        let is_section = is_section_line(line);
+        let is_metadata = match (line.first(), line.last()) {
+            (Some(first), Some(last)) if first.kind == TokenKind::MetadataStart => {
+                self.highlights.iter().any(|h| {
+                    h.kind == HighlightKind::MetadataKey
+                        && first.span.start() <= h.span.start()
+                        && h.span.end() <= last.span.end()
+                })
+            }
+            _ => false,
+        };
+
+        let mut prev: Option<&Token> = None;
+        for token in line {
+            let is_repeated = prev.is_some_and(|p| p.kind == token.kind);
+            prev = Some(token);
+            if self.is_highlighted(token.span) {
+                continue;
+            }
+            let kind = match token.kind {
+                TokenKind::LineComment | TokenKind::BlockComment => HighlightKind::Comment,
+                TokenKind::Whitespace | TokenKind::Newline => continue,
+                _ if is_section => HighlightKind::Section,
+                TokenKind::MetadataStart | TokenKind::Colon if is_metadata => HighlightKind::Marker,
+                _ if self.in_component(token.span) => HighlightKind::Marker,
+                _ => continue,
+            };
+            // join runs of the same token, like `==`
+            match syntax.last_mut() {
+                Some(last)
+                    if is_repeated
+                        && last.kind == kind
+                        && last.span.end() == token.span.start() =>
+                {
+                    last.span = Span::new(last.span.start(), token.span.end());
+                }
+                _ => syntax.push(Highlight {
+                    span: token.span,
+                    kind,
+                }),
+            }
+        }
+    }
+}
+
+/// Checks if a line is a valid section header
+///
+/// Same as the parser: `=`s, the name, optional `=`s and then only
+/// whitespace or comments.
+fn is_section_line(line: &[Token]) -> bool {
+    let mut kinds = line
+        .iter()
+        .map(|t| t.kind)
+        .filter(|k| *k != TokenKind::Newline)
+        .peekable();
+    if kinds.peek() != Some(&TokenKind::Eq) {
+        return false;
+    }
+    while kinds.next_if_eq(&TokenKind::Eq).is_some() {}
+    while kinds.next_if(|k| *k != TokenKind::Eq).is_some() {}
+    while kinds.next_if_eq(&TokenKind::Eq).is_some() {}
+    kinds.all(|k| {
+        matches!(
+            k,
+            TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use test_case::test_case;
+    use HighlightKind::*;
+
+    fn kinds(input: &str) -> Vec<(&str, HighlightKind)> {
+        highlight(input, Extensions::all())
+            .into_iter()
+            .map(|h| (&input[h.span.range()], h.kind))
+            .collect()
+    }
+
+    #[test]
+    fn components() {
+        assert_eq!(
+            kinds("@&(~1)dough|masa{=2%kg}(rested) #pot{2}(big) ~rest{1-2%h}"),
+            [
+                ("@", Marker),
+                ("&(~1)", Modifier),
+                ("dough", Ingredient),
+                ("|", Marker),
+                ("masa", Ingredient),
+                ("{", Marker),
+                ("=", Marker),
+                ("2", Quantity),
+                ("%", Marker),
+                ("kg", Unit),
+                ("}", Marker),
+                ("(", Marker),
+                ("rested", Note),
+                (")", Marker),
+                ("#", Marker),
+                ("pot", Cookware),
+                ("{", Marker),
+                ("2", Quantity),
+                ("}", Marker),
+                ("(", Marker),
+                ("big", Note),
+                (")", Marker),
+                ("~", Marker),
+                ("rest", Timer),
+                ("{", Marker),
+                ("1-2", Quantity),
+                ("%", Marker),
+                ("h", Unit),
+                ("}", Marker),
+            ]
+        );
+    }
+
+    #[test]
+    fn metadata_and_comments() {
+        let input = indoc! {"
+            >> servings: 2
+            [- block
+            comment -]
+            Mix. -- line
+        "};
+        assert_eq!(
+            kinds(input),
+            [
+                (">>", Marker),
+                ("servings", MetadataKey),
+                (":", Marker),
+                ("2", MetadataValue),
+                ("[- block\ncomment -]", Comment),
+                ("-- line", Comment),
+            ]
+        );
+    }
+
+    #[test]
+    fn frontmatter() {
+        let input = "---\nservings: 2\n---\n>> key: value\n";
+        // metadata in the old syntax is a step with a frontmatter
+        assert_eq!(kinds(input), [("servings: 2", Frontmatter)]);
+    }
+
+    #[test_case("= Dough" => vec![("=", Section), ("Dough", Section)] ; "named")]
+    #[test_case("== Dough == -- c" => vec![("==", Section), ("Dough", Section), ("==", Section), ("-- c", Comment)] ; "fenced")]
+    #[test_case("==" => vec![("==", Section)] ; "unnamed")]
+    #[test_case("= = ==" => Vec::<(&str, HighlightKind)>::new() ; "invalid")]
+    fn section(input: &str) -> Vec<(&str, HighlightKind)> {
+        kinds(input)
+    }
+
+    #[test]
+    fn no_overlaps() {
+        let input = include_str!("../benches/complex_test_recipe.cook");
+        let highlights = highlight(input, Extensions::all());
+        assert!(!highlights.is_empty());
+        for w in highlights.windows(2) {
+            assert!(w[0].span.end() <= w[1].span.start(), "{w:?}");
+        }
+    }
+}
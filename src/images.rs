@@ -0,0 +1,226 @@
+//! Recipe and step images
+//!
+//! Images are sidecar files next to the recipe, named after it:
+//! - `Recipe.jpg` is an image of the whole recipe.
+//! - `Recipe.3.jpg` is an image of step 3 of the first section.
+//! - `Recipe.2.3.jpg` is an image of step 3 of the second section.
+//!
+//! Step numbers are the ones in [`Step::number`](crate::Step::number) and
+//! section numbers start at 1. The extension is one of [`IMAGE_EXTENSIONS`],
+//! in any case.
+//!
+//! Use [`find_images`] to discover them and [`Recipe::attach_images`] to fill
+//! [`Step::images`](crate::Step::images). Images of the whole recipe can also
+//! be in the metadata, see [`Metadata::images`](crate::Metadata::images).
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{quantity::QuantityValue, Content, Recipe};
+
+/// Extensions recognized as images
+pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "gif", "avif"];
+
+/// What an image is of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageTarget {
+    /// The whole recipe
+    Recipe,
+    /// A step
+    Step {
+        /// Index in [`Recipe::sections`]
+        section: usize,
+        /// [`Step::number`](crate::Step::number) in the section
+        step: u32,
+    },
+}
+
+/// An image file of a recipe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Image {
+    /// What the image is of
+    pub target: ImageTarget,
+    /// Path to the file
+    pub path: PathBuf,
+}
+
+/// Gets what an image file is of from its name
+///
+/// `recipe_name` is the recipe file name without the extension. Returns
+/// `None` if the file is not an image of the recipe.
+///
+/// ```
+/// # use cooklang::images::{image_target, ImageTarget};
+/// assert_eq!(image_target("Pancakes", "Pancakes.jpg"), Some(ImageTarget::Recipe));
+/// assert_eq!(
+///     image_target("Pancakes", "Pancakes.2.3.png"),
+///     Some(ImageTarget::Step { section: 1, step: 3 })
+/// );
+/// assert_eq!(image_target("Pancakes", "Pancakes.cook"), None);
+/// ```
+pub fn image_target(recipe_name: &str, file_name: &str) -> Option<ImageTarget> {
+    let rest = file_name.strip_prefix(recipe_name)?.strip_prefix('.')?;
+    let (numbers, extension) = rest.rsplit_once('.').unwrap_or(("", rest));
+    if !IMAGE_EXTENSIONS
+        .iter()
+        .any(|e| e.eq_ignore_ascii_case(extension))
+    {
+        return None;
+    }
+    if numbers.is_empty() {
+        return Some(ImageTarget::Recipe);
+    }
+    let numbers = numbers
+        .split('.')
+        .map(|n| n.parse::<u32>().ok().filter(|&n| n > 0))
+        .collect::<Option<Vec<_>>>()?;
+    match numbers[..] {
+        [step] => Some(ImageTarget::Step { section: 0, step }),
+        [section, step] => Some(ImageTarget::Step {
+            section: section as usize - 1,
+            step,
+        }),
+        _ => None,
+    }
+}
+
+/// Finds the images of a recipe file
+///
+/// Looks in the directory of the recipe for the files described in the
+/// [module docs](self). They are sorted by target, recipe images first, and
+/// then by path.
+pub fn find_images(recipe_path: &Path) -> std::io::Result<Vec<Image>> {
+    let Some(recipe_name) = recipe_path.file_stem().and_then(|s| s.to_str()) else {
+        return Ok(Vec::new());
+    };
+    let dir = match recipe_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut images = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+        let Some(target) = image_target(recipe_name, file_name) else {
+            continue;
+        };
+        if entry.file_type()?.is_file() {
+            images.push(Image {
+                target,
+                path: recipe_path.with_file_name(file_name),
+            });
+        }
+    }
+    images.sort_by(|a, b| (a.target, &a.path).cmp(&(b.target, &b.path)));
+    Ok(images)
+}
+
+impl<D, V: QuantityValue> Recipe<D, V> {
+    /// Adds step images to [`Step::images`](crate::Step::images)
+    ///
+    /// Images of the whole recipe are ignored. Returns the step images whose
+    /// step does not exist.
+    ///
+    /// ```
+    /// # use std::path::PathBuf;
+    /// # use cooklang::images::{Image, ImageTarget};
+    /// let mut recipe = cooklang::parse("Mix @flour{}.\n\nBake.").into_output().unwrap();
+    /// let image = |step, path: &str| Image {
+    ///     target: ImageTarget::Step { section: 0, step },
+    ///     path: PathBuf::from(path),
+    /// };
+    /// let images = [image(2, "a.jpg"), image(3, "b.jpg")];
+    /// let missing = recipe.attach_images(&images);
+    /// assert_eq!(recipe.sections[0].content[1].unwrap_step().images, ["a.jpg"]);
+    /// assert_eq!(missing, [&images[1]]);
+    /// ```
+    pub fn attach_images<'a>(&mut self, images: &'a [Image]) -> Vec<&'a Image> {
+        let mut missing = Vec::new();
+        for image in images {
+            let ImageTarget::Step { section, step } = image.target else {
+                continue;
+            };
+            let found = self.sections.get_mut(section).and_then(|s| {
+                s.content.iter_mut().find_map(|content| match content {
+                    Content::Step(s) if s.number == step => Some(s),
+                    _ => None,
+                })
+            });
+            match found {
+                Some(s) => s.images.push(image.path.to_string_lossy().into_owned()),
+                None => missing.push(image),
+            }
+        }
+        missing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("Pancakes.jpg" => Some(ImageTarget::Recipe); "recipe")]
+    #[test_case("Pancakes.PNG" => Some(ImageTarget::Recipe); "uppercase extension")]
+    #[test_case("Pancakes.3.webp" => Some(ImageTarget::Step { section: 0, step: 3 }); "step")]
+    #[test_case("Pancakes.2.1.jpeg" => Some(ImageTarget::Step { section: 1, step: 1 }); "section step")]
+    #[test_case("Pancakes.cook" => None; "recipe file")]
+    #[test_case("Pancakes.3.txt" => None; "not an image")]
+    #[test_case("Pancakes.0.jpg" => None; "step 0")]
+    #[test_case("Pancakes.0.1.jpg" => None; "section 0")]
+    #[test_case("Pancakes.a.jpg" => None; "not a number")]
+    #[test_case("Pancakes.1.2.3.jpg" => None; "too many numbers")]
+    #[test_case("Pancakes Deluxe.jpg" => None; "other recipe")]
+    #[test_case("jpg" => None; "only extension")]
+    fn targets(file_name: &str) -> Option<ImageTarget> {
+        image_target("Pancakes", file_name)
+    }
+
+    #[test]
+    fn attach() {
+        let mut recipe = crate::parse(indoc! {"
+            Mix @flour{}.
+
+            > Some text
+
+            Bake.
+
+            == Sauce ==
+            Fry the @onion{}.
+        "})
+        .into_output()
+        .unwrap();
+        let image = |section, step, path: &str| Image {
+            target: ImageTarget::Step { section, step },
+            path: PathBuf::from(path),
+        };
+        let images = [
+            Image {
+                target: ImageTarget::Recipe,
+                path: PathBuf::from("recipe.jpg"),
+            },
+            image(0, 2, "bake.jpg"),
+            image(0, 2, "bake2.jpg"),
+            image(1, 1, "fry.jpg"),
+            image(1, 2, "missing.jpg"),
+            image(2, 1, "missing2.jpg"),
+        ];
+        let missing = recipe.attach_images(&images);
+        assert_eq!(missing, [&images[4], &images[5]]);
+        let step = |section: usize, content: usize| {
+            &recipe.sections[section].content[content]
+                .unwrap_step()
+                .images
+        };
+        assert!(step(0, 0).is_empty());
+        assert_eq!(step(0, 2), &["bake.jpg", "bake2.jpg"]);
+        assert_eq!(step(1, 0), &["fry.jpg"]);
+    }
+}
@@ -0,0 +1,405 @@
+//! schema.org JSON-LD import
+//!
+//! Reads a [schema.org `Recipe`](https://schema.org/Recipe) from JSON-LD, like
+//! the one inside the `<script type="application/ld+json">` tag of a recipe
+//! web page. The recipe can be the top level object, in a list or in a
+//! `@graph`.
+//!
+//! The properties are mapped to the standard metadata keys, the reverse of
+//! [`export::jsonld`](crate::export::jsonld). `recipeIngredient` is placed in
+//! the `recipeInstructions`, which can be plain text, `HowToStep`s or
+//! `HowToSection`s.
+//!
+//! ```
+//! # use cooklang::{Converter, import::jsonld};
+//! let input = r#"{
+//!     "@context": "https://schema.org",
+//!     "@type": "Recipe",
+//!     "name": "Hot milk",
+//!     "recipeIngredient": ["2 cups milk"],
+//!     "recipeInstructions": [{ "@type": "HowToStep", "text": "Warm the milk." }]
+//! }"#;
+//! let imported = jsonld::import(input, &Converter::default())?;
+//! assert_eq!(imported.text, "---\ntitle: Hot milk\n---\n\nWarm the @milk{2%cups}.\n");
+//! # Ok::<(), cooklang::import::ImportError>(())
+//! ```
+
+use serde_json::{Map, Value};
+
+use crate::{convert::Converter, ingredient_line, metadata::StdKey};
+
+use super::{first_number, Draft, DraftContent, DraftSection, ImportError, ImportedRecipe};
+
+type Object = Map<String, Value>;
+
+/// Imports the first recipe found in a JSON-LD document
+pub fn import(input: &str, converter: &Converter) -> Result<ImportedRecipe, ImportError> {
+    let value: Value = serde_json::from_str(input)?;
+    let recipe = find_recipe(&value).ok_or(ImportError::NoRecipe)?;
+    Ok(draft(recipe, converter).into_cooklang())
+}
+
+fn find_recipe(value: &Value) -> Option<&Object> {
+    match value {
+        Value::Object(obj) => {
+            if has_type(obj, "Recipe") {
+                return Some(obj);
+            }
+            obj.get("@graph").and_then(find_recipe)
+        }
+        Value::Array(list) => list.iter().find_map(find_recipe),
+        _ => None,
+    }
+}
+
+fn has_type(obj: &Object, ty: &str) -> bool {
+    match obj.get("@type") {
+        Some(Value::String(s)) => s == ty,
+        Some(Value::Array(types)) => types.iter().any(|t| t.as_str() == Some(ty)),
+        _ => false,
+    }
+}
+
+fn draft(recipe: &Object, converter: &Converter) -> Draft {
+    let mut draft = Draft::default();
+
+    if let Some(name) = recipe.get("name").and_then(text) {
+        draft.set(StdKey::Title, name);
+    }
+    if let Some(description) = recipe.get("description").and_then(text) {
+        draft.set(StdKey::Description, description);
+    }
+    if let Some(author) = recipe.get("author").and_then(name_and_url) {
+        draft.set(StdKey::Author, author);
+    }
+    let source = recipe
+        .get("url")
+        .or_else(|| recipe.get("isBasedOn"))
+        .and_then(name_and_url);
+    if let Some(source) = source {
+        draft.set(StdKey::Source, source);
+    }
+    if let Some(servings) = recipe.get("recipeYield").and_then(servings) {
+        draft.set(StdKey::Servings, servings);
+    }
+    let prep_time = recipe.get("prepTime").and_then(minutes);
+    let cook_time = recipe.get("cookTime").and_then(minutes);
+    if prep_time.is_some() || cook_time.is_some() {
+        if let Some(prep_time) = prep_time {
+            draft.set(StdKey::PrepTime, format!("{prep_time} min"));
+        }
+        if let Some(cook_time) = cook_time {
+            draft.set(StdKey::CookTime, format!("{cook_time} min"));
+        }
+    } else if let Some(total) = recipe.get("totalTime").and_then(minutes) {
+        draft.set(StdKey::Time, format!("{total} min"));
+    }
+    if let Some(keywords) = recipe.get("keywords").and_then(list) {
+        draft.set(StdKey::Tags, keywords);
+    }
+    let lists = [
+        ("recipeCuisine", StdKey::Cuisine),
+        ("recipeCategory", StdKey::Course),
+        ("image", StdKey::Images),
+    ];
+    for (property, key) in lists {
+        match recipe.get(property).and_then(list).as_deref() {
+            Some([single]) => draft.set(key, single.as_str()),
+            Some(many) => draft.set(key, many),
+            None => {}
+        }
+    }
+
+    let ingredients = recipe
+        .get("recipeIngredient")
+        .or_else(|| recipe.get("ingredients"));
+    draft.ingredients = as_slice(ingredients)
+        .iter()
+        .filter_map(text)
+        .map(|line| ingredient_line::parse(&line, converter))
+        .collect();
+
+    let mut main = DraftSection::default();
+    for item in as_slice(recipe.get("recipeInstructions")) {
+        match item {
+            Value::Object(obj) if has_type(obj, "HowToSection") => {
+                let mut section = DraftSection {
+                    name: obj.get("name").and_then(text),
+                    content: Vec::new(),
+                };
+                for item in as_slice(obj.get("itemListElement")) {
+                    instruction(item, &mut section.content);
+                }
+                if !main.content.is_empty() {
+                    draft.sections.push(std::mem::take(&mut main));
+                }
+                draft.sections.push(section);
+            }
+            item => instruction(item, &mut main.content),
+        }
+    }
+    if !main.content.is_empty() {
+        draft.sections.push(main);
+    }
+
+    draft
+}
+
+/// Adds a `HowToStep`, `HowToTip` or text to the section content
+fn instruction(item: &Value, content: &mut Vec<DraftContent>) {
+    match item {
+        Value::String(s) => content.extend(
+            s.lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(|l| DraftContent::Step(l.to_string())),
+        ),
+        Value::Object(obj) => {
+            let Some(text) = obj.get("text").or_else(|| obj.get("name")).and_then(text) else {
+                return;
+            };
+            if has_type(obj, "HowToTip") {
+                content.push(DraftContent::Text(text));
+            } else {
+                content.push(DraftContent::Step(text));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn as_slice(value: Option<&Value>) -> &[Value] {
+    match value {
+        Some(Value::Array(list)) => list,
+        Some(value) => std::slice::from_ref(value),
+        None => &[],
+    }
+}
+
+/// A string, or the first of a list of them
+fn text(value: &Value) -> Option<String> {
+    let s = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Array(list) => return list.iter().find_map(text),
+        _ => return None,
+    };
+    (!s.is_empty()).then_some(s)
+}
+
+/// A list of strings, a comma separated string or objects with a `url`
+fn list(value: &Value) -> Option<Vec<String>> {
+    let list = match value {
+        Value::String(s) => s
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| match v {
+                Value::Object(obj) => obj.get("url").and_then(text),
+                v => text(v),
+            })
+            .collect(),
+        Value::Object(obj) => obj.get("url").and_then(text).into_iter().collect(),
+        _ => return None,
+    };
+    Some(list).filter(|l: &Vec<String>| !l.is_empty())
+}
+
+/// A `Person` or `CreativeWork` as `Name <Url>`
+fn name_and_url(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(obj) => {
+            let name = obj.get("name").and_then(text);
+            let url = obj.get("url").and_then(text);
+            match (name, url) {
+                (Some(name), Some(url)) => Some(format!("{name} <{url}>")),
+                (name, url) => name.or(url),
+            }
+        }
+        Value::Array(list) => list.iter().find_map(name_and_url),
+        value => text(value),
+    }
+}
+
+/// The first number in the yield, like `4` in `4 servings`
+fn servings(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().and_then(|n| n.try_into().ok()),
+        Value::String(s) => first_number(s),
+        Value::Array(list) => list.iter().find_map(servings),
+        _ => None,
+    }
+}
+
+/// Minutes in an ISO 8601 duration, like `PT1H30M`
+fn minutes(value: &Value) -> Option<u32> {
+    let s = value.as_str()?.trim().strip_prefix('P')?;
+    let (date, time) = s.split_once('T').unwrap_or((s, ""));
+    let mut total = 0.0;
+    for (part, is_time) in [(date, false), (time, true)] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() || c == '.' {
+                number.push(c);
+                continue;
+            }
+            let n = number.parse::<f64>().ok()?;
+            number.clear();
+            total += match (c, is_time) {
+                ('D', false) => n * 24.0 * 60.0,
+                ('W', false) => n * 7.0 * 24.0 * 60.0,
+                ('H', true) => n * 60.0,
+                ('M', true) => n,
+                ('S', true) => n / 60.0,
+                _ => return None,
+            };
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    Some(total.round() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{import::ImportWarning, CooklangParser};
+    use indoc::indoc;
+    use test_case::test_case;
+
+    #[test]
+    fn full_recipe() {
+        let input = r#"{
+            "@context": "https://schema.org",
+            "@graph": [
+                { "@type": "WebPage", "name": "Not this" },
+                {
+                    "@type": ["Recipe"],
+                    "name": "Pancakes",
+                    "description": "Fluffy pancakes",
+                    "author": [{ "@type": "Person", "name": "Jane Doe", "url": "https://jane.example.com" }],
+                    "url": "https://example.com/pancakes",
+                    "prepTime": "PT10M",
+                    "cookTime": "PT1H5M",
+                    "recipeYield": ["4", "4 servings"],
+                    "keywords": "breakfast, sweet",
+                    "recipeCuisine": "American",
+                    "recipeCategory": ["Breakfast", "Dessert"],
+                    "image": { "@type": "ImageObject", "url": "https://example.com/pancakes.jpg" },
+                    "recipeIngredient": [
+                        "2 cups all-purpose flour, sifted",
+                        "2 eggs",
+                        "1 1/2 cups milk"
+                    ],
+                    "recipeInstructions": [
+                        {
+                            "@type": "HowToSection",
+                            "name": "Batter",
+                            "itemListElement": [
+                                { "@type": "HowToStep", "text": "Mix the flour, eggs and milk." },
+                                { "@type": "HowToTip", "text": "Let it rest." }
+                            ]
+                        },
+                        {
+                            "@type": "HowToSection",
+                            "name": "Cook",
+                            "itemListElement": "Cook in a pan.\nServe."
+                        }
+                    ]
+                }
+            ]
+        }"#;
+        let imported = import(input, &Converter::default()).unwrap();
+        assert_eq!(
+            imported.text,
+            indoc! {"
+                ---
+                title: Pancakes
+                description: Fluffy pancakes
+                author: Jane Doe <https://jane.example.com>
+                source: https://example.com/pancakes
+                servings: 4
+                prep time: 10 min
+                cook time: 65 min
+                tags:
+                - breakfast
+                - sweet
+                cuisine: American
+                course:
+                - Breakfast
+                - Dessert
+                image: https://example.com/pancakes.jpg
+                ---
+
+                == Batter ==
+
+                Mix the @all-purpose flour|flour{2%cups}(sifted), @eggs{2} and @milk{1 1/2%cups}.
+
+                > Let it rest.
+
+                == Cook ==
+
+                Cook in a pan.
+
+                Serve.
+            "}
+        );
+        assert_eq!(
+            imported.warnings,
+            [ImportWarning::LowConfidenceMatch {
+                ingredient: "all-purpose flour".into(),
+                text: "flour".into()
+            }]
+        );
+
+        let parser = CooklangParser::default();
+        let recipe = parser.parse(&imported.text).into_output().unwrap();
+        assert_eq!(recipe.metadata.title(), Some("Pancakes"));
+        assert_eq!(recipe.metadata.servings(), Some(vec![4]));
+        assert_eq!(recipe.ingredients.len(), 3);
+        assert_eq!(recipe.sections.len(), 2);
+    }
+
+    #[test]
+    fn plain_instructions() {
+        let input = r#"[{
+            "@type": "Recipe",
+            "recipeIngredient": ["1 tsp salt", "pepper"],
+            "recipeInstructions": "Season with salt."
+        }]"#;
+        let imported = import(input, &Converter::default()).unwrap();
+        assert_eq!(imported.text, "@pepper{}\n\nSeason with @salt{1%tsp}.\n");
+        assert_eq!(
+            imported.warnings,
+            [ImportWarning::NotFound {
+                ingredient: "pepper".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let converter = Converter::default();
+        assert!(matches!(import("{", &converter), Err(ImportError::Json(_))));
+        assert!(matches!(
+            import(r#"{"@type": "Person"}"#, &converter),
+            Err(ImportError::NoRecipe)
+        ));
+    }
+
+    #[test_case("PT30M" => Some(30))]
+    #[test_case("PT1H30M" => Some(90))]
+    #[test_case("P0DT2H" => Some(120))]
+    #[test_case("PT90S" => Some(2))]
+    #[test_case("P1D" => Some(1440))]
+    #[test_case("30 minutes" => None)]
+    #[test_case("PT1X" => None)]
+    fn iso_duration(s: &str) -> Option<u32> {
+        minutes(&Value::String(s.into()))
+    }
+}
@@ -0,0 +1,396 @@
+//! MealMaster import
+//!
+//! Reads the MealMaster text format. A file can have many recipes, each
+//! between a `MMMMM----- Recipe via Meal-Master` (or `-----`) header line and
+//! a `MMMMM` (or `-----`) line:
+//!
+//! ```text
+//! MMMMM----- Recipe via Meal-Master (tm) v8.05
+//!
+//!       Title: Hot milk
+//!  Categories: Drinks
+//!       Yield: 2 servings
+//!
+//!       2 c  Milk
+//!
+//!   Warm the milk.
+//!
+//! MMMMM
+//! ```
+//!
+//! The ingredients are in columns: amount, a unit code and the name, in one
+//! or two columns per line. Lines with the name starting with `-` continue
+//! the note of the previous ingredient. Group headings, like
+//! `MMMMM-----SAUCE-----`, have no place in Cooklang and are dropped. Each
+//! paragraph of the directions is a step.
+
+use crate::{
+    convert::Converter, ingredient_line, metadata::StdKey, parser::parse_numeric_value, Quantity,
+    Value,
+};
+
+use super::{
+    first_number, Draft, DraftContent, DraftSection, ImportError, ImportWarning, ImportedRecipe,
+};
+
+/// Imports all the recipes in a MealMaster file
+pub fn import(input: &str, converter: &Converter) -> Result<Vec<ImportedRecipe>, ImportError> {
+    let mut recipes = Vec::new();
+    let mut lines = input.lines();
+    while let Some(line) = lines.next() {
+        if !is_header(line) {
+            continue;
+        }
+        let body = lines.by_ref().take_while(|l| !is_end(l));
+        recipes.push(draft(body, converter).into_cooklang());
+    }
+    if recipes.is_empty() {
+        return Err(ImportError::NoRecipe);
+    }
+    Ok(recipes)
+}
+
+fn is_header(line: &str) -> bool {
+    let line = line.trim();
+    (line.starts_with("MMMMM") || line.starts_with("-----"))
+        && line.to_ascii_lowercase().contains("meal-master")
+}
+
+fn is_end(line: &str) -> bool {
+    matches!(line.trim(), "MMMMM" | "-----")
+}
+
+/// Group heading, like `MMMMM-----SAUCE-----`
+fn group_heading(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let rest = line
+        .strip_prefix("MMMMM")
+        .or_else(|| line.starts_with("-----").then_some(line))?;
+    let name = rest.trim_matches(['-', '=', ' ']);
+    (!name.is_empty()).then_some(name)
+}
+
+#[derive(PartialEq)]
+enum State {
+    Header,
+    Ingredients,
+    Directions,
+}
+
+fn draft<'a>(lines: impl Iterator<Item = &'a str>, converter: &Converter) -> Draft {
+    let mut draft = Draft::default();
+    let mut state = State::Header;
+    let mut paragraph = Vec::new();
+    let mut steps = Vec::new();
+
+    for line in lines {
+        if state == State::Header {
+            if ingredient_columns(line).is_none() {
+                if let Some((key, value)) = line.split_once(':') {
+                    header_field(&mut draft, key.trim(), value.trim());
+                    continue;
+                }
+                if line.trim().is_empty() {
+                    continue;
+                }
+            }
+            state = State::Ingredients;
+        }
+        if state == State::Ingredients {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(name) = group_heading(line) {
+                draft.warnings.push(ImportWarning::Dropped {
+                    what: format!("Ingredient group '{name}'"),
+                });
+                continue;
+            }
+            if let Some(ingredients) = ingredient_columns(line) {
+                for igr in ingredients {
+                    add_ingredient(&mut draft, igr, converter);
+                }
+                continue;
+            }
+            state = State::Directions;
+        }
+        if line.trim().is_empty() {
+            if !paragraph.is_empty() {
+                steps.push(paragraph.join(" "));
+                paragraph.clear();
+            }
+        } else {
+            paragraph.push(line.trim());
+        }
+    }
+    if !paragraph.is_empty() {
+        steps.push(paragraph.join(" "));
+    }
+
+    if !steps.is_empty() {
+        draft.sections.push(DraftSection {
+            name: None,
+            content: steps.into_iter().map(DraftContent::Step).collect(),
+        });
+    }
+    draft
+}
+
+fn header_field(draft: &mut Draft, key: &str, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    match key.to_ascii_lowercase().as_str() {
+        "title" => draft.set(StdKey::Title, value),
+        "categories" => {
+            let tags = value
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty() && !t.eq_ignore_ascii_case("none"))
+                .collect::<Vec<_>>();
+            if !tags.is_empty() {
+                draft.set(StdKey::Tags, tags);
+            }
+        }
+        "yield" | "servings" => {
+            let is_servings = value
+                .split_whitespace()
+                .nth(1)
+                .is_none_or(|unit| unit.to_ascii_lowercase().starts_with("serving"));
+            match first_number(value) {
+                Some(n) if is_servings => draft.set(StdKey::Servings, n),
+                _ => draft.warnings.push(ImportWarning::Dropped {
+                    what: format!("Yield '{value}'"),
+                }),
+            }
+        }
+        _ => draft.warnings.push(ImportWarning::Dropped {
+            what: format!("Header '{key}'"),
+        }),
+    }
+}
+
+/// Ingredient as written in the columns
+struct Columns<'a> {
+    amount: &'a str,
+    unit: &'a str,
+    text: &'a str,
+}
+
+/// Splits the line in one or two columns of ingredients
+///
+/// The columns are in chars, not bytes, so names like `Jalapeños` work.
+fn ingredient_columns(line: &str) -> Option<Vec<Columns<'_>>> {
+    let line = line.trim_end();
+    let (first, second) = match line.char_indices().nth(41) {
+        Some((split, _)) => (&line[..split], Some(&line[split..])),
+        None => (line, None),
+    };
+    let first = columns(first)?;
+    let second = second.and_then(columns);
+    Some(std::iter::once(first).chain(second).collect())
+}
+
+fn columns(line: &str) -> Option<Columns<'_>> {
+    let line = line.trim_end();
+    let offsets = line.char_indices().take(12).collect::<Vec<_>>();
+    if offsets.len() < 12 || offsets[7].1 != ' ' || offsets[10].1 != ' ' {
+        return None;
+    }
+    let amount = line[..offsets[7].0].trim();
+    let unit = line[offsets[8].0..offsets[10].0].trim();
+    let text = line[offsets[11].0..].trim();
+    let amount_ok = amount
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '/' | '.' | ' ' | '-'));
+    let unit_ok = unit.is_empty() || unit_name(unit).is_some();
+    (amount_ok && unit_ok && !text.is_empty()).then_some(Columns { amount, unit, text })
+}
+
+fn add_ingredient(draft: &mut Draft, igr: Columns, converter: &Converter) {
+    // continuation of the previous ingredient
+    if let Some(more) = igr.text.strip_prefix('-') {
+        if let Some(last) = draft.ingredients.last_mut() {
+            let note = last.note.get_or_insert_with(String::new);
+            if !note.is_empty() {
+                note.push(' ');
+            }
+            note.push_str(more.trim());
+            return;
+        }
+    }
+
+    // notes are usually after a `;`
+    let mut line = ingredient_line::parse(&igr.text.replacen(';', ",", 1), converter);
+    let mut unit = unit_name(igr.unit).flatten().map(str::to_string);
+    if let Some(size) = unit.take_if(|u| matches!(u.as_str(), "small" | "medium" | "large")) {
+        line.name = format!("{size} {}", line.name);
+    }
+    if !igr.amount.is_empty() {
+        let value =
+            parse_numeric_value(igr.amount).unwrap_or_else(|| Value::Text(igr.amount.to_string()));
+        line.quantity = Some(Quantity::new(value, unit));
+    }
+    draft.ingredients.push(line);
+}
+
+/// Unit name of a MealMaster unit code
+///
+/// Returns `Some(None)` for the codes without unit, like `ea` (each).
+fn unit_name(code: &str) -> Option<Option<&'static str>> {
+    let name = match code {
+        "x" | "ea" => return Some(None),
+        "sm" => "small",
+        "md" => "medium",
+        "lg" => "large",
+        "cn" => "can",
+        "pk" => "package",
+        "pn" => "pinch",
+        "dr" => "drop",
+        "ds" => "dash",
+        "ct" => "carton",
+        "bn" => "bunch",
+        "sl" => "slice",
+        "t" | "ts" => "tsp",
+        "T" | "tb" => "tbsp",
+        "fl" => "fl oz",
+        "c" => "cup",
+        "pt" => "pint",
+        "qt" => "quart",
+        "ga" => "gallon",
+        "oz" => "oz",
+        "lb" => "lb",
+        "ml" | "cb" => "ml",
+        "cl" => "cl",
+        "dl" => "dl",
+        "l" => "l",
+        "mg" => "mg",
+        "cg" => "cg",
+        "dg" => "dg",
+        "g" => "g",
+        "kg" => "kg",
+        _ => return None,
+    };
+    Some(Some(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CooklangParser;
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {"
+        Some text before the recipes
+
+        MMMMM----- Recipe via Meal-Master (tm) v8.05
+
+              Title: Pancakes
+         Categories: Breakfast, Sweet
+              Yield: 4 servings
+
+              2 c  Flour                               1 ts Salt
+              2 lg Eggs; beaten
+                   -at room temperature
+          1 1/2 c  Milk
+        MMMMM--------------------------TOPPING-------------------------------
+                   Honey
+
+          Mix the flour, salt, eggs and
+          milk in a bowl.
+
+          Cook and serve with honey.
+
+        MMMMM
+
+        ----- Recipe via Meal-Master (tm) v8.05
+
+              Title: Toast
+              Yield: 1 loaf
+
+              1 sl Bread
+
+          Toast the bread.
+
+        -----
+    "};
+
+    #[test]
+    fn recipes() {
+        let recipes = import(INPUT, &Converter::default()).unwrap();
+        assert_eq!(recipes.len(), 2);
+        assert_eq!(
+            recipes[0].text,
+            indoc! {"
+                ---
+                title: Pancakes
+                tags:
+                - Breakfast
+                - Sweet
+                servings: 4
+                ---
+
+                Mix the @flour{2%cup}, @salt{1%tsp}, @large Eggs|eggs{2}(beaten at room temperature) and @milk{1 1/2%cup} in a bowl.
+
+                Cook and serve with @honey{}.
+            "}
+        );
+        assert_eq!(
+            recipes[0].warnings,
+            [
+                ImportWarning::Dropped {
+                    what: "Ingredient group 'TOPPING'".into()
+                },
+                ImportWarning::LowConfidenceMatch {
+                    ingredient: "large Eggs".into(),
+                    text: "eggs".into()
+                }
+            ]
+        );
+        assert_eq!(
+            recipes[1].text,
+            "---\ntitle: Toast\n---\n\nToast the @bread{1%slice}.\n"
+        );
+        assert_eq!(
+            recipes[1].warnings,
+            [ImportWarning::Dropped {
+                what: "Yield '1 loaf'".into()
+            }]
+        );
+
+        let parser = CooklangParser::default();
+        for recipe in &recipes {
+            assert!(recipe.parse(&parser).is_valid());
+        }
+    }
+
+    #[test]
+    fn non_ascii_ingredients() {
+        let input = indoc! {"
+            MMMMM----- Recipe via Meal-Master (tm) v8.05
+
+                  Title: Salsa
+
+                  2    Jalapeños, minced                   1 ts Jalapeño powder
+                  1 c  Tomatoes
+
+              Mix the jalapeños, jalapeño powder and tomatoes.
+
+            MMMMM
+        "};
+        let recipes = import(input, &Converter::default()).unwrap();
+        assert_eq!(
+            recipes[0].text,
+            "---\ntitle: Salsa\n---\n\nMix the @jalapeños{2}(minced), \
+             @jalapeño powder{1%tsp} and @tomatoes{1%cup}.\n"
+        );
+    }
+
+    #[test]
+    fn no_recipes() {
+        assert!(matches!(
+            import("Just text", &Converter::default()),
+            Err(ImportError::NoRecipe)
+        ));
+    }
+}
@@ -0,0 +1,443 @@
+//! Import recipes from other formats into Cooklang
+//!
+//! - [`jsonld`] for schema.org `Recipe` JSON-LD, the format most recipe web
+//!   pages embed.
+//! - [`mealmaster`] for the MealMaster text format.
+//! - [`paprika`] for recipes exported from the Paprika app.
+//! - [`recipeml`] for RecipeML XML.
+//!
+//! The importers output a Cooklang document. Other formats list the
+//! ingredients apart from the steps, so each ingredient line is parsed with
+//! [`ingredient_line::parse`] and then searched in the step text to place the
+//! `@` marker. When the match is not exact or there is no match at all,
+//! an [`ImportWarning`] is reported.
+
+use std::fmt::Write;
+
+use thiserror::Error;
+
+use crate::{
+    convert::Converter,
+    ingredient_line::IngredientLine,
+    metadata::{CooklangValueExt, StdKey},
+    CooklangParser, RecipeResult,
+};
+
+pub mod jsonld;
+pub mod mealmaster;
+pub mod paprika;
+pub mod recipeml;
+
+/// Output of an importer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedRecipe {
+    /// The recipe as a Cooklang document
+    pub text: String,
+    /// Things to review in the document
+    pub warnings: Vec<ImportWarning>,
+}
+
+impl ImportedRecipe {
+    /// Parses the imported document
+    pub fn parse(&self, parser: &CooklangParser) -> RecipeResult {
+        parser.parse(&self.text)
+    }
+}
+
+/// Error that prevents importing a recipe
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("No recipe found in the input")]
+    NoRecipe,
+}
+
+/// Information lost importing a recipe
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ImportWarning {
+    /// Only part of the ingredient name was found in a step
+    #[error("Ingredient '{ingredient}' matched '{text}' in a step with low confidence")]
+    LowConfidenceMatch {
+        /// Name of the ingredient
+        ingredient: String,
+        /// Text it matched in the step
+        text: String,
+    },
+    /// The ingredient was not found in any step
+    ///
+    /// It's added to a step before the others.
+    #[error("Ingredient '{ingredient}' not found in the steps")]
+    NotFound {
+        /// Name of the ingredient
+        ingredient: String,
+    },
+    /// Part of the input has no place in a Cooklang document
+    #[error("{what} can't be imported and was dropped")]
+    Dropped {
+        /// What was dropped, like `Photo data`
+        what: String,
+    },
+}
+
+/// Recipe read by an importer, before writing it as Cooklang
+#[derive(Debug, Default)]
+pub(crate) struct Draft {
+    pub metadata: serde_yaml::Mapping,
+    pub ingredients: Vec<IngredientLine>,
+    pub sections: Vec<DraftSection>,
+    /// Warnings found reading the input
+    pub warnings: Vec<ImportWarning>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct DraftSection {
+    pub name: Option<String>,
+    pub content: Vec<DraftContent>,
+}
+
+#[derive(Debug)]
+pub(crate) enum DraftContent {
+    Step(String),
+    Text(String),
+}
+
+impl Draft {
+    /// Sets a metadata entry
+    pub(crate) fn set(&mut self, key: StdKey, value: impl Into<serde_yaml::Value>) {
+        self.metadata.insert(key.as_ref().into(), value.into());
+    }
+
+    /// Sets a time metadata entry from text like `1 hr 30 mins`
+    ///
+    /// If the time is not understood, it's kept as it is.
+    pub(crate) fn set_time(&mut self, key: StdKey, text: &str, converter: &Converter) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let normalized = text
+            .split_whitespace()
+            .map(|w| match w.to_lowercase().as_str() {
+                "hr" | "hrs" | "hr." | "hrs." => "h",
+                _ => w,
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        match serde_yaml::Value::from(normalized).as_minutes(converter) {
+            Some(minutes) => self.set(key, format!("{minutes} min")),
+            None => self.set(key, text),
+        }
+    }
+
+    /// Writes the Cooklang document, placing the ingredients in the steps
+    pub(crate) fn into_cooklang(mut self) -> ImportedRecipe {
+        if self.sections.is_empty() {
+            self.sections.push(DraftSection::default());
+        }
+        let mut warnings = std::mem::take(&mut self.warnings);
+        let mut steps = self
+            .sections
+            .iter()
+            .flat_map(|s| &s.content)
+            .map(|c| match c {
+                DraftContent::Step(text) => Some(StepMarkers::new(text)),
+                DraftContent::Text(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut not_found = Vec::new();
+        for igr in &self.ingredients {
+            if igr.name.is_empty() {
+                continue;
+            }
+            let placed = steps
+                .iter_mut()
+                .flatten()
+                .find_map(|step| step.place(igr).map(|conf| (step, conf)));
+            match placed {
+                Some((_, Confidence::Exact)) => {}
+                Some((step, Confidence::Low(range))) => {
+                    warnings.push(ImportWarning::LowConfidenceMatch {
+                        ingredient: igr.name.clone(),
+                        text: step.text[range].to_string(),
+                    });
+                }
+                None => {
+                    warnings.push(ImportWarning::NotFound {
+                        ingredient: igr.name.clone(),
+                    });
+                    not_found.push(marker(igr, None));
+                }
+            }
+        }
+
+        let mut out = String::new();
+        if !self.metadata.is_empty() {
+            let yaml = serde_yaml::to_string(&self.metadata).expect("serialize metadata");
+            out.push_str("---\n");
+            out.push_str(&yaml);
+            out.push_str("---\n");
+        }
+        let mut steps = steps.into_iter();
+        for (index, section) in self.sections.iter().enumerate() {
+            match &section.name {
+                Some(name) => push_paragraph(&mut out, &format!("== {} ==", one_line(name))),
+                None if index > 0 => push_paragraph(&mut out, "=="),
+                None => {}
+            }
+            if index == 0 && !not_found.is_empty() {
+                push_paragraph(&mut out, &not_found.join(", "));
+            }
+            for content in &section.content {
+                let step = steps.next().expect("step for each content");
+                match (content, step) {
+                    (DraftContent::Step(_), Some(step)) => {
+                        push_paragraph(&mut out, &step.finish());
+                    }
+                    (DraftContent::Text(text), _) => {
+                        push_paragraph(&mut out, &format!("> {}", escape(&one_line(text))));
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        ImportedRecipe {
+            text: out,
+            warnings,
+        }
+    }
+}
+
+/// First whole number in a text, like `4` in `Serves 4`
+pub(crate) fn first_number(text: &str) -> Option<u32> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let digits = &text[start..];
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+enum Confidence {
+    Exact,
+    Low(std::ops::Range<usize>),
+}
+
+/// Step text and the ingredient markers placed in it
+struct StepMarkers {
+    text: String,
+    markers: Vec<(std::ops::Range<usize>, String)>,
+}
+
+impl StepMarkers {
+    fn new(text: &str) -> Self {
+        Self {
+            text: one_line(text),
+            markers: Vec::new(),
+        }
+    }
+
+    /// Places the ingredient in the first free mention of its name
+    ///
+    /// If the name is not found, the last words of it are tried, like `flour`
+    /// for `all-purpose flour`, which is a low confidence match.
+    fn place(&mut self, igr: &IngredientLine) -> Option<Confidence> {
+        let words = igr.name.split_whitespace().collect::<Vec<_>>();
+        for skip in 0..words.len() {
+            let candidate = words[skip..].join(" ");
+            let found = self
+                .find(&candidate)
+                .or_else(|| self.find(&plural_variant(&candidate)));
+            if let Some(range) = found {
+                let text = &self.text[range.clone()];
+                self.markers.push((range.clone(), marker(igr, Some(text))));
+                self.markers.sort_by_key(|(r, _)| r.start);
+                return Some(if skip == 0 {
+                    Confidence::Exact
+                } else {
+                    Confidence::Low(range)
+                });
+            }
+        }
+        None
+    }
+
+    /// Finds a whole word match, ignoring ASCII case, not already marked
+    fn find(&self, needle: &str) -> Option<std::ops::Range<usize>> {
+        if needle.is_empty() {
+            return None;
+        }
+        let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+        self.text
+            .char_indices()
+            .map(|(start, _)| start..start + needle.len())
+            .filter(|r| {
+                self.text
+                    .get(r.clone())
+                    .is_some_and(|s| s.eq_ignore_ascii_case(needle))
+            })
+            .filter(|r| {
+                !is_word(self.text[..r.start].chars().next_back())
+                    && !is_word(self.text[r.end..].chars().next())
+            })
+            .find(|r| {
+                !self
+                    .markers
+                    .iter()
+                    .any(|(m, _)| m.start < r.end && r.start < m.end)
+            })
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::with_capacity(self.text.len());
+        let mut last = 0;
+        for (range, marker) in self.markers {
+            out.push_str(&escape(&self.text[last..range.start]));
+            out.push_str(&marker);
+            last = range.end;
+        }
+        out.push_str(&escape(&self.text[last..]));
+        out
+    }
+}
+
+/// Singular or plural of the last word, only for regular English plurals
+fn plural_variant(name: &str) -> String {
+    match name.strip_suffix('s') {
+        Some(singular) => singular.to_string(),
+        None => format!("{name}s"),
+    }
+}
+
+/// Ingredient marker, with an alias if the text in the step is different
+fn marker(igr: &IngredientLine, text: Option<&str>) -> String {
+    let mut name = igr
+        .name
+        .replace(['@', '#', '~', '{', '}', '|', '(', ')'], "");
+    // keep the case of the step text
+    if let Some(text) = text.filter(|t| t.eq_ignore_ascii_case(&name)) {
+        name = text.to_string();
+    }
+    let mut s = format!("@{name}");
+    if let Some(text) = text.filter(|t| *t != name) {
+        write!(s, "|{text}").unwrap();
+    }
+    s.push('{');
+    if let Some(q) = &igr.quantity {
+        write!(s, "{}", q.value()).unwrap();
+        if let Some(unit) = q.unit() {
+            write!(s, "%{unit}").unwrap();
+        }
+    }
+    s.push('}');
+    if let Some(note) = &igr.note {
+        write!(s, "({})", note.replace(')', "")).unwrap();
+    }
+    s
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn push_paragraph(out: &mut String, text: &str) {
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(text);
+    out.push('\n');
+}
+
+/// Escapes the text so no Cooklang syntax is found in it
+fn escape(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut first = true;
+    while let Some(c) = chars.next() {
+        let special = match c {
+            '@' | '#' | '~' | '\\' => true,
+            '-' => chars.peek() == Some(&'-'),
+            '[' => chars.peek() == Some(&'-'),
+            '>' | '=' => first,
+            _ => false,
+        };
+        if special {
+            s.push('\\');
+        }
+        s.push(c);
+        first = false;
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ingredient_line, Converter};
+
+    #[test]
+    fn place_ingredients() {
+        let converter = Converter::default();
+        let draft = Draft {
+            metadata: Default::default(),
+            ingredients: [
+                "2 cups all-purpose flour",
+                "3 eggs, beaten",
+                "1 tsp salt",
+                "1 tsp nutmeg",
+            ]
+            .map(|line| ingredient_line::parse(line, &converter))
+            .into(),
+            sections: vec![DraftSection {
+                name: None,
+                content: vec![
+                    DraftContent::Step("Whisk the Egg with the salt.".into()),
+                    DraftContent::Step("Add the flour -- slowly @ 20#.".into()),
+                ],
+            }],
+            warnings: vec![ImportWarning::Dropped {
+                what: "Rating".into(),
+            }],
+        };
+        let imported = draft.into_cooklang();
+        assert_eq!(
+            imported.text,
+            "@nutmeg{1%tsp}\n\nWhisk the @eggs|Egg{3}(beaten) with the @salt{1%tsp}.\n\nAdd the @all-purpose flour|flour{2%cups} \\-- slowly \\@ 20\\#.\n"
+        );
+        assert_eq!(
+            imported.warnings,
+            [
+                ImportWarning::Dropped {
+                    what: "Rating".into()
+                },
+                ImportWarning::LowConfidenceMatch {
+                    ingredient: "all-purpose flour".into(),
+                    text: "flour".into()
+                },
+                ImportWarning::NotFound {
+                    ingredient: "nutmeg".into()
+                }
+            ]
+        );
+
+        let recipe = imported
+            .parse(&CooklangParser::default())
+            .into_output()
+            .unwrap();
+        let names = recipe
+            .ingredients
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["nutmeg", "eggs", "salt", "all-purpose flour"]);
+        let step = recipe.sections[0].content[2].unwrap_step();
+        assert!(matches!(
+            &step.items[2],
+            crate::Item::Text { value } if value == " -- slowly @ 20#."
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+//! Paprika import
+//!
+//! Reads a recipe exported from [Paprika](https://www.paprikaapp.com/). The
+//! `.paprikarecipes` export is a zip of gzipped JSON files, one per recipe;
+//! this reads the JSON of one of them, once unzipped and decompressed.
+//!
+//! The ingredients and directions are text with one entry per line. Lines
+//! ending with `:` in the ingredients, like `For the sauce:`, are taken as
+//! group headings, which Cooklang has not, and dropped. The notes are added as
+//! text at the end and the embedded photo is dropped.
+//!
+//! ```
+//! # use cooklang::{Converter, import::paprika};
+//! let input = r#"{
+//!     "name": "Hot milk",
+//!     "ingredients": "2 cups milk",
+//!     "directions": "Warm the milk.",
+//!     "photo_data": "iVBORw0KGgo="
+//! }"#;
+//! let imported = paprika::import(input, &Converter::default())?;
+//! assert_eq!(imported.text, "---\ntitle: Hot milk\n---\n\nWarm the @milk{2%cups}.\n");
+//! assert_eq!(imported.warnings.len(), 1);
+//! # Ok::<(), cooklang::import::ImportError>(())
+//! ```
+
+use serde::Deserialize;
+
+use crate::{convert::Converter, ingredient_line, metadata::StdKey};
+
+use super::{
+    first_number, Draft, DraftContent, DraftSection, ImportError, ImportWarning, ImportedRecipe,
+};
+
+/// The fields of a Paprika recipe
+///
+/// Others, like the `uid` or `hash`, are bookkeeping of the app.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PaprikaRecipe {
+    name: String,
+    description: String,
+    ingredients: String,
+    directions: String,
+    notes: String,
+    servings: String,
+    prep_time: String,
+    cook_time: String,
+    total_time: String,
+    difficulty: String,
+    rating: u8,
+    categories: Vec<String>,
+    source: String,
+    source_url: String,
+    image_url: String,
+    nutritional_info: String,
+    photo_data: String,
+}
+
+/// Imports a Paprika recipe
+pub fn import(input: &str, converter: &Converter) -> Result<ImportedRecipe, ImportError> {
+    let mut value: serde_json::Value = serde_json::from_str(input)?;
+    let Some(fields) = value.as_object_mut() else {
+        return Err(ImportError::NoRecipe);
+    };
+    // missing fields are sometimes null
+    fields.retain(|_, v| !v.is_null());
+    let recipe = PaprikaRecipe::deserialize(value)?;
+    Ok(draft(recipe, converter).into_cooklang())
+}
+
+fn draft(recipe: PaprikaRecipe, converter: &Converter) -> Draft {
+    let mut draft = Draft::default();
+
+    let texts = [
+        (StdKey::Title, &recipe.name),
+        (StdKey::Description, &recipe.description),
+        (StdKey::Difficulty, &recipe.difficulty),
+    ];
+    for (key, value) in texts {
+        if !value.trim().is_empty() {
+            draft.set(key, value.trim());
+        }
+    }
+    let source = match (recipe.source.trim(), recipe.source_url.trim()) {
+        ("", "") => None,
+        (name, "") => Some(name.to_string()),
+        ("", url) => Some(url.to_string()),
+        (name, url) => Some(format!("{name} <{url}>")),
+    };
+    if let Some(source) = source {
+        draft.set(StdKey::Source, source);
+    }
+    let servings = recipe.servings.trim();
+    if !servings.is_empty() {
+        match first_number(servings) {
+            Some(n) => draft.set(StdKey::Servings, n),
+            None => draft.warnings.push(ImportWarning::Dropped {
+                what: format!("Servings '{servings}'"),
+            }),
+        }
+    }
+    if recipe.prep_time.trim().is_empty() && recipe.cook_time.trim().is_empty() {
+        draft.set_time(StdKey::Time, &recipe.total_time, converter);
+    } else {
+        draft.set_time(StdKey::PrepTime, &recipe.prep_time, converter);
+        draft.set_time(StdKey::CookTime, &recipe.cook_time, converter);
+    }
+    if !recipe.categories.is_empty() {
+        draft.set(StdKey::Tags, recipe.categories);
+    }
+    if !recipe.image_url.trim().is_empty() {
+        draft.set(StdKey::Images, recipe.image_url.trim());
+    }
+    if recipe.rating > 0 {
+        draft.metadata.insert("rating".into(), recipe.rating.into());
+    }
+    if !recipe.nutritional_info.trim().is_empty() {
+        draft
+            .metadata
+            .insert("nutrition".into(), recipe.nutritional_info.trim().into());
+    }
+    if !recipe.photo_data.is_empty() {
+        draft.warnings.push(ImportWarning::Dropped {
+            what: "Embedded photo".into(),
+        });
+    }
+
+    for line in lines(&recipe.ingredients) {
+        if let Some(heading) = line.strip_suffix(':') {
+            draft.warnings.push(ImportWarning::Dropped {
+                what: format!("Ingredient group '{}'", heading.trim()),
+            });
+            continue;
+        }
+        draft
+            .ingredients
+            .push(ingredient_line::parse(line, converter));
+    }
+
+    let mut section = DraftSection::default();
+    section
+        .content
+        .extend(lines(&recipe.directions).map(|l| DraftContent::Step(l.to_string())));
+    section.content.extend(
+        recipe
+            .notes
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(|n| DraftContent::Text(n.to_string())),
+    );
+    if !section.content.is_empty() {
+        draft.sections.push(section);
+    }
+
+    draft
+}
+
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim).filter(|l| !l.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CooklangParser;
+    use indoc::indoc;
+
+    #[test]
+    fn full_recipe() {
+        let input = r#"{
+            "uid": "0A1B2C",
+            "name": "Pancakes",
+            "description": "",
+            "ingredients": "For the batter:\n2 cups flour\n2 eggs\n\n1 1/2 cups milk",
+            "directions": "Mix the flour, eggs and milk.\nCook in a pan.",
+            "notes": "Best with honey.",
+            "servings": "4 servings",
+            "prep_time": "10 mins",
+            "cook_time": "1 hr 5 mins",
+            "total_time": "",
+            "difficulty": "Easy",
+            "rating": 5,
+            "categories": ["Breakfast"],
+            "source": "Grandma",
+            "source_url": "",
+            "image_url": null,
+            "nutritional_info": "",
+            "photo_data": null,
+            "hash": "abc"
+        }"#;
+        let imported = import(input, &Converter::default()).unwrap();
+        assert_eq!(
+            imported.text,
+            indoc! {"
+                ---
+                title: Pancakes
+                difficulty: Easy
+                source: Grandma
+                servings: 4
+                prep time: 10 min
+                cook time: 65 min
+                tags:
+                - Breakfast
+                rating: 5
+                ---
+
+                Mix the @flour{2%cups}, @eggs{2} and @milk{1 1/2%cups}.
+
+                Cook in a pan.
+
+                > Best with honey.
+            "}
+        );
+        assert_eq!(
+            imported.warnings,
+            [ImportWarning::Dropped {
+                what: "Ingredient group 'For the batter'".into()
+            }]
+        );
+        let recipe = imported
+            .parse(&CooklangParser::default())
+            .into_output()
+            .unwrap();
+        assert_eq!(recipe.ingredients.len(), 3);
+    }
+
+    #[test]
+    fn not_a_recipe() {
+        let converter = Converter::default();
+        assert!(matches!(
+            import("[]", &converter),
+            Err(ImportError::NoRecipe)
+        ));
+        assert!(matches!(
+            import(r#"{"name": 1}"#, &converter),
+            Err(ImportError::Json(_))
+        ));
+    }
+}
@@ -0,0 +1,340 @@
+//! RecipeML import
+//!
+//! Reads [RecipeML](http://www.formatdata.com/recipeml/) XML. A document can
+//! have many `<recipe>` elements.
+//!
+//! The `<head>` is mapped to the metadata, the `<ing>` elements of the
+//! `<ingredients>` are placed in the `<step>`s of the `<directions>` and each
+//! `<dir-div>` is a section. Ingredient groups (`<ing-div>` titles),
+//! `<nutrition>` and other elements with no place in Cooklang are dropped.
+//!
+//! ```
+//! # use cooklang::{Converter, import::recipeml};
+//! let input = r#"<recipeml version="0.5">
+//!   <recipe>
+//!     <head><title>Hot milk</title></head>
+//!     <ingredients>
+//!       <ing><amt><qty>2</qty><unit>cups</unit></amt><item>milk</item></ing>
+//!     </ingredients>
+//!     <directions><step>Warm the milk.</step></directions>
+//!   </recipe>
+//! </recipeml>"#;
+//! let recipes = recipeml::import(input, &Converter::default())?;
+//! assert_eq!(recipes[0].text, "---\ntitle: Hot milk\n---\n\nWarm the @milk{2%cups}.\n");
+//! # Ok::<(), cooklang::import::ImportError>(())
+//! ```
+
+use roxmltree::{Document, Node};
+
+use crate::{
+    convert::Converter, ingredient_line::IngredientLine, metadata::StdKey,
+    parser::parse_numeric_value, Quantity, Value,
+};
+
+use super::{
+    first_number, Draft, DraftContent, DraftSection, ImportError, ImportWarning, ImportedRecipe,
+};
+
+/// Imports all the recipes in a RecipeML document
+pub fn import(input: &str, converter: &Converter) -> Result<Vec<ImportedRecipe>, ImportError> {
+    let doc = Document::parse(input)?;
+    let recipes = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("recipe"))
+        .map(|recipe| draft(recipe, converter).into_cooklang())
+        .collect::<Vec<_>>();
+    if recipes.is_empty() {
+        return Err(ImportError::NoRecipe);
+    }
+    Ok(recipes)
+}
+
+fn draft(recipe: Node, converter: &Converter) -> Draft {
+    let mut draft = Draft::default();
+    for child in recipe.children().filter(Node::is_element) {
+        match child.tag_name().name() {
+            "head" => head(&mut draft, child, converter),
+            "description" => draft.set(StdKey::Description, text(child)),
+            "ingredients" => ingredients(&mut draft, child),
+            "directions" => directions(&mut draft, child),
+            name => dropped(&mut draft, name),
+        }
+    }
+    draft
+}
+
+fn head(draft: &mut Draft, head: Node, converter: &Converter) {
+    for child in head.children().filter(Node::is_element) {
+        let value = text(child);
+        match child.tag_name().name() {
+            "title" => draft.set(StdKey::Title, value),
+            "subtitle" | "description" => draft.set(StdKey::Description, value),
+            "categories" => {
+                let tags = elements(child, "cat").map(text).collect::<Vec<_>>();
+                if !tags.is_empty() {
+                    draft.set(StdKey::Tags, tags);
+                }
+            }
+            "yield" => {
+                let qty = elements(child, "qty").next().map(text).unwrap_or(value);
+                match first_number(&qty) {
+                    Some(n) => draft.set(StdKey::Servings, n),
+                    None => dropped(draft, "yield"),
+                }
+            }
+            "preptime" => {
+                let kind = child.attribute("type").unwrap_or_default().to_lowercase();
+                let key = if kind.contains("cook") {
+                    StdKey::CookTime
+                } else if kind.contains("prep") {
+                    StdKey::PrepTime
+                } else {
+                    StdKey::Time
+                };
+                draft.set_time(key, &value, converter);
+            }
+            "source" => draft.set(StdKey::Source, value),
+            name => dropped(draft, name),
+        }
+    }
+}
+
+fn ingredients(draft: &mut Draft, node: Node) {
+    for child in node.children().filter(Node::is_element) {
+        match child.tag_name().name() {
+            "ing" => draft.ingredients.push(ingredient(child)),
+            "ing-div" => {
+                if let Some(title) = elements(child, "title").next() {
+                    draft.warnings.push(ImportWarning::Dropped {
+                        what: format!("Ingredient group '{}'", text(title)),
+                    });
+                }
+                ingredients(draft, child);
+            }
+            "title" | "note" => {}
+            name => dropped(draft, name),
+        }
+    }
+}
+
+fn ingredient(ing: Node) -> IngredientLine {
+    let mut line = IngredientLine {
+        name: String::new(),
+        quantity: None,
+        note: None,
+    };
+    for child in ing.children().filter(Node::is_element) {
+        match child.tag_name().name() {
+            "item" => line.name = text(child),
+            "amt" => {
+                let qty = elements(child, "qty").next().map(amount);
+                let unit = elements(child, "unit")
+                    .next()
+                    .map(text)
+                    .filter(|u| !u.is_empty());
+                if let Some(value) = qty {
+                    line.quantity = Some(Quantity::new(value, unit));
+                }
+            }
+            "prep" | "ing-note" => {
+                let note = text(child);
+                if !note.is_empty() {
+                    line.note = Some(match line.note.take() {
+                        Some(prev) => format!("{prev}, {note}"),
+                        None => note,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    line
+}
+
+/// `<qty>` value, a number or a `<range>`
+fn amount(qty: Node) -> Value {
+    let numbers = qty
+        .descendants()
+        .filter(|n| n.has_tag_name("q1") || n.has_tag_name("q2"))
+        .map(text)
+        .collect::<Vec<_>>();
+    let text = if numbers.is_empty() {
+        text(qty)
+    } else {
+        numbers.join("-")
+    };
+    parse_numeric_value(&text).unwrap_or(Value::Text(text))
+}
+
+fn directions(draft: &mut Draft, node: Node) {
+    let mut main = DraftSection::default();
+    let mut has_elements = false;
+    for child in node.children().filter(Node::is_element) {
+        has_elements = true;
+        match child.tag_name().name() {
+            "step" => main.content.push(DraftContent::Step(text(child))),
+            "note" => main.content.push(DraftContent::Text(text(child))),
+            "dir-div" => {
+                if !main.content.is_empty() {
+                    draft.sections.push(std::mem::take(&mut main));
+                }
+                let mut section = DraftSection {
+                    name: elements(child, "title").next().map(text),
+                    content: Vec::new(),
+                };
+                for step in child.children().filter(Node::is_element) {
+                    match step.tag_name().name() {
+                        "step" => section.content.push(DraftContent::Step(text(step))),
+                        "note" => section.content.push(DraftContent::Text(text(step))),
+                        _ => {}
+                    }
+                }
+                draft.sections.push(section);
+            }
+            name => dropped(draft, name),
+        }
+    }
+    // plain text directions, one step per line
+    if !has_elements {
+        main.content.extend(
+            text(node)
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(|l| DraftContent::Step(l.to_string())),
+        );
+    }
+    if !main.content.is_empty() {
+        draft.sections.push(main);
+    }
+}
+
+fn elements<'a, 'i>(node: Node<'a, 'i>, name: &'static str) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+/// All the text inside a node, with the whitespace collapsed
+fn text(node: Node) -> String {
+    let mut s = String::new();
+    for t in node.descendants().filter(|n| n.is_text()) {
+        s.push_str(t.text().unwrap_or_default());
+    }
+    let lines = s
+        .lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>();
+    lines.join("\n").trim().to_string()
+}
+
+fn dropped(draft: &mut Draft, element: &str) {
+    draft.warnings.push(ImportWarning::Dropped {
+        what: format!("Element '<{element}>'"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CooklangParser;
+    use indoc::indoc;
+
+    #[test]
+    fn full_recipe() {
+        let input = indoc! {r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <recipeml version="0.5">
+              <recipe>
+                <head>
+                  <title>Pancakes</title>
+                  <categories><cat>Breakfast</cat><cat>Sweet</cat></categories>
+                  <yield><qty>4</qty><unit>servings</unit></yield>
+                  <preptime type="Cooking"><time><qty>20</qty><timeunit>minutes</timeunit></time></preptime>
+                </head>
+                <ingredients>
+                  <ing><amt><qty>2</qty><unit>cups</unit></amt><item>flour</item><prep>sifted</prep></ing>
+                  <ing-div>
+                    <title>Wet</title>
+                    <ing><amt><qty><range><q1>1</q1><q2>2</q2></range></qty></amt><item>eggs</item></ing>
+                    <ing><amt><qty>1 1/2</qty><unit>cups</unit></amt><item>milk</item></ing>
+                  </ing-div>
+                </ingredients>
+                <directions>
+                  <dir-div>
+                    <title>Batter</title>
+                    <step>Mix the flour, eggs
+                      and milk.</step>
+                  </dir-div>
+                  <dir-div>
+                    <title>Cook</title>
+                    <step>Cook in a pan.</step>
+                  </dir-div>
+                </directions>
+                <nutrition>Lots</nutrition>
+              </recipe>
+            </recipeml>
+        "#};
+        let recipes = import(input, &Converter::default()).unwrap();
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(
+            recipes[0].text,
+            indoc! {"
+                ---
+                title: Pancakes
+                tags:
+                - Breakfast
+                - Sweet
+                servings: 4
+                cook time: 20 min
+                ---
+
+                == Batter ==
+
+                Mix the @flour{2%cups}(sifted), @eggs{1-2} and @milk{1 1/2%cups}.
+
+                == Cook ==
+
+                Cook in a pan.
+            "}
+        );
+        assert_eq!(
+            recipes[0].warnings,
+            [
+                ImportWarning::Dropped {
+                    what: "Ingredient group 'Wet'".into()
+                },
+                ImportWarning::Dropped {
+                    what: "Element '<nutrition>'".into()
+                },
+            ]
+        );
+        assert!(recipes[0].parse(&CooklangParser::default()).is_valid());
+    }
+
+    #[test]
+    fn plain_directions() {
+        let input = indoc! {"
+            <recipeml><recipe>
+              <ingredients><ing><item>salt</item></ing></ingredients>
+              <directions>
+                Season with salt.
+                Serve.
+              </directions>
+            </recipe></recipeml>
+        "};
+        let recipes = import(input, &Converter::default()).unwrap();
+        assert_eq!(recipes[0].text, "Season with @salt{}.\n\nServe.\n");
+    }
+
+    #[test]
+    fn errors() {
+        let converter = Converter::default();
+        assert!(matches!(
+            import("<recipeml>", &converter),
+            Err(ImportError::Xml(_))
+        ));
+        assert!(matches!(
+            import("<recipeml/>", &converter),
+            Err(ImportError::NoRecipe)
+        ));
+    }
+}
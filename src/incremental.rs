@@ -0,0 +1,498 @@
+//! Incremental parsing for editors
+//!
+//! An editor parses the same recipe again after every keystroke. An
+//! [`IncrementalState`] keeps the text, its tokens and the result of the
+//! last parse for every block. Applying a [`TextEdit`] only lexes again the
+//! lines around the edit until the tokens are the same as before, and parsing
+//! the state again reuses the blocks before the edit. The parser and the
+//! analysis run from the first block affected by the edit.
+//!
+//! ```
+//! use cooklang::{CooklangParser, incremental::{IncrementalState, TextEdit}};
+//!
+//! let parser = CooklangParser::default();
+//! let mut state = IncrementalState::new("Add @flour{100%g}.\n\nBake.");
+//! let recipe = parser.parse_state(&mut state).into_output().unwrap();
+//! assert_eq!(recipe.ingredients.len(), 1);
+//!
+//! // replace "Bake." with "Add @salt."
+//! let recipe = parser
+//!     .reparse(&mut state, &TextEdit::new(20..25, "Add @salt."))
+//!     .unwrap()
+//!     .into_output()
+//!     .unwrap();
+//! assert_eq!(state.input(), "Add @flour{100%g}.\n\nAdd @salt.");
+//! assert_eq!(recipe.ingredients.len(), 2);
+//! ```
+//!
+//! The blocks after the edit are parsed again too, as references and step
+//! numbers depend on everything before them. The result is always the same as
+//! parsing the new text from scratch.
+
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::{
+    analysis::{Checkpoint, ParseOptions, RecipeCollector},
+    lexer::TokenKind,
+    parser::{self, PullParser, Token},
+    span::Span,
+    CooklangParser, Extensions, RecipeResult,
+};
+
+/// A change in the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Byte range of the previous text to replace
+    pub range: Range<usize>,
+    /// Text to insert instead
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+
+    /// Insert text at an offset
+    pub fn insert(offset: usize, text: impl Into<String>) -> Self {
+        Self::new(offset..offset, text)
+    }
+
+    /// Delete a range
+    pub fn delete(range: Range<usize>) -> Self {
+        Self::new(range, "")
+    }
+}
+
+/// The range of a [`TextEdit`] does not fit the text
+///
+/// The range is inverted, out of bounds or its ends are not in a char
+/// boundary.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Invalid edit range {}..{} for a text of {len} bytes", range.start, range.end)]
+pub struct InvalidRange {
+    pub range: Range<usize>,
+    /// Length of the text
+    pub len: usize,
+}
+
+/// Text of a recipe, its tokens and the analysis of its blocks
+///
+/// The analysis of the blocks before an edit is reused, so always parse a
+/// state with the same converter and [`ParseOptions`]. The checks in the
+/// options only run for the blocks parsed again. If the extensions change,
+/// the whole text is parsed again.
+#[derive(Debug, Clone)]
+pub struct IncrementalState {
+    input: String,
+    tokens: Vec<Token>,
+    frontmatter_end: Option<usize>,
+    /// Blocks of the last parse, in order
+    blocks: Vec<ParsedBlock>,
+    /// Extensions of the last parse
+    extensions: Extensions,
+    /// Number of tokens at the start that did not change since the last parse
+    unchanged: usize,
+}
+
+/// A block of the last parse
+#[derive(Debug, Clone)]
+struct ParsedBlock {
+    /// Index of the first token after the block
+    next: usize,
+    /// Number of tokens the block depends on
+    ///
+    /// This includes the next token if it was needed to find the end of the
+    /// block.
+    depends_on: usize,
+    /// State of the analysis after the block
+    analysis: Checkpoint,
+}
+
+impl IncrementalState {
+    /// Lexes a new text
+    pub fn new(input: impl Into<String>) -> Self {
+        let input = input.into();
+        let tokens = parser::token_stream(&input).collect();
+        let frontmatter_end = parser::frontmatter_end(&input);
+        Self {
+            input,
+            tokens,
+            frontmatter_end,
+            blocks: Vec::new(),
+            extensions: Extensions::empty(),
+            unchanged: 0,
+        }
+    }
+
+    /// Current text
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Applies an edit to the text
+    ///
+    /// Only the lines around the edit are lexed again. If the range does not
+    /// fit the text, nothing changes.
+    pub fn edit(&mut self, edit: &TextEdit) -> Result<(), InvalidRange> {
+        let Range { start, end } = edit.range.clone();
+        if start > end || !self.input.is_char_boundary(start) || !self.input.is_char_boundary(end) {
+            return Err(InvalidRange {
+                range: edit.range.clone(),
+                len: self.input.len(),
+            });
+        }
+        self.input.replace_range(start..end, &edit.text);
+
+        let frontmatter_end = parser::frontmatter_end(&self.input);
+        let same_frontmatter = match (self.frontmatter_end, frontmatter_end) {
+            (None, None) => true,
+            (Some(old), Some(new)) => old == new && start >= old,
+            _ => false,
+        };
+        if !same_frontmatter {
+            *self = Self::new(std::mem::take(&mut self.input));
+            return Ok(());
+        }
+        let base = frontmatter_end.unwrap_or(0);
+
+        // Restart at the beginning of the line of the edit. Tokens never look
+        // back, so the tokens before a newline do not change.
+        let head = self.tokens.partition_point(|t| t.span.end() <= start);
+        let restart = self.tokens[..head]
+            .iter()
+            .rposition(|t| t.kind == TokenKind::Newline)
+            .map_or(0, |i| i + 1);
+        let restart_offset = restart
+            .checked_sub(1)
+            .map_or(base, |i| self.tokens[i].span.end());
+        self.unchanged = self.unchanged.min(restart);
+
+        // Old tokens after the edit, in the new text
+        let delta = edit.text.len() as isize - (end - start) as isize;
+        let shift = |t: &Token| Token {
+            kind: t.kind,
+            span: Span::new(
+                t.span.start().wrapping_add_signed(delta),
+                t.span.end().wrapping_add_signed(delta),
+            ),
+        };
+        let old_tail = &self.tokens[self.tokens.partition_point(|t| t.span.start() < end)..];
+
+        let mut lexed = Vec::new();
+        let mut resync = old_tail.len();
+        let mut candidate = 0;
+        let mut stream = parser::TokenStream::new(&self.input[restart_offset..]);
+        stream.offset(restart_offset);
+        for token in stream {
+            // When a token starts where an old token after the edit started,
+            // the rest of the text is the same, and so are the tokens.
+            while candidate < old_tail.len()
+                && shift(&old_tail[candidate]).span.start() < token.span.start()
+            {
+                candidate += 1;
+            }
+            if candidate < old_tail.len()
+                && shift(&old_tail[candidate]).span.start() == token.span.start()
+            {
+                resync = candidate;
+                break;
+            }
+            lexed.push(token);
+        }
+
+        let tail = old_tail[resync..].iter().map(shift).collect::<Vec<_>>();
+        self.tokens.truncate(restart);
+        self.tokens.extend(lexed);
+        self.tokens.extend(tail);
+        Ok(())
+    }
+}
+
+impl CooklangParser {
+    /// Parse the text of an [`IncrementalState`]
+    ///
+    /// Only the blocks from the first one affected by the edits since the
+    /// last parse are parsed again.
+    pub fn parse_state(&self, state: &mut IncrementalState) -> RecipeResult {
+        self.parse_state_with_options(state, ParseOptions::default())
+    }
+
+    /// Same as [`Self::parse_state`] but with aditional options
+    #[tracing::instrument(level = "debug", name = "parse_state", skip_all, fields(len = state.input.len()))]
+    pub fn parse_state_with_options(
+        &self,
+        state: &mut IncrementalState,
+        options: ParseOptions,
+    ) -> RecipeResult {
+        if state.extensions != self.extensions() {
+            state.blocks.clear();
+            state.extensions = self.extensions();
+        }
+        let kept = state
+            .blocks
+            .partition_point(|b| b.depends_on <= state.unchanged);
+        state.blocks.truncate(kept);
+        state.unchanged = state.tokens.len();
+
+        let IncrementalState {
+            input,
+            tokens,
+            blocks,
+            ..
+        } = state;
+        let (mut parser, mut collector) = match blocks.last() {
+            Some(last) => (
+                PullParser::resume_with_tokens(
+                    input,
+                    tokens[last.next..].iter().copied(),
+                    self.extensions(),
+                ),
+                RecipeCollector::from_checkpoint(
+                    input,
+                    self.extensions(),
+                    self.converter(),
+                    options,
+                    last.analysis.clone(),
+                ),
+            ),
+            None => (
+                PullParser::with_tokens(input, tokens.iter().copied(), self.extensions()),
+                RecipeCollector::new(input, self.extensions(), self.converter(), options),
+            ),
+        };
+
+        // frontmatter
+        parser.take_events().for_each(|ev| collector.event(ev));
+        while parser.next_block().is_some() {
+            parser.take_events().for_each(|ev| collector.event(ev));
+            let (end, lookahead) = parser.block_end().expect("block without tokens");
+            let next = tokens.partition_point(|t| t.span.start() < end);
+            blocks.push(ParsedBlock {
+                next,
+                depends_on: next + usize::from(lookahead),
+                analysis: collector.checkpoint(),
+            });
+        }
+        collector.finish()
+    }
+
+    /// Applies an edit to the state and parses the new text
+    ///
+    /// The result is the same as [`Self::parse`] with the new text. See
+    /// [`IncrementalState::edit`] for the errors.
+    pub fn reparse(
+        &self,
+        state: &mut IncrementalState,
+        edit: &TextEdit,
+    ) -> Result<RecipeResult, InvalidRange> {
+        self.reparse_with_options(state, edit, ParseOptions::default())
+    }
+
+    /// Same as [`Self::reparse`] but with aditional options
+    pub fn reparse_with_options(
+        &self,
+        state: &mut IncrementalState,
+        edit: &TextEdit,
+        options: ParseOptions,
+    ) -> Result<RecipeResult, InvalidRange> {
+        state.edit(edit)?;
+        Ok(self.parse_state_with_options(state, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{CheckResult, SourceMap};
+    use indoc::indoc;
+    use test_case::test_case;
+
+    const RECIPE: &str = indoc! {"
+        >> servings: 2
+        = Dough
+        Mix @flour{500%g} and @water{300%ml} in a #bowl. -- comment
+        Knead the @&(~1)dough{} for ~{10%min}.
+
+        [- block
+        comment -]
+        = Bake
+        Bake the @&(=1)dough{} with more @&flour{20%g} at 180 °C.
+
+        Serve with @salt{}.
+    "};
+
+    /// Parse with a source map, to compare the results
+    fn parse_with_map(parse: impl FnOnce(ParseOptions) -> RecipeResult) -> String {
+        let mut source_map = SourceMap::default();
+        let result = parse(ParseOptions {
+            source_map: Some(&mut source_map),
+            ..Default::default()
+        });
+        format!("{:?} {source_map:?}", result.into_tuple())
+    }
+
+    fn check(input: &str, edit: TextEdit) {
+        let parser = CooklangParser::default();
+        let mut state = IncrementalState::new(input);
+        parser.parse_state(&mut state);
+        state.edit(&edit).unwrap();
+        let mut expected = input.to_string();
+        expected.replace_range(edit.range.clone(), &edit.text);
+        assert_eq!(state.input, expected);
+        let fresh = IncrementalState::new(expected.as_str());
+        assert_eq!(state.tokens, fresh.tokens, "{edit:?}");
+        assert_eq!(state.frontmatter_end, fresh.frontmatter_end);
+
+        let incremental = parse_with_map(|o| parser.parse_state_with_options(&mut state, o));
+        let full = parse_with_map(|o| parser.parse_with_options(&expected, o));
+        assert_eq!(incremental, full, "{edit:?}");
+    }
+
+    #[test_case("\n" ; "newline")]
+    #[test_case("\n\n" ; "blank line")]
+    #[test_case("[-" ; "open block comment")]
+    #[test_case("-]" ; "close block comment")]
+    #[test_case("--" ; "line comment")]
+    #[test_case("@" ; "ingredient")]
+    #[test_case("{" ; "open brace")]
+    #[test_case("word" ; "word")]
+    #[test_case("12" ; "number")]
+    #[test_case("\r" ; "carriage return")]
+    #[test_case("---\n" ; "fence")]
+    #[test_case(">> " ; "metadata")]
+    fn insert_everywhere(text: &str) {
+        for (offset, _) in RECIPE.char_indices() {
+            check(RECIPE, TextEdit::insert(offset, text));
+        }
+        check(RECIPE, TextEdit::insert(RECIPE.len(), text));
+    }
+
+    #[test]
+    fn delete_everywhere() {
+        let offsets = RECIPE.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+        for len in [1, 2, 5, 30] {
+            for (i, &start) in offsets.iter().enumerate() {
+                let end = offsets.get(i + len).copied().unwrap_or(RECIPE.len());
+                check(RECIPE, TextEdit::delete(start..end));
+            }
+        }
+    }
+
+    #[test]
+    fn frontmatter() {
+        let input = indoc! {"
+            ---
+            servings: 2
+            ---
+            Add @flour{}.
+        "};
+        check(input, TextEdit::insert(26, "Then add @salt.\n"));
+        check(input, TextEdit::new(14..15, "4"));
+        check(input, TextEdit::delete(0..4));
+        check(
+            "---\nservings: 2\n",
+            TextEdit::insert(16, "---\nAdd @salt."),
+        );
+        check("---\na: b\n---", TextEdit::insert(12, "Add"));
+    }
+
+    #[test]
+    fn only_lexes_edited_lines() {
+        let mut state = IncrementalState::new(RECIPE);
+        // mark the last token, if it is lexed again the mark is lost
+        state.tokens.last_mut().unwrap().kind = TokenKind::Eof;
+        state.edit(&TextEdit::insert(20, "Salt.")).unwrap();
+        assert_eq!(state.tokens.last().unwrap().kind, TokenKind::Eof);
+        let len = state.input.len();
+        state.edit(&TextEdit::new(len - 2..len, "!\n")).unwrap();
+        assert_eq!(state.tokens.last().unwrap().kind, TokenKind::Newline);
+    }
+
+    /// Names of the recipe references checked by a parse
+    fn checked_recipes(parser: &CooklangParser, state: &mut IncrementalState) -> Vec<String> {
+        let mut checked = Vec::new();
+        let options = ParseOptions {
+            recipe_ref_check: Some(Box::new(|name: &str| {
+                checked.push(name.to_string());
+                CheckResult::Ok
+            })),
+            ..Default::default()
+        };
+        parser.parse_state_with_options(state, options);
+        checked
+    }
+
+    #[test]
+    fn reuses_blocks_before_edit() {
+        let parser = CooklangParser::default();
+        let mut state = IncrementalState::new("Add @@a{}.\n\nAdd @@b{}.\n\nAdd @@c{}.");
+        assert_eq!(checked_recipes(&parser, &mut state), ["a", "b", "c"]);
+        // the last block depends on the end of the input
+        assert_eq!(checked_recipes(&parser, &mut state), ["c"]);
+
+        state.edit(&TextEdit::insert(19, "x")).unwrap();
+        assert_eq!(checked_recipes(&parser, &mut state), ["bx", "c"]);
+
+        // from the earliest of many edits
+        state.edit(&TextEdit::insert(32, "y")).unwrap();
+        state.edit(&TextEdit::insert(7, "z")).unwrap();
+        assert_eq!(checked_recipes(&parser, &mut state), ["az", "bx", "cy"]);
+
+        // joining two blocks
+        assert_eq!(state.input(), "Add @@az{}.\n\nAdd @@bx{}.\n\nAdd @@cy{}.");
+        state.edit(&TextEdit::delete(25..26)).unwrap();
+        assert_eq!(checked_recipes(&parser, &mut state), ["bx", "cy"]);
+    }
+
+    #[test]
+    fn other_extensions() {
+        let mut state = IncrementalState::new("Add @@a{}.\n\nAdd @@b{}.\n\nAdd @@c{}.");
+        let parser = CooklangParser::default();
+        checked_recipes(&parser, &mut state);
+        let other = CooklangParser::new(Extensions::COMPAT, Default::default());
+        assert_eq!(checked_recipes(&other, &mut state), ["a", "b", "c"]);
+        assert_eq!(checked_recipes(&other, &mut state), ["c"]);
+    }
+
+    #[test_case(5, 2 ; "inverted")]
+    #[test_case(3, 100 ; "out of bounds")]
+    #[test_case(12, 14 ; "not char boundary")]
+    fn invalid_range(start: usize, end: usize) {
+        let mut state = IncrementalState::new("Add @jalapeño{}.");
+        let tokens = state.tokens.clone();
+        let err = state.edit(&TextEdit::delete(start..end)).unwrap_err();
+        assert_eq!(err.range, start..end);
+        assert_eq!(err.len, 17);
+        assert_eq!(state.input(), "Add @jalapeño{}.");
+        assert_eq!(state.tokens, tokens);
+    }
+
+    #[test]
+    fn many_edits() {
+        let parser = CooklangParser::default();
+        let mut state = IncrementalState::new("");
+        let mut text = String::new();
+        for (i, c) in RECIPE.char_indices() {
+            let edit = TextEdit::insert(i, c);
+            text.insert(i, c);
+            let incremental = parser.reparse(&mut state, &edit).unwrap().into_tuple();
+            let full = parser.parse(&text).into_tuple();
+            assert_eq!(format!("{incremental:?}"), format!("{full:?}"));
+        }
+        assert_eq!(state.input(), RECIPE);
+        while !text.is_empty() {
+            let mut boundaries = text.char_indices().map(|(i, _)| i).skip(text.len() / 3);
+            let start = boundaries.next().unwrap();
+            let end = boundaries.nth(2).unwrap_or(text.len());
+            text.replace_range(start..end, "");
+            state.edit(&TextEdit::delete(start..end)).unwrap();
+            assert_eq!(state.tokens, IncrementalState::new(text.as_str()).tokens);
+        }
+    }
+}
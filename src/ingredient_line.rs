@@ -0,0 +1,248 @@
+//! Parse free text ingredient lines
+//!
+//! Recipes outside Cooklang list the ingredients as lines of text, like
+//! `2 1/2 cups all-purpose flour, sifted`. [`parse`] splits a line in the
+//! name, [`Quantity`] and note of the ingredient.
+//!
+//! ```
+//! # use cooklang::{Converter, ingredient_line};
+//! let converter = Converter::default();
+//! let line = ingredient_line::parse("2 1/2 cups all-purpose flour, sifted", &converter);
+//! assert_eq!(line.name, "all-purpose flour");
+//! assert_eq!(line.quantity.unwrap().to_string(), "2 1/2 cups");
+//! assert_eq!(line.note.as_deref(), Some("sifted"));
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{convert::Converter, parser::parse_numeric_value, quantity::Number, Quantity, Value};
+
+/// Ingredient line split in its parts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IngredientLine {
+    /// Name of the ingredient
+    ///
+    /// It can be empty if the line is only a quantity.
+    pub name: String,
+    /// Quantity, if any
+    pub quantity: Option<Quantity>,
+    /// Note with the rest of the information
+    ///
+    /// Everything after the first comma and inside parentheses.
+    pub note: Option<String>,
+}
+
+/// Parses an ingredient line
+///
+/// The line has the form `[quantity] [unit] name[, note]`:
+/// - The quantity is a number, fraction (also `½`), mixed number or range
+///   (`1-2` or `1 to 2`) with the same rules as in a recipe.
+/// - Words like `a pinch of` or `a few` are a text quantity. If the word is a
+///   unit, like `a cup of`, it's 1 of that unit.
+/// - The unit is only taken if it's in the `converter`, or if it's followed
+///   by `of`, like `2 sprigs of`.
+/// - Text in parentheses and after the first comma is the note.
+pub fn parse(line: &str, converter: &Converter) -> IngredientLine {
+    let line = normalize(line);
+    let (line, mut notes) = take_parentheses(&line);
+    let (main, note) = match line.split_once(',') {
+        Some((main, note)) => (main, Some(note)),
+        None => (line.as_str(), None),
+    };
+    notes.extend(
+        note.map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(String::from),
+    );
+
+    let mut words = main.split_whitespace().collect::<Vec<_>>();
+    if let [.., to, taste] = words.as_slice() {
+        if to.eq_ignore_ascii_case("to") && taste.eq_ignore_ascii_case("taste") {
+            words.truncate(words.len() - 2);
+            notes.push("to taste".into());
+        }
+    }
+
+    let (quantity, rest) = quantity(&words, converter);
+    let rest = match rest {
+        [of, rest @ ..] if of.eq_ignore_ascii_case("of") => rest,
+        rest => rest,
+    };
+
+    IngredientLine {
+        name: rest.join(" "),
+        quantity,
+        note: (!notes.is_empty()).then(|| notes.join(", ")),
+    }
+}
+
+/// Takes the quantity and unit from the start of the words
+fn quantity<'a, 'w>(
+    words: &'a [&'w str],
+    converter: &Converter,
+) -> (Option<Quantity>, &'a [&'w str]) {
+    // longest prefix that is a number, at most "1 1/2 - 2 1/2"
+    let numeric = (1..=words.len().min(5))
+        .rev()
+        .find_map(|n| parse_numeric_value(&words[..n].join(" ")).map(|v| (v, n)));
+
+    if let Some((value, n)) = numeric {
+        let rest = &words[n..];
+        let (unit, rest) = unit(rest, converter);
+        return (Some(Quantity::new(value, unit)), rest);
+    }
+
+    // a pinch of, a cup of, a few
+    if let [a, word, rest @ ..] = words {
+        if a.eq_ignore_ascii_case("a") || a.eq_ignore_ascii_case("an") {
+            if let Some(unit) = find_unit(word, converter) {
+                let value = Value::Number(Number::Regular(1.0));
+                return (Some(Quantity::new(value, Some(unit))), rest);
+            }
+            if matches!(rest.first(), Some(of) if of.eq_ignore_ascii_case("of"))
+                || word.eq_ignore_ascii_case("few")
+                || word.eq_ignore_ascii_case("couple")
+            {
+                let value = Value::Text(format!("{a} {word}"));
+                return (Some(Quantity::new(value, None)), rest);
+            }
+        }
+    }
+
+    (None, words)
+}
+
+fn unit<'a, 'w>(words: &'a [&'w str], converter: &Converter) -> (Option<String>, &'a [&'w str]) {
+    if let [first, second, rest @ ..] = words {
+        let two = format!("{first} {second}");
+        if let Some(unit) = find_unit(&two, converter) {
+            return (Some(unit), rest);
+        }
+    }
+    match words {
+        [word, rest @ ..] if find_unit(word, converter).is_some() => (Some(word.to_string()), rest),
+        // unknown unit, like "2 sprigs of"
+        [word, of, ..] if of.eq_ignore_ascii_case("of") => (Some(word.to_string()), &words[1..]),
+        _ => (None, words),
+    }
+}
+
+fn find_unit(word: &str, converter: &Converter) -> Option<String> {
+    if converter.find_unit(word).is_some() {
+        return Some(word.to_string());
+    }
+    let lower = word.to_lowercase();
+    converter.find_unit(&lower).map(|_| lower)
+}
+
+/// Replaces unicode fractions and dashes and splits `200g` in `200 g`
+fn normalize(line: &str) -> String {
+    let line = line.trim().trim_start_matches(['-', '*', '•']).trim_start();
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(frac) = unicode_fraction(c) {
+            if out.ends_with(|c: char| c.is_ascii_digit()) {
+                out.push(' ');
+            }
+            out.push_str(frac);
+            continue;
+        }
+        match c {
+            '–' | '—' => out.push('-'),
+            '⁄' => out.push('/'),
+            c if c.is_ascii_digit() => {
+                out.push(c);
+                if chars.peek().is_some_and(|n| n.is_alphabetic()) {
+                    out.push(' ');
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    // 1 to 2
+    let words = out.split_whitespace().collect::<Vec<_>>();
+    if let [a, to, b, ..] = words.as_slice() {
+        if to.eq_ignore_ascii_case("to")
+            && parse_numeric_value(a).is_some()
+            && parse_numeric_value(b).is_some()
+        {
+            let rest = words[3..].join(" ");
+            return format!("{a}-{b} {rest}");
+        }
+    }
+    out
+}
+
+fn unicode_fraction(c: char) -> Option<&'static str> {
+    let s = match c {
+        '½' => "1/2",
+        '⅓' => "1/3",
+        '⅔' => "2/3",
+        '¼' => "1/4",
+        '¾' => "3/4",
+        '⅕' => "1/5",
+        '⅖' => "2/5",
+        '⅗' => "3/5",
+        '⅘' => "4/5",
+        '⅙' => "1/6",
+        '⅚' => "5/6",
+        '⅛' => "1/8",
+        '⅜' => "3/8",
+        '⅝' => "5/8",
+        '⅞' => "7/8",
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Removes the text in parentheses, returning it
+fn take_parentheses(line: &str) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(line.len());
+    let mut notes = Vec::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('(') {
+        let Some(close) = rest[open..].find(')').map(|i| open + i) else {
+            break;
+        };
+        out.push_str(&rest[..open]);
+        out.push(' ');
+        let note = rest[open + 1..close].trim();
+        if !note.is_empty() {
+            notes.push(note.to_string());
+        }
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    (out, notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("2 cups flour, sifted", "flour", Some("2 cups"), Some("sifted"); "unit and note")]
+    #[test_case("2 1/2 cups all-purpose flour", "all-purpose flour", Some("2 1/2 cups"), None; "mixed fraction")]
+    #[test_case("3/4 cup milk", "milk", Some("3/4 cup"), None; "fraction")]
+    #[test_case("1½ tsp salt", "salt", Some("1 1/2 tsp"), None; "unicode fraction")]
+    #[test_case("2-3 cloves garlic", "cloves garlic", Some("2-3"), None; "range")]
+    #[test_case("1 to 2 tbsp olive oil", "olive oil", Some("1-2 tbsp"), None; "to range")]
+    #[test_case("200g butter (softened)", "butter", Some("200 g"), Some("softened"); "attached unit")]
+    #[test_case("2 (14 oz) cans tomatoes, drained", "cans tomatoes", Some("2"), Some("14 oz, drained"); "parentheses and note")]
+    #[test_case("2 cups of water", "water", Some("2 cups"), None; "of after unit")]
+    #[test_case("3 sprigs of thyme", "thyme", Some("3 sprigs"), None; "unknown unit with of")]
+    #[test_case("a pinch of salt", "salt", Some("a pinch"), None; "a pinch of")]
+    #[test_case("a cup of sugar", "sugar", Some("1 cup"), None; "a unit of")]
+    #[test_case("a few basil leaves", "basil leaves", Some("a few"), None; "a few")]
+    #[test_case("- 2 large eggs", "large eggs", Some("2"), None; "bullet")]
+    #[test_case("salt and pepper to taste", "salt and pepper", None, Some("to taste"); "to taste")]
+    #[test_case("a large onion", "a large onion", None, None; "not a quantity")]
+    fn line(line: &str, name: &str, quantity: Option<&str>, note: Option<&str>) {
+        let converter = Converter::default();
+        let line = parse(line, &converter);
+        assert_eq!(line.name, name);
+        assert_eq!(line.quantity.map(|q| q.to_string()).as_deref(), quantity);
+        assert_eq!(line.note.as_deref(), note);
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     model::Ingredient,
     quantity::{GroupedQuantity, GroupedValue},
     scale::ScaleOutcome,
-    Cookware, ScaledRecipe, Value,
+    Content, Cookware, IngredientReferenceTarget, Item, ScaledRecipe, Section, Step, Value,
 };
 
 /// Ingredient with all quantities from it's references and itself grouped.
@@ -23,6 +23,9 @@ pub struct GroupedIngredient<'a> {
     /// Ingredient definition
     pub ingredient: &'a Ingredient<Value>,
     /// Grouped quantity of itself and all of it references
+    ///
+    /// The ones in sections skipped when scaling are left out, see
+    /// [`ScalableRecipe::scale_sections`](crate::ScalableRecipe::scale_sections).
     pub quantity: GroupedQuantity,
     /// Scale outcome, if scaled to a custom target
     ///
@@ -76,34 +79,19 @@ impl ScaledRecipe {
     /// ```
     pub fn group_ingredients<'a>(&'a self, converter: &Converter) -> Vec<GroupedIngredient<'a>> {
         let mut list = Vec::new();
-        let data = self.scaled_data();
         for (index, ingredient) in self.ingredients.iter().enumerate() {
             if !ingredient.relation.is_definition() {
                 continue;
             }
-            let grouped = ingredient.group_quantities(&self.ingredients, converter);
-            let outcome: Option<ScaleOutcome> = data
-                .as_ref()
-                .map(|data| {
-                    let mut outcome = &data.ingredients[index]; // temp value
-                    let all_indices = std::iter::once(index)
-                        .chain(ingredient.relation.referenced_from().iter().copied());
-                    for index in all_indices {
-                        match &data.ingredients[index] {
-                            e @ ScaleOutcome::Error(_) => return e, // if err, return
-                            e @ ScaleOutcome::Fixed => outcome = e, // if fixed, store
-                            _ => {}
-                        }
-                    }
-                    outcome
-                })
-                .cloned();
-            list.push(GroupedIngredient {
-                index,
-                ingredient,
-                quantity: grouped,
-                outcome,
-            });
+            let all_indices = std::iter::once(index)
+                .chain(ingredient.relation.referenced_from().iter().copied())
+                .filter(|&i| !self.is_skipped(Component::Ingredient, i))
+                .collect::<Vec<_>>();
+            // all in skipped sections
+            if all_indices.is_empty() {
+                continue;
+            }
+            list.push(self.grouped_ingredient(index, all_indices, converter));
         }
         list
     }
@@ -118,7 +106,17 @@ impl ScaledRecipe {
             if !cookware.relation.is_definition() {
                 continue;
             }
-            let amount = cookware.group_amounts(&self.cookware);
+            let mut all_indices = std::iter::once(index)
+                .chain(cookware.relation.referenced_from().iter().copied())
+                .filter(|&i| !self.is_skipped(Component::Cookware, i))
+                .peekable();
+            if all_indices.peek().is_none() {
+                continue;
+            }
+            let mut amount = GroupedValue::empty();
+            for value in all_indices.filter_map(|i| self.cookware[i].quantity.as_ref()) {
+                amount.add(value);
+            }
             list.push(GroupedCookware {
                 index,
                 cookware,
@@ -127,6 +125,157 @@ impl ScaledRecipe {
         }
         list
     }
+
+    /// List of the ingredients used in a step, with the quantities of all
+    /// their uses in the step grouped.
+    ///
+    /// References to an ingredient (`@&flour`) are resolved to their
+    /// definition, so [`GroupedIngredient::index`] and
+    /// [`GroupedIngredient::ingredient`] are always the definition, which may
+    /// be in another step. References to a step or section (`@&(~1)dough`)
+    /// have no definition and are listed as themselves.
+    ///
+    /// Order is the order of first use in the step. The quantity only has the
+    /// uses in the step, for the total use [`Self::group_ingredients`].
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Extensions, Converter, Content};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse("Mix @flour{500%g} and @water.\n\nAdd @&flour{100%g} and @&flour{50%g}.")
+    ///     .into_output()
+    ///     .unwrap()
+    ///     .default_scale();
+    /// let Content::Step(step) = &recipe.sections[0].content[1] else { panic!() };
+    /// let grouped = recipe.group_step_ingredients(step, parser.converter());
+    ///
+    /// // Only flour, resolved to its definition in the first step
+    /// assert_eq!(grouped.len(), 1);
+    /// assert_eq!(grouped[0].index, 0);
+    /// assert_eq!(grouped[0].quantity.to_string(), "150 g");
+    /// ```
+    pub fn group_step_ingredients<'a>(
+        &'a self,
+        step: &Step,
+        converter: &Converter,
+    ) -> Vec<GroupedIngredient<'a>> {
+        self.group_items_ingredients(step.items.iter(), converter)
+    }
+
+    /// List of the ingredients used in a section, with the quantities of all
+    /// their uses in the section grouped.
+    ///
+    /// Same as [`Self::group_step_ingredients`] for all the steps of the
+    /// section.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Extensions, Converter};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let input = "== Dough ==\nMix @flour{500%g} and @water{300%ml}.\n\nAdd @&flour{100%g}.\n\n\
+    ///              == Topping ==\nSpread @tomato{200%g}.";
+    /// let recipe = parser.parse(input).into_output().unwrap().default_scale();
+    /// let grouped = recipe.group_section_ingredients(&recipe.sections[0], parser.converter());
+    ///
+    /// assert_eq!(grouped.len(), 2);
+    /// assert_eq!(grouped[0].ingredient.name, "flour");
+    /// assert_eq!(grouped[0].quantity.to_string(), "600 g");
+    /// assert_eq!(grouped[1].ingredient.name, "water");
+    /// ```
+    pub fn group_section_ingredients<'a>(
+        &'a self,
+        section: &Section,
+        converter: &Converter,
+    ) -> Vec<GroupedIngredient<'a>> {
+        let items = section.content.iter().flat_map(|content| match content {
+            Content::Step(step) => step.items.as_slice(),
+            Content::Text(_) => &[],
+        });
+        self.group_items_ingredients(items, converter)
+    }
+
+    fn group_items_ingredients<'a, 'i>(
+        &'a self,
+        items: impl Iterator<Item = &'i Item>,
+        converter: &Converter,
+    ) -> Vec<GroupedIngredient<'a>> {
+        // definition index and the indices of its uses, in order
+        let mut uses: Vec<(usize, Vec<usize>)> = Vec::new();
+        for item in items {
+            let Item::Ingredient { index } = *item else {
+                continue;
+            };
+            let definition = match self.ingredients[index].relation.references_to() {
+                Some((definition, IngredientReferenceTarget::Ingredient)) => definition,
+                _ => index,
+            };
+            match uses.iter_mut().find(|(d, _)| *d == definition) {
+                Some((_, indices)) => indices.push(index),
+                None => uses.push((definition, vec![index])),
+            }
+        }
+
+        uses.into_iter()
+            .map(|(index, indices)| self.grouped_ingredient(index, indices, converter))
+            .collect()
+    }
+
+    /// Groups the quantities of the ingredients in `indices` for the
+    /// definition in `index`
+    fn grouped_ingredient(
+        &self,
+        index: usize,
+        indices: Vec<usize>,
+        converter: &Converter,
+    ) -> GroupedIngredient {
+        let mut quantity = GroupedQuantity::default();
+        for q in indices
+            .iter()
+            .filter_map(|&i| self.ingredients[i].quantity.as_ref())
+        {
+            quantity.add(q, converter);
+        }
+        let _ = quantity.fit(converter);
+        GroupedIngredient {
+            index,
+            ingredient: &self.ingredients[index],
+            quantity,
+            outcome: self.grouped_outcome(indices.into_iter()),
+        }
+    }
+
+    /// Checks if a component is in a section skipped when scaling
+    fn is_skipped(&self, component: Component, index: usize) -> bool {
+        let Some(data) = self.scaled_data() else {
+            return false;
+        };
+        let outcomes = match component {
+            Component::Ingredient => &data.ingredients,
+            Component::Cookware => &data.cookware,
+        };
+        matches!(outcomes.get(index), Some(ScaleOutcome::Skipped))
+    }
+
+    /// Scale outcome of many ingredients, the error or fixed one if any
+    fn grouped_outcome(&self, indices: impl Iterator<Item = usize>) -> Option<ScaleOutcome> {
+        let data = self.scaled_data()?;
+        let mut outcome = None;
+        for index in indices {
+            match &data.ingredients[index] {
+                e @ ScaleOutcome::Error(_) => return Some(e.clone()),
+                e @ ScaleOutcome::Fixed => outcome = Some(e),
+                e => {
+                    outcome.get_or_insert(e);
+                }
+            }
+        }
+        outcome.cloned()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Component {
+    Ingredient,
+    Cookware,
 }
 
 /// List of ingredients with quantities.
@@ -30,6 +30,8 @@ pub enum TokenKind {
     Hash,
     /// "~"
     Tilde,
+    /// "^"
+    Caret,
     /// "?"
     Question,
     /// "+"
@@ -92,8 +94,8 @@ fn is_word_char(c: char) -> bool {
     match c {
         c if c.is_alphabetic() => true, // quick return true
         ' ' | '\n' | '\r' | '\t' | '0'..='9' | '.' => false, // common chars that break a word
-        '>' | ':' | '@' | '#' | '~' | '?' | '+' | '-' | '/' | '*' | '&' | '|' | '=' | '%' | '{'
-        | '}' | '(' | ')' => false,
+        '>' | ':' | '@' | '#' | '~' | '^' | '?' | '+' | '-' | '/' | '*' | '&' | '|' | '=' | '%'
+        | '{' | '}' | '(' | ')' => false,
         c if c.is_separator_space() || c.is_punctuation() => false, // '\' (escape) is punctuation and not common, so I will leave it here
         _ => true,
     }
@@ -136,6 +138,7 @@ impl Cursor<'_> {
             '@' => TokenKind::At,
             '#' => TokenKind::Hash,
             '~' => TokenKind::Tilde,
+            '^' => TokenKind::Caret,
             '?' => TokenKind::Question,
             '+' => TokenKind::Plus,
             '/' => TokenKind::Slash,
@@ -222,6 +225,9 @@ macro_rules! T {
     [~] => {
         $crate::lexer::TokenKind::Tilde
     };
+    [^] => {
+        $crate::lexer::TokenKind::Caret
+    };
     [?] => {
         $crate::lexer::TokenKind::Question
     };
@@ -407,6 +413,7 @@ mod tests {
         t!("@basic", vec![At, Word]);
         t!("#basic", vec![Hash, Word]);
         t!("~basic", vec![Tilde, Word]);
+        t!("^{180}", vec![Caret, OpenBrace, Int, CloseBrace]);
         t!("@single word", vec![At, Word, Whitespace, Word]);
         t!(
             "@multi word{}",
@@ -64,6 +64,12 @@ pub mod _features {
     //!   enabled. [This is the bundled file](https://github.com/cooklang/cooklang-rs/blob/main/units.toml)
     //!
     //! - `aisle`. Enables the [`aisle`](crate::aisle) module.
+    //!
+    //! - `import`. Enables the [`import`](crate::import) module to convert
+    //!   recipes from other formats.
+    //!
+    //! - `schema`. Enables the [`metadata_schema`](crate::metadata_schema)
+    //!   module and [`ParseOptions::metadata_schema`](crate::ParseOptions).
 }
 
 #[cfg(feature = "aisle")]
@@ -72,17 +78,32 @@ pub mod analysis;
 pub mod ast;
 pub mod convert;
 pub mod error;
+pub mod export;
+pub mod highlight;
+pub mod images;
+#[cfg(feature = "import")]
+pub mod import;
+pub mod ingredient_line;
+pub mod incremental;
 pub mod ingredient_list;
+pub mod line_index;
+pub mod lint;
 pub mod located;
 pub mod metadata;
+#[cfg(feature = "schema")]
+pub mod metadata_schema;
 pub mod model;
 pub mod parser;
 pub mod quantity;
+pub mod report;
 pub mod scale;
+pub mod schedule;
 pub mod span;
+pub mod temperature;
 pub mod text;
 
 mod lexer;
+mod yaml_location;
 
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
@@ -128,6 +149,10 @@ bitflags! {
         const TIMER_REQUIRES_TIME      = 1 << 10;
         /// This extensions also enables [`Self::COMPONENT_MODIFIERS`].
         const INTERMEDIATE_PREPARATIONS = 1 << 11 | Self::COMPONENT_MODIFIERS.bits();
+        /// Mark sections as optional with `==? Sauce ==`
+        const OPTIONAL_SECTIONS        = 1 << 12;
+        /// Temperature components, like `^{180%°C}(fan)`
+        const TEMPERATURES             = 1 << 13;
 
         /// Enables a subset of extensions to maximize compatibility with other
         /// cooklang parsers.
@@ -142,7 +167,9 @@ bitflags! {
                         | Self::MODES.bits()
                         | Self::INLINE_QUANTITIES.bits()
                         | Self::RANGE_VALUES.bits()
-                        | Self::INTERMEDIATE_PREPARATIONS.bits();
+                        | Self::INTERMEDIATE_PREPARATIONS.bits()
+                        | Self::OPTIONAL_SECTIONS.bits()
+                        | Self::TEMPERATURES.bits();
     }
 }
 
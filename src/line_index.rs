@@ -0,0 +1,203 @@
+//! Conversion between byte offsets and line/column positions
+//!
+//! [`Span`]s are byte offsets. Editors and tools use lines and columns, and
+//! each one counts columns in a different unit: bytes, UTF-16 code units (LSP
+//! and JS strings) or chars. A [`LineIndex`] does the conversion both ways.
+//!
+//! ```
+//! use cooklang::line_index::{ColumnUnit, LineCol, LineIndex};
+//!
+//! let text = "Add @ñoras{}.\nBake 🍞.";
+//! let index = LineIndex::new(text);
+//! let offset = text.find('.').unwrap();
+//! assert_eq!(index.line_col(text, offset, ColumnUnit::Utf8), LineCol::new(0, 13));
+//! assert_eq!(index.line_col(text, offset, ColumnUnit::Char), LineCol::new(0, 12));
+//!
+//! let end = text.len();
+//! assert_eq!(index.line_col(text, end, ColumnUnit::Utf16), LineCol::new(1, 8));
+//! assert_eq!(index.offset(text, LineCol::new(1, 8), ColumnUnit::Utf16), end);
+//! ```
+
+use serde::Serialize;
+
+use crate::Span;
+
+/// How columns are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ColumnUnit {
+    /// Bytes
+    Utf8,
+    /// UTF-16 code units, like LSP and JS strings
+    Utf16,
+    /// Chars, unicode code points
+    Char,
+}
+
+impl ColumnUnit {
+    fn len(self, c: char) -> usize {
+        match self {
+            ColumnUnit::Utf8 => c.len_utf8(),
+            ColumnUnit::Utf16 => c.len_utf16(),
+            ColumnUnit::Char => 1,
+        }
+    }
+}
+
+/// A line and a column, both starting at 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LineCol {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// Index of where each line starts in a text
+///
+/// It does not keep the text, so every method takes it again. It has to be
+/// the same the index was built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Byte offset where each line starts
+    line_starts: Vec<usize>,
+    len: usize,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            line_starts,
+            len: text.len(),
+        }
+    }
+
+    /// Number of lines
+    ///
+    /// A text ending in a newline has an empty last line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Span of a line, with the newline if any
+    pub fn line_span(&self, line: usize) -> Option<Span> {
+        let start = *self.line_starts.get(line)?;
+        let end = self.line_starts.get(line + 1).copied().unwrap_or(self.len);
+        Some(Span::new(start, end))
+    }
+
+    /// Line and column of a byte offset
+    ///
+    /// Offsets past the end are the end of the text.
+    ///
+    /// # Panics
+    ///
+    /// If the offset is not in a char boundary.
+    pub fn line_col(&self, text: &str, offset: usize, unit: ColumnUnit) -> LineCol {
+        debug_assert_eq!(text.len(), self.len, "text is not the one indexed");
+        let offset = offset.min(text.len());
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        let start = self.line_starts[line];
+        let column = match unit {
+            ColumnUnit::Utf8 => {
+                assert!(text.is_char_boundary(offset), "offset not in char boundary");
+                offset - start
+            }
+            ColumnUnit::Utf16 => text[start..offset].encode_utf16().count(),
+            ColumnUnit::Char => text[start..offset].chars().count(),
+        };
+        LineCol { line, column }
+    }
+
+    /// Start and end positions of a span
+    pub fn range(&self, text: &str, span: Span, unit: ColumnUnit) -> (LineCol, LineCol) {
+        (
+            self.line_col(text, span.start(), unit),
+            self.line_col(text, span.end(), unit),
+        )
+    }
+
+    /// Byte offset of a line and column
+    ///
+    /// Columns past the end of a line are the end of the line, before the
+    /// newline, and lines past the end are the end of the text. Columns in
+    /// the middle of a char are the start of the char.
+    pub fn offset(&self, text: &str, pos: LineCol, unit: ColumnUnit) -> usize {
+        debug_assert_eq!(text.len(), self.len, "text is not the one indexed");
+        let Some(line) = self.line_span(pos.line) else {
+            return text.len();
+        };
+        let mut column = 0;
+        for (i, c) in text[line.range()].char_indices() {
+            column += unit.len(c);
+            if column > pos.column || c == '\n' {
+                return line.start() + i;
+            }
+        }
+        line.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const TEXT: &str = "a 🍅 b\nsecond ñ line\n\nlast";
+
+    #[test_case(ColumnUnit::Utf8 ; "utf8")]
+    #[test_case(ColumnUnit::Utf16 ; "utf16")]
+    #[test_case(ColumnUnit::Char ; "char")]
+    fn round_trip(unit: ColumnUnit) {
+        let index = LineIndex::new(TEXT);
+        for (offset, _) in TEXT.char_indices().chain([(TEXT.len(), ' ')]) {
+            let pos = index.line_col(TEXT, offset, unit);
+            assert_eq!(index.offset(TEXT, pos, unit), offset, "{pos:?}");
+        }
+    }
+
+    #[test_case(ColumnUnit::Utf8 => (0, 7) ; "utf8")]
+    #[test_case(ColumnUnit::Utf16 => (0, 5) ; "utf16")]
+    #[test_case(ColumnUnit::Char => (0, 4) ; "char")]
+    fn units(unit: ColumnUnit) -> (usize, usize) {
+        let index = LineIndex::new(TEXT);
+        let pos = index.line_col(TEXT, 7, unit);
+        (pos.line, pos.column)
+    }
+
+    #[test]
+    fn clamp() {
+        let index = LineIndex::new(TEXT);
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(
+            index.line_col(TEXT, TEXT.len() + 10, ColumnUnit::Utf8),
+            LineCol::new(3, 4)
+        );
+        assert_eq!(
+            index.offset(TEXT, LineCol::new(0, 100), ColumnUnit::Utf8),
+            8
+        );
+        assert_eq!(
+            index.offset(TEXT, LineCol::new(100, 0), ColumnUnit::Utf8),
+            TEXT.len()
+        );
+        // middle of the tomato
+        assert_eq!(index.offset(TEXT, LineCol::new(0, 3), ColumnUnit::Utf8), 2);
+        assert_eq!(index.offset(TEXT, LineCol::new(0, 3), ColumnUnit::Utf16), 2);
+    }
+
+    #[test]
+    fn line_span() {
+        let index = LineIndex::new(TEXT);
+        assert_eq!(&TEXT[index.line_span(0).unwrap().range()], "a 🍅 b\n");
+        assert_eq!(&TEXT[index.line_span(3).unwrap().range()], "last");
+        assert_eq!(index.line_span(4), None);
+    }
+}
@@ -0,0 +1,517 @@
+//! Recipe linter
+//!
+//! The parser only reports what is wrong in the syntax or can't be
+//! understood. [`lint`] checks for things that are valid but probably a
+//! mistake or bad style:
+//!
+//! - Ingredients and cookware not used in any step.
+//! - Timers without name.
+//! - Metric and imperial units in the same recipe.
+//! - Steps that are too long.
+//! - Missing metadata, like `servings` or `title`.
+//! - Ingredients defined more than once, that should be references.
+//!
+//! Every check has its own [`DiagCode`], so they can be allowed or denied like
+//! any other warning.
+//!
+//! ```
+//! # use cooklang::{CooklangParser, ParseOptions, analysis::SourceMap, lint::{lint, LintOptions}};
+//! let input = "Add @salt{} to the @water{}.\n\nAdd more @salt{}.";
+//! let parser = CooklangParser::default();
+//! let mut source_map = SourceMap::default();
+//! let options = ParseOptions {
+//!     source_map: Some(&mut source_map),
+//!     ..Default::default()
+//! };
+//! let recipe = parser.parse_with_options(input, options).into_output().unwrap();
+//! let report = lint(input, &recipe, &source_map, &parser, &LintOptions::default());
+//! let codes = report.iter().map(|d| d.code.as_str()).collect::<Vec<_>>();
+//! assert_eq!(codes, ["missing-metadata", "missing-metadata", "duplicate-ingredient"]);
+//! ```
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::{
+    analysis::SourceMap,
+    convert::System,
+    error::{label, DiagCode, LintLevel, Lints, SourceDiag, SourceReport, Stage, Suggestion},
+    metadata::StdKey,
+    parser::{Event, PullParser},
+    Content, CooklangParser, Extensions, Item, Modifiers, ScalableRecipe, Span,
+};
+
+/// Options for [`lint`]
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    /// Steps with more words than this are too long
+    ///
+    /// By default, 100.
+    pub max_step_words: usize,
+    /// Metadata every recipe should have
+    ///
+    /// By default, [`StdKey::Title`] and [`StdKey::Servings`].
+    pub required_metadata: Vec<StdKey>,
+    /// Level of the warnings by their code
+    ///
+    /// The `[allow]`, `[warn]` and `[deny]` config keys in the recipe have
+    /// preference.
+    pub lints: Lints,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            max_step_words: 100,
+            required_metadata: vec![StdKey::Title, StdKey::Servings],
+            lints: Lints::new(),
+        }
+    }
+}
+
+/// Checks a recipe for style problems
+///
+/// The `recipe` and the `source_map` have to be from parsing `input` with
+/// `parser`, see [`ParseOptions::source_map`](crate::ParseOptions::source_map).
+/// If the `source_map` was not filled, the warnings have no labels and long
+/// steps are not checked.
+pub fn lint(
+    input: &str,
+    recipe: &ScalableRecipe,
+    source_map: &SourceMap,
+    parser: &CooklangParser,
+    options: &LintOptions,
+) -> SourceReport {
+    let mut report = SourceReport::empty();
+    missing_metadata(recipe, options, &mut report);
+    unused_components(recipe, source_map, &mut report);
+    duplicate_ingredients(input, recipe, source_map, &mut report);
+    unnamed_timers(recipe, source_map, &mut report);
+    mixed_unit_systems(recipe, source_map, parser, &mut report);
+    long_steps(input, recipe, source_map, options, &mut report);
+
+    let mut lints = options.lints.clone();
+    lints.extend(&recipe_lints(input, parser.extensions()));
+    report.apply_lints(&lints);
+    report
+}
+
+/// Warning pointing to `span`, or unlabeled if there is no span
+fn warning(
+    code: DiagCode,
+    message: impl Into<crate::error::CowStr>,
+    span: Option<&Span>,
+) -> SourceDiag {
+    match span {
+        Some(span) => SourceDiag::warning(code, message, label!(span), Stage::Lint),
+        None => SourceDiag::unlabeled(code, message, crate::error::Severity::Warning, Stage::Lint),
+    }
+}
+
+fn missing_metadata(recipe: &ScalableRecipe, options: &LintOptions, report: &mut SourceReport) {
+    for &key in &options.required_metadata {
+        // aliases, like `serves`, count too
+        let present = recipe.metadata.map.keys().any(|k| {
+            k.as_str()
+                .and_then(|k| StdKey::from_str(k).ok())
+                .is_some_and(|k| k == key)
+        });
+        if !present {
+            report.warn(
+                SourceDiag::unlabeled(
+                    DiagCode::MissingMetadata,
+                    format!("Missing metadata: {key}"),
+                    crate::error::Severity::Warning,
+                    Stage::Lint,
+                )
+                .hint(format!("Add '{key}' to the frontmatter")),
+            );
+        }
+    }
+}
+
+fn unused_components(recipe: &ScalableRecipe, source_map: &SourceMap, report: &mut SourceReport) {
+    let mut used_ingredients = vec![false; recipe.ingredients.len()];
+    let mut used_cookware = vec![false; recipe.cookware.len()];
+    for section in &recipe.sections {
+        for content in &section.content {
+            let Content::Step(step) = content else {
+                continue;
+            };
+            for item in &step.items {
+                match *item {
+                    Item::Ingredient { index } => used_ingredients[index] = true,
+                    Item::Cookware { index } => used_cookware[index] = true,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    for (index, igr) in recipe.ingredients.iter().enumerate() {
+        let used = used_ingredients[index]
+            || igr
+                .relation
+                .referenced_from()
+                .iter()
+                .any(|&r| used_ingredients[r]);
+        if igr.relation.is_definition() && !used {
+            report.warn(
+                warning(
+                    DiagCode::UnusedIngredient,
+                    format!("Ingredient not used in any step: {}", igr.name),
+                    source_map.ingredients.get(index),
+                )
+                .hint(format!(
+                    "Use it in a step with '@&{}' or remove it",
+                    igr.name
+                )),
+            );
+        }
+    }
+
+    for (index, cw) in recipe.cookware.iter().enumerate() {
+        let used = used_cookware[index]
+            || cw
+                .relation
+                .referenced_from()
+                .iter()
+                .any(|&r| used_cookware[r]);
+        if cw.relation.is_definition() && !used {
+            report.warn(
+                warning(
+                    DiagCode::UnusedCookware,
+                    format!("Cookware not used in any step: {}", cw.name),
+                    source_map.cookware.get(index),
+                )
+                .hint(format!(
+                    "Use it in a step with '#&{}' or remove it",
+                    cw.name
+                )),
+            );
+        }
+    }
+}
+
+fn duplicate_ingredients(
+    input: &str,
+    recipe: &ScalableRecipe,
+    source_map: &SourceMap,
+    report: &mut SourceReport,
+) {
+    let mut first_definition = HashMap::new();
+    for (index, igr) in recipe.ingredients.iter().enumerate() {
+        if !igr.relation.is_definition() {
+            continue;
+        }
+        let first = *first_definition
+            .entry(igr.name.to_lowercase())
+            .or_insert(index);
+        // `+` means the author wants a new one
+        if first == index || igr.modifiers().contains(Modifiers::NEW) {
+            continue;
+        }
+        let mut diag = SourceDiag::unlabeled(
+            DiagCode::DuplicateIngredient,
+            format!("Ingredient defined more than once: {}", igr.name),
+            crate::error::Severity::Warning,
+            Stage::Lint,
+        )
+        .hint(
+            "Use a reference to add more of the same ingredient, or '@+' if it's a different one",
+        );
+        if let (Some(&span), Some(first)) = (
+            source_map.ingredients.get(index),
+            source_map.ingredients.get(first),
+        ) {
+            diag.add_label(label!(span, "defined again here"));
+            diag.add_label(label!(first, "first defined here"));
+            if input[span.range()].starts_with('@') {
+                diag.add_suggestion(Suggestion::new(
+                    "Make it a reference",
+                    Span::pos(span.start() + 1),
+                    "&",
+                ));
+            }
+        }
+        report.warn(diag);
+    }
+}
+
+fn unnamed_timers(recipe: &ScalableRecipe, source_map: &SourceMap, report: &mut SourceReport) {
+    for (index, timer) in recipe.timers.iter().enumerate() {
+        if timer.name.is_none() {
+            report.warn(
+                warning(
+                    DiagCode::UnnamedTimer,
+                    "Timer without name",
+                    source_map.timers.get(index),
+                )
+                .hint("Name it, like '~rest{10%min}', so it's clear what it is for"),
+            );
+        }
+    }
+}
+
+fn mixed_unit_systems(
+    recipe: &ScalableRecipe,
+    source_map: &SourceMap,
+    parser: &CooklangParser,
+    report: &mut SourceReport,
+) {
+    let converter = parser.converter();
+    let system = |unit: Option<&str>| unit.and_then(|u| converter.find_unit(u)?.system);
+
+    let mut metric = Vec::new();
+    let mut imperial = Vec::new();
+    let ingredients = recipe.ingredients.iter().enumerate().map(|(index, igr)| {
+        (
+            igr.quantity.as_ref().and_then(|q| q.unit()),
+            source_map.ingredients.get(index),
+        )
+    });
+    let inline = recipe
+        .inline_quantities
+        .iter()
+        .enumerate()
+        .map(|(index, q)| (q.unit(), source_map.inline_quantities.get(index)));
+    for (unit, span) in ingredients.chain(inline) {
+        match system(unit) {
+            Some(System::Metric) => metric.push(span),
+            Some(System::Imperial) => imperial.push(span),
+            None => {}
+        }
+    }
+    if metric.is_empty() || imperial.is_empty() {
+        return;
+    }
+
+    // point to the units of the least used system
+    let (main, other, spans) = if imperial.len() <= metric.len() {
+        ("metric", "imperial", imperial)
+    } else {
+        ("imperial", "metric", metric)
+    };
+    let mut diag = SourceDiag::unlabeled(
+        DiagCode::MixedUnitSystems,
+        "Metric and imperial units in the same recipe",
+        crate::error::Severity::Warning,
+        Stage::Lint,
+    )
+    .hint(format!("Use only {main} units"));
+    for span in spans.into_iter().flatten() {
+        diag.add_label(label!(span, "{} unit", other));
+    }
+    report.warn(diag);
+}
+
+fn long_steps(
+    input: &str,
+    recipe: &ScalableRecipe,
+    source_map: &SourceMap,
+    options: &LintOptions,
+    report: &mut SourceReport,
+) {
+    for (section, spans) in recipe.sections.iter().zip(&source_map.sections) {
+        for (content, span) in section.content.iter().zip(&spans.content) {
+            let Content::Step(step) = content else {
+                continue;
+            };
+            let words = input[span.range()].split_whitespace().count();
+            if words > options.max_step_words {
+                report.warn(
+                    warning(
+                        DiagCode::LongStep,
+                        format!("Step {} is too long: {words} words", step.number),
+                        Some(span),
+                    )
+                    .hint(format!(
+                        "Split it in shorter steps, of up to {} words",
+                        options.max_step_words
+                    )),
+                );
+            }
+        }
+    }
+}
+
+/// Lint levels from the config keys in the recipe
+fn recipe_lints(input: &str, extensions: Extensions) -> Lints {
+    let mut lints = Lints::new();
+    if !extensions.contains(Extensions::MODES) {
+        return lints;
+    }
+    for event in PullParser::new(input, extensions) {
+        let Event::Metadata { key, value } = event else {
+            continue;
+        };
+        let key = key.text_trimmed();
+        let level = key
+            .strip_prefix('[')
+            .and_then(|k| k.strip_suffix(']'))
+            .and_then(|k| LintLevel::from_str(k).ok());
+        if let Some(level) = level {
+            // unknown codes are already reported by the parser
+            lints.set_list(level, &input[value.span().range()]);
+        }
+    }
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::ParseOptions;
+
+    const META: &str = "---\ntitle: Test\nservings: 2\n---\n";
+
+    fn lint_codes(input: &str) -> Vec<(DiagCode, String)> {
+        let parser = CooklangParser::default();
+        let mut source_map = SourceMap::default();
+        let options = ParseOptions {
+            source_map: Some(&mut source_map),
+            ..Default::default()
+        };
+        let recipe = parser
+            .parse_with_options(input, options)
+            .into_output()
+            .unwrap();
+        let options = LintOptions {
+            max_step_words: 10,
+            ..Default::default()
+        };
+        lint(input, &recipe, &source_map, &parser, &options)
+            .iter()
+            .map(|d| {
+                let text = d.labels.first().map_or("", |l| &input[l.0.range()]);
+                (d.code, text.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clean() {
+        let input = format!("{META}Mix @flour{{}} in a #bowl{{}} and wait ~rest{{10%min}}.");
+        assert_eq!(lint_codes(&input), []);
+    }
+
+    #[test]
+    fn missing_metadata() {
+        let codes = lint_codes("---\nserves: 2\n---\nMix.");
+        assert_eq!(codes, [(DiagCode::MissingMetadata, String::new())]);
+    }
+
+    #[test]
+    fn unused_components() {
+        let input = indoc! {"
+            >> [mode]: components
+            @flour{} @salt{} #bowl{} #pan{}
+            >> [mode]: default
+            Mix the @&flour{} in the #&bowl{}.
+        "};
+        let input = format!("{META}{input}");
+        assert_eq!(
+            lint_codes(&input),
+            [
+                (DiagCode::UnusedIngredient, "@salt{}".to_string()),
+                (DiagCode::UnusedCookware, "#pan{}".to_string()),
+            ]
+        );
+    }
+
+    #[test_case("Add @salt{} and more @salt{}." => vec![(DiagCode::DuplicateIngredient, "@salt{}".to_string())] ; "duplicate")]
+    #[test_case("Add @salt{} and more @&salt{}." => Vec::<(DiagCode, String)>::new() ; "reference")]
+    #[test_case("Add @salt{} and more @+salt{}." => Vec::<(DiagCode, String)>::new() ; "new")]
+    #[test_case("Add ~{1%min} and ~name{2%min}." => vec![(DiagCode::UnnamedTimer, "~{1%min}".to_string())] ; "unnamed timer")]
+    #[test_case("Add @a{1%kg}, @b{1%lb} and @c{1%oz}." => vec![(DiagCode::MixedUnitSystems, "@a{1%kg}".to_string())] ; "mixed units")]
+    #[test_case("One two three four five six seven eight nine ten eleven." => vec![(DiagCode::LongStep, "One two three four five six seven eight nine ten eleven.".to_string())] ; "long step")]
+    fn checks(input: &str) -> Vec<(DiagCode, String)> {
+        lint_codes(&format!("{META}{input}"))
+    }
+
+    #[test]
+    fn without_source_map() {
+        let input = "Add @a{1%kg}, @b{1%lb} and @a{}. Use a #pan{} and wait ~{1%min}.";
+        let input = format!("{META}{input}");
+        let parser = CooklangParser::default();
+        let recipe = parser.parse(&input).into_output().unwrap();
+        let report = lint(
+            &input,
+            &recipe,
+            &SourceMap::default(),
+            &parser,
+            &LintOptions::default(),
+        );
+        let codes = report.iter().map(|d| d.code).collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            [
+                DiagCode::DuplicateIngredient,
+                DiagCode::UnnamedTimer,
+                DiagCode::MixedUnitSystems
+            ]
+        );
+        assert!(report.iter().all(|d| d.labels.is_empty()));
+    }
+
+    #[test]
+    fn duplicate_suggestion() {
+        let input = format!("{META}Add @salt{{}} and more @salt{{}}.");
+        let parser = CooklangParser::default();
+        let mut source_map = SourceMap::default();
+        let options = ParseOptions {
+            source_map: Some(&mut source_map),
+            ..Default::default()
+        };
+        let recipe = parser
+            .parse_with_options(&input, options)
+            .into_output()
+            .unwrap();
+        let report = lint(
+            &input,
+            &recipe,
+            &source_map,
+            &parser,
+            &LintOptions::default(),
+        );
+        let diag = report.iter().next().unwrap();
+        assert_eq!(
+            diag.suggestions[0].apply(&input),
+            format!("{META}Add @salt{{}} and more @&salt{{}}.")
+        );
+    }
+
+    #[test]
+    fn lint_levels() {
+        let input = indoc! {"
+            >> [allow]: duplicate-ingredient
+            >> [deny]: unnamed-timer
+            Add @salt{} and more @salt{} for ~{1%min}.
+        "};
+        let input = format!("{META}{input}");
+        let parser = CooklangParser::default();
+        let mut source_map = SourceMap::default();
+        let options = ParseOptions {
+            source_map: Some(&mut source_map),
+            ..Default::default()
+        };
+        let recipe = parser
+            .parse_with_options(&input, options)
+            .into_output()
+            .unwrap();
+        let report = lint(
+            &input,
+            &recipe,
+            &source_map,
+            &parser,
+            &LintOptions::default(),
+        );
+        let diags = report.into_vec();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code, DiagCode::UnnamedTimer);
+        assert!(diags[0].is_error());
+    }
+}
@@ -2,11 +2,16 @@
 
 use std::{borrow::Cow, num::ParseFloatError, str::FromStr};
 
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
 use thiserror::Error;
 
 use crate::{
+    analysis::SourceMap,
     convert::{ConvertError, ConvertTo, ConvertUnit, ConvertValue, PhysicalQuantity, UnknownUnit},
+    error::{label, DiagCode, Severity, SourceDiag, SourceReport, Stage},
     Converter,
 };
 
@@ -214,6 +219,233 @@ impl Metadata {
         self.get(StdKey::Locale)
             .and_then(CooklangValueExt::as_locale)
     }
+
+    /// Difficulty of the recipe
+    ///
+    /// The `difficulty` key [`as_difficulty`](CooklangValueExt::as_difficulty).
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.get(StdKey::Difficulty)
+            .and_then(CooklangValueExt::as_difficulty)
+    }
+
+    /// Images of the recipe
+    ///
+    /// The `image` key, or any of its aliases like `images`,
+    /// [`as_images`](CooklangValueExt::as_images).
+    pub fn images(&self) -> Option<Vec<Cow<str>>> {
+        self.map
+            .iter()
+            .find(|(key, _)| {
+                key.as_str()
+                    .is_some_and(|k| matches!(StdKey::from_str(k), Ok(StdKey::Images)))
+            })
+            .and_then(|(_, value)| value.as_images())
+    }
+
+    /// Course of the recipe, like `dessert`
+    ///
+    /// The `course` key normalized with the [`Vocabulary`]. `None` if it's not
+    /// in it.
+    pub fn course<'v>(&self, vocabulary: &'v Vocabulary) -> Option<&'v str> {
+        let value = self.get(StdKey::Course)?.as_str_like()?;
+        vocabulary.normalize(StdKey::Course, &value)
+    }
+
+    /// Cuisine of the recipe, like `italian`
+    ///
+    /// The `cuisine` key normalized with the [`Vocabulary`]. `None` if it's not
+    /// in it.
+    pub fn cuisine<'v>(&self, vocabulary: &'v Vocabulary) -> Option<&'v str> {
+        let value = self.get(StdKey::Cuisine)?.as_str_like()?;
+        vocabulary.normalize(StdKey::Cuisine, &value)
+    }
+
+    /// Diets the recipe is suitable for, like `vegan`
+    ///
+    /// The `diet` key [`as_tags`](CooklangValueExt::as_tags), normalized with
+    /// the [`Vocabulary`]. The diets not in it are left out.
+    pub fn diet<'v>(&self, vocabulary: &'v Vocabulary) -> Option<Vec<&'v str>> {
+        let diets = self.get(StdKey::Diet)?.as_tags()?;
+        let mut normalized = Vec::with_capacity(diets.len());
+        for diet in diets {
+            if let Some(diet) = vocabulary.normalize(StdKey::Diet, &diet) {
+                if !normalized.contains(&diet) {
+                    normalized.push(diet);
+                }
+            }
+        }
+        Some(normalized)
+    }
+
+    /// Deserializes the metadata into your own type
+    ///
+    /// Every entry that can't be deserialized is reported, not only the first
+    /// one. The errors are labeled at the key with the locations in
+    /// `source_map`, see [`ParseOptions::source_map`](crate::ParseOptions::source_map).
+    /// With an empty one, like `&SourceMap::default()`, they have no labels.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, ParseOptions, analysis::SourceMap};
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Meta {
+    ///     servings: u32,
+    ///     #[serde(default)]
+    ///     spicy: bool,
+    /// }
+    ///
+    /// let input = "---\nservings: 4\nspicy: very\n---\nAdd @chili{}.";
+    /// let mut source_map = SourceMap::default();
+    /// let options = ParseOptions {
+    ///     source_map: Some(&mut source_map),
+    ///     ..Default::default()
+    /// };
+    /// let recipe = CooklangParser::default()
+    ///     .parse_with_options(input, options)
+    ///     .into_output()
+    ///     .unwrap();
+    /// let report = recipe
+    ///     .metadata
+    ///     .deserialize_into::<Meta>(&source_map)
+    ///     .unwrap_err();
+    /// let error = report.errors().next().unwrap();
+    /// assert_eq!(&input[error.labels[0].0.range()], "spicy");
+    /// ```
+    pub fn deserialize_into<T: DeserializeOwned>(
+        &self,
+        source_map: &SourceMap,
+    ) -> Result<T, SourceReport> {
+        let mut map = self.map.clone();
+        let mut removed = Vec::new();
+        let mut report = SourceReport::empty();
+        // remove the failing entries one by one to find all the errors
+        loop {
+            let mut failed_key = None;
+            let result = T::deserialize(MetadataDeserializer {
+                map: &map,
+                failed_key: &mut failed_key,
+            });
+            match (result, failed_key) {
+                (Ok(value), _) if removed.is_empty() => return Ok(value),
+                (Ok(_), _) => return Err(report),
+                (Err(err), Some(key)) => {
+                    let span = self
+                        .map
+                        .keys()
+                        .position(|k| *k == key)
+                        .and_then(|index| source_map.metadata.get(index).copied().flatten());
+                    let message = match key.as_str() {
+                        Some(key_s) => format!("Invalid metadata entry '{key_s}': {err}"),
+                        None => format!("Invalid metadata entry: {err}"),
+                    };
+                    let diag = match span {
+                        Some(span) => SourceDiag::error(
+                            DiagCode::MetadataDeserialize,
+                            message,
+                            label!(span, "this entry"),
+                            Stage::Analysis,
+                        ),
+                        None => SourceDiag::unlabeled(
+                            DiagCode::MetadataDeserialize,
+                            message,
+                            Severity::Error,
+                            Stage::Analysis,
+                        ),
+                    };
+                    report.error(diag);
+                    map.shift_remove(&key);
+                    removed.push(key);
+                }
+                (Err(err), None) => {
+                    // like a missing field that was removed before, already reported
+                    let message = err.to_string();
+                    let reported = removed
+                        .iter()
+                        .filter_map(serde_yaml::Value::as_str)
+                        .any(|key| message.contains(&format!("`{key}`")));
+                    if !reported {
+                        let diag = SourceDiag::unlabeled(
+                            DiagCode::MetadataDeserialize,
+                            format!("Invalid metadata: {message}"),
+                            Severity::Error,
+                            Stage::Analysis,
+                        );
+                        report.error(diag);
+                    }
+                    return Err(report);
+                }
+            }
+        }
+    }
+}
+
+/// Deserializes a metadata map and remembers the key of the failing entry
+struct MetadataDeserializer<'a> {
+    map: &'a serde_yaml::Mapping,
+    failed_key: &'a mut Option<serde_yaml::Value>,
+}
+
+impl<'de> Deserializer<'de> for MetadataDeserializer<'_> {
+    type Error = serde_yaml::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(MetadataEntries {
+            iter: self.map.iter(),
+            current: None,
+            failed_key: self.failed_key,
+        })
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+struct MetadataEntries<'a> {
+    iter: serde_yaml::mapping::Iter<'a>,
+    current: Option<(&'a serde_yaml::Value, &'a serde_yaml::Value)>,
+    failed_key: &'a mut Option<serde_yaml::Value>,
+}
+
+impl<'de> MapAccess<'de> for MetadataEntries<'_> {
+    type Error = serde_yaml::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        self.current = Some((key, value));
+        // unknown fields fail here
+        seed.deserialize(key.clone()).map(Some).inspect_err(|_| {
+            *self.failed_key = Some(key.clone());
+        })
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (key, value) = self.current.take().expect("value before key");
+        seed.deserialize(value.clone()).inspect_err(|_| {
+            *self.failed_key = Some(key.clone());
+        })
+    }
 }
 
 pub trait MetaIndex: private::Sealed {
@@ -343,6 +575,19 @@ pub trait CooklangValueExt: private::Sealed {
     /// though it _should_ be `en_GB`.
     fn as_locale(&self) -> Option<(&str, Option<&str>)>;
 
+    /// Get a [`Difficulty`]
+    ///
+    /// It can be a word, like `easy`, `medium` or `hard` and some synonyms,
+    /// or a number in a 1 to 5 scale, where 1 and 2 are easy, 3 is medium and
+    /// 4 and 5 are hard.
+    fn as_difficulty(&self) -> Option<Difficulty>;
+
+    /// String or YAML sequence of strings with image paths or URLs
+    ///
+    /// Unlike [`as_tags`](CooklangValueExt::as_tags), a single string is not
+    /// split, as URLs can have commas. Empty entries removed.
+    fn as_images(&self) -> Option<Vec<Cow<str>>>;
+
     /// String or number as a string
     fn as_str_like(&self) -> Option<Cow<str>>;
 }
@@ -406,6 +651,14 @@ impl CooklangValueExt for serde_yaml::Value {
         value_as_locale(self).ok()
     }
 
+    fn as_difficulty(&self) -> Option<Difficulty> {
+        value_as_difficulty(self).ok()
+    }
+
+    fn as_images(&self) -> Option<Vec<Cow<str>>> {
+        value_as_images(self).ok()
+    }
+
     fn as_str_like(&self) -> Option<Cow<str>> {
         if let Some(s) = self.as_str() {
             Some(Cow::from(s))
@@ -547,10 +800,75 @@ fn value_as_locale(val: &serde_yaml::Value) -> Result<(&str, Option<&str>), Meta
     Err(MetadataError::InvalidLocale(s.to_string()))
 }
 
+fn value_as_difficulty(val: &serde_yaml::Value) -> Result<Difficulty, MetadataError> {
+    if let Some(n) = val.as_u64() {
+        return match n {
+            1 | 2 => Ok(Difficulty::Easy),
+            3 => Ok(Difficulty::Medium),
+            4 | 5 => Ok(Difficulty::Hard),
+            _ => Err(MetadataError::InvalidDifficulty(n.to_string())),
+        };
+    }
+    let s = val
+        .as_str()
+        .ok_or(MetadataError::expect_type(MetaType::String, val))?;
+    let difficulty = match normalize_term(s).as_str() {
+        "easy" | "simple" | "beginner" => Difficulty::Easy,
+        "medium" | "moderate" | "intermediate" => Difficulty::Medium,
+        "hard" | "difficult" | "advanced" | "expert" => Difficulty::Hard,
+        _ => return Err(MetadataError::InvalidDifficulty(s.to_string())),
+    };
+    Ok(difficulty)
+}
+
+fn value_as_images(val: &serde_yaml::Value) -> Result<Vec<Cow<str>>, MetadataError> {
+    let entries = if let Some(s) = val.as_str() {
+        vec![s]
+    } else if let Some(seq) = val.as_sequence() {
+        seq.iter()
+            .map(|val| val.as_str())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(MetadataError::BadSequenceType {
+                expected: MetaType::String,
+                got: seq.first().map(MetaType::from).unwrap_or(MetaType::Unknown),
+            })?
+    } else {
+        return Err(MetadataError::expect_type(MetaType::Sequence, val));
+    };
+    Ok(entries
+        .into_iter()
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(Cow::from)
+        .collect())
+}
+
+/// Checks that the entries in the vocabulary are in it
+fn check_vocabulary(
+    key: StdKey,
+    values: &[Cow<str>],
+    vocabulary: &Vocabulary,
+) -> Result<(), MetadataError> {
+    let unknown = values
+        .iter()
+        .filter(|v| vocabulary.normalize(key, v).is_none())
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>();
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(MetadataError::NotInVocabulary {
+            key,
+            values: unknown,
+        })
+    }
+}
+
 pub(crate) fn check_std_entry(
     key: StdKey,
     value: &serde_yaml::Value,
     converter: &Converter,
+    vocabulary: Option<&Vocabulary>,
 ) -> Result<Option<crate::scale::Servings>, MetadataError> {
     match key {
         StdKey::Servings => {
@@ -579,11 +897,31 @@ pub(crate) fn check_std_entry(
                 .as_name_and_url()
                 .ok_or(MetadataError::expect_type(MetaType::Mapping, value))?;
         }
-        StdKey::Course => {}
-        StdKey::Difficulty => {}
-        StdKey::Cuisine => {}
-        StdKey::Diet => {}
-        StdKey::Images => {}
+        StdKey::Difficulty => {
+            value_as_difficulty(value)?;
+        }
+        StdKey::Course | StdKey::Cuisine | StdKey::Diet => {
+            let values = if key == StdKey::Diet {
+                value_as_tags(value)?
+            } else {
+                let value = value
+                    .as_str_like()
+                    .ok_or(MetadataError::expect_type(MetaType::String, value))?;
+                vec![value]
+            };
+            let default;
+            let vocabulary = match vocabulary {
+                Some(vocabulary) => vocabulary,
+                None => {
+                    default = Vocabulary::default();
+                    &default
+                }
+            };
+            check_vocabulary(key, &values, vocabulary)?;
+        }
+        StdKey::Images => {
+            value_as_images(value)?;
+        }
     }
 
     Ok(None)
@@ -685,6 +1023,190 @@ pub enum RecipeTime {
     },
 }
 
+/// Difficulty of a recipe
+///
+/// See [`CooklangValueExt::as_difficulty`] for the accepted values.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// Known values of the `course`, `cuisine` and `diet` keys
+///
+/// Each [`Term`] has a name and aliases. A value is normalized to the name of
+/// the term it matches, ignoring case, `-` and `_`. So `Gluten-Free` is the
+/// `gluten free` diet.
+///
+/// The default has common values in English, but you can add your own or
+/// start from an empty one. It can also be deserialized, like from a config
+/// file, and the missing fields keep the default terms.
+///
+/// ```
+/// # use cooklang::metadata::{StdKey, Term, Vocabulary};
+/// let mut vocabulary = Vocabulary::default();
+/// assert_eq!(vocabulary.normalize(StdKey::Course, "Starter"), Some("appetizer"));
+/// assert_eq!(vocabulary.normalize(StdKey::Cuisine, "Basque"), None);
+///
+/// vocabulary.cuisines.push(Term::new("basque", ["euskal"]));
+/// assert_eq!(vocabulary.normalize(StdKey::Cuisine, "Euskal"), Some("basque"));
+/// ```
+///
+/// Values not in the vocabulary are warnings when parsing, it can be changed
+/// with [`ParseOptions::vocabulary`](crate::ParseOptions::vocabulary).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Vocabulary {
+    /// Values of the `course` key
+    pub courses: Vec<Term>,
+    /// Values of the `cuisine` key
+    pub cuisines: Vec<Term>,
+    /// Values of the `diet` key
+    pub diets: Vec<Term>,
+}
+
+/// A value of a [`Vocabulary`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Term {
+    /// The normalized value
+    pub name: String,
+    /// Other ways to write it
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl Term {
+    pub fn new<'a>(name: &str, aliases: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            name: name.to_string(),
+            aliases: aliases.into_iter().map(str::to_string).collect(),
+        }
+    }
+
+    fn matches(&self, normalized: &str) -> bool {
+        std::iter::once(&self.name)
+            .chain(&self.aliases)
+            .any(|t| normalize_term(t) == normalized)
+    }
+}
+
+const COURSES: &[(&str, &[&str])] = &[
+    ("breakfast", &[]),
+    ("brunch", &[]),
+    ("lunch", &[]),
+    ("dinner", &["supper"]),
+    ("appetizer", &["starter", "hors d'oeuvre"]),
+    ("main", &["main course", "main dish"]),
+    ("side", &["side dish"]),
+    ("soup", &[]),
+    ("salad", &[]),
+    ("dessert", &["pudding", "sweet"]),
+    ("snack", &[]),
+    ("drink", &["drinks", "beverage", "cocktail"]),
+    ("sauce", &["condiment", "dressing"]),
+    ("bread", &[]),
+];
+
+const CUISINES: &[(&str, &[&str])] = &[
+    ("american", &["usa"]),
+    ("british", &["english", "uk"]),
+    ("caribbean", &[]),
+    ("chinese", &["china"]),
+    ("french", &["france"]),
+    ("german", &["germany"]),
+    ("greek", &["greece"]),
+    ("indian", &["india"]),
+    ("italian", &["italy"]),
+    ("japanese", &["japan"]),
+    ("korean", &["korea"]),
+    ("mediterranean", &[]),
+    ("mexican", &["mexico", "tex mex"]),
+    ("middle eastern", &["levantine"]),
+    ("moroccan", &["morocco"]),
+    ("spanish", &["spain"]),
+    ("thai", &["thailand"]),
+    ("turkish", &["turkey"]),
+    ("vietnamese", &["vietnam"]),
+];
+
+const DIETS: &[(&str, &[&str])] = &[
+    ("vegan", &["plant based"]),
+    ("vegetarian", &["veggie"]),
+    ("pescatarian", &["pescetarian"]),
+    ("gluten free", &["glutenfree", "coeliac", "celiac"]),
+    ("dairy free", &["lactose free", "non dairy"]),
+    ("nut free", &[]),
+    ("low carb", &[]),
+    ("low fat", &[]),
+    ("low calorie", &[]),
+    ("low sodium", &["low salt"]),
+    ("keto", &["ketogenic"]),
+    ("paleo", &[]),
+    ("diabetic", &[]),
+    ("halal", &[]),
+    ("kosher", &[]),
+];
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        let terms = |table: &[(&str, &[&str])]| {
+            table
+                .iter()
+                .map(|(name, aliases)| Term::new(name, aliases.iter().copied()))
+                .collect()
+        };
+        Self {
+            courses: terms(COURSES),
+            cuisines: terms(CUISINES),
+            diets: terms(DIETS),
+        }
+    }
+}
+
+impl Vocabulary {
+    /// Empty vocabulary, every value will be unknown
+    pub fn empty() -> Self {
+        Self {
+            courses: Vec::new(),
+            cuisines: Vec::new(),
+            diets: Vec::new(),
+        }
+    }
+
+    /// Terms of a key, empty for the keys without vocabulary
+    pub fn terms(&self, key: StdKey) -> &[Term] {
+        match key {
+            StdKey::Course => &self.courses,
+            StdKey::Cuisine => &self.cuisines,
+            StdKey::Diet => &self.diets,
+            _ => &[],
+        }
+    }
+
+    /// Name of the term matching a value of a key
+    pub fn normalize(&self, key: StdKey, value: &str) -> Option<&str> {
+        let value = normalize_term(value);
+        self.terms(key)
+            .iter()
+            .find(|t| t.matches(&value))
+            .map(|t| t.name.as_str())
+    }
+}
+
+/// Lowercase, with `-` and `_` as spaces and the whitespace collapsed
+fn normalize_term(s: &str) -> String {
+    s.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Returns minutes
 fn parse_time(s: &str, converter: &Converter) -> Result<u32, ParseTimeError> {
     if s.is_empty() {
@@ -757,13 +1279,7 @@ fn parse_common_time_format(s: &str) -> Option<u32> {
 }
 
 fn parse_time_with_units(s: &str, converter: &Converter) -> Result<u32, ParseTimeError> {
-    let to_minutes = |value, unit| {
-        if converter.unit_count() == 0 {
-            hard_coded_time_units(value, unit)
-        } else {
-            dynamic_time_units(value, unit, converter)
-        }
-    };
+    let to_minutes = |value, unit| time_to_minutes(value, unit, converter);
 
     let mut total = 0.0;
     let mut parts = s.split_whitespace();
@@ -784,6 +1300,21 @@ fn parse_time_with_units(s: &str, converter: &Converter) -> Result<u32, ParseTim
     Ok(total.round() as u32)
 }
 
+/// Converts a value in the given time unit to minutes
+///
+/// If the converter has no units, a small hard coded set of time units is used.
+pub(crate) fn time_to_minutes(
+    value: f64,
+    unit: &str,
+    converter: &Converter,
+) -> Result<f64, ParseTimeError> {
+    if converter.unit_count() == 0 {
+        hard_coded_time_units(value, unit)
+    } else {
+        dynamic_time_units(value, unit, converter)
+    }
+}
+
 fn dynamic_time_units(
     value: f64,
     unit: &str,
@@ -851,6 +1382,10 @@ pub(crate) enum MetadataError {
     ParseTimeError(#[from] ParseTimeError),
     #[error("Invalid locale: {0}")]
     InvalidLocale(String),
+    #[error("Invalid difficulty: {0}")]
+    InvalidDifficulty(String),
+    #[error("Unknown {key}: {}", values.join(", "))]
+    NotInVocabulary { key: StdKey, values: Vec<String> },
 }
 
 impl MetadataError {
@@ -1060,4 +1595,144 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn difficulty() {
+        let d = |s: &str| {
+            serde_yaml::from_str::<serde_yaml::Value>(s)
+                .unwrap()
+                .as_difficulty()
+        };
+        assert_eq!(d("easy"), Some(Difficulty::Easy));
+        assert_eq!(d("Beginner"), Some(Difficulty::Easy));
+        assert_eq!(d("2"), Some(Difficulty::Easy));
+        assert_eq!(d("moderate"), Some(Difficulty::Medium));
+        assert_eq!(d("3"), Some(Difficulty::Medium));
+        assert_eq!(d("Difficult"), Some(Difficulty::Hard));
+        assert_eq!(d("5"), Some(Difficulty::Hard));
+        assert_eq!(d("6"), None);
+        assert_eq!(d("impossible"), None);
+        assert_eq!(d("[easy]"), None);
+    }
+
+    #[test]
+    fn images() {
+        let mut metadata = Metadata::default();
+        assert_eq!(metadata.images(), None);
+        metadata
+            .map
+            .insert("pictures".into(), "https://example.com/a,b.jpg".into());
+        assert_eq!(
+            metadata.images(),
+            Some(vec!["https://example.com/a,b.jpg".into()])
+        );
+        let value: serde_yaml::Value = serde_yaml::from_str("[a.jpg, ' ', b.png]").unwrap();
+        assert_eq!(
+            value.as_images(),
+            Some(vec!["a.jpg".into(), "b.png".into()])
+        );
+        let value: serde_yaml::Value = serde_yaml::from_str("[a.jpg, [b.png]]").unwrap();
+        assert_eq!(value.as_images(), None);
+        assert_eq!(serde_yaml::Value::from(3).as_images(), None);
+    }
+
+    #[test]
+    fn vocabulary() {
+        let mut metadata = Metadata::default();
+        let vocabulary = Vocabulary::default();
+        metadata.map.insert("course".into(), "Main Course".into());
+        metadata.map.insert("cuisine".into(), "Italy".into());
+        metadata.map.insert(
+            "diet".into(),
+            "Vegan, gluten-free, plant_based, carnivore".into(),
+        );
+        assert_eq!(metadata.course(&vocabulary), Some("main"));
+        assert_eq!(metadata.cuisine(&vocabulary), Some("italian"));
+        assert_eq!(
+            metadata.diet(&vocabulary),
+            Some(vec!["vegan", "gluten free"])
+        );
+
+        let empty = Vocabulary::empty();
+        assert_eq!(metadata.course(&empty), None);
+        assert_eq!(metadata.diet(&empty), Some(vec![]));
+
+        let custom: Vocabulary =
+            serde_yaml::from_str("courses: [{name: main, aliases: [plat]}]").unwrap();
+        assert_eq!(custom.normalize(StdKey::Course, "Plat"), Some("main"));
+        assert_eq!(custom.diets, vocabulary.diets);
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct TestMeta {
+        title: String,
+        #[serde(default)]
+        rating: Option<u8>,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    fn parse(input: &str) -> (Metadata, SourceMap) {
+        let mut source_map = SourceMap::default();
+        let options = crate::ParseOptions {
+            source_map: Some(&mut source_map),
+            ..Default::default()
+        };
+        let recipe = crate::CooklangParser::default()
+            .parse_with_options(input, options)
+            .into_output()
+            .unwrap();
+        (recipe.metadata, source_map)
+    }
+
+    #[test]
+    fn deserialize_into() {
+        let (metadata, source_map) = parse("---\ntitle: Pancakes\nrating: 4\n---\n");
+        let meta = metadata.deserialize_into::<TestMeta>(&source_map).unwrap();
+        assert_eq!(meta.title, "Pancakes");
+        assert_eq!(meta.rating, Some(4));
+        assert!(meta.tags.is_empty());
+    }
+
+    #[test]
+    fn deserialize_into_errors() {
+        let input = "---\ntitle: [1, 2]\nrating: 1000\ntags: [a]\ncolor: red\n---\n";
+        let (metadata, source_map) = parse(input);
+        let report = metadata
+            .deserialize_into::<TestMeta>(&source_map)
+            .unwrap_err();
+        let labels = report
+            .errors()
+            .map(|e| {
+                assert_eq!(e.code, DiagCode::MetadataDeserialize);
+                &input[e.labels[0].0.range()]
+            })
+            .collect::<Vec<_>>();
+        // title is also missing after removing it, but it's only reported once
+        assert_eq!(labels, ["title", "rating", "color"]);
+    }
+
+    #[test]
+    fn deserialize_into_old_syntax() {
+        let input = ">> title: Pancakes\n>> rating: good\n";
+        let (metadata, source_map) = parse(input);
+        let report = metadata
+            .deserialize_into::<TestMeta>(&source_map)
+            .unwrap_err();
+        let error = report.errors().next().unwrap();
+        assert_eq!(&input[error.labels[0].0.range()], "rating");
+    }
+
+    #[test]
+    fn deserialize_into_no_source_map() {
+        let (metadata, _) = parse("---\nrating: 4\n---\n");
+        let report = metadata
+            .deserialize_into::<TestMeta>(&SourceMap::default())
+            .unwrap_err();
+        let errors = report.errors().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].labels.is_empty());
+        assert!(errors[0].message.contains("`title`"));
+    }
 }
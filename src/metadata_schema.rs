@@ -0,0 +1,858 @@
+//! Validate the metadata with a JSON Schema
+//!
+//! A [`MetadataSchema`] can be given to the parser with
+//! [`ParseOptions::metadata_schema`](crate::ParseOptions::metadata_schema) to
+//! check the YAML frontmatter of every recipe. Each violation is a warning at
+//! the key or value with the problem, with the
+//! [`MetadataSchema`](crate::error::DiagCode::MetadataSchema) code, so they can
+//! be made errors with [`ParseOptions::lints`](crate::ParseOptions::lints).
+//!
+//! ```
+//! # use cooklang::{CooklangParser, ParseOptions, metadata_schema::MetadataSchema};
+//! let schema = MetadataSchema::from_json(r#"{
+//!     "type": "object",
+//!     "required": ["title"],
+//!     "properties": {
+//!         "title": { "type": "string" },
+//!         "rating": { "type": "integer", "minimum": 1, "maximum": 5 }
+//!     }
+//! }"#)?;
+//!
+//! let input = "---\ntitle: Pancakes\nrating: 10\n---\nMix @flour{}.";
+//! let options = ParseOptions {
+//!     metadata_schema: Some(&schema),
+//!     ..Default::default()
+//! };
+//! let result = CooklangParser::default().parse_with_options(input, options);
+//! let warning = result.report().warnings().next().unwrap();
+//! assert_eq!(warning.message, "Invalid metadata: 'rating' must be at most 5");
+//! assert_eq!(&input[warning.labels[0].0.range()], "10");
+//! # Ok::<(), cooklang::metadata_schema::SchemaError>(())
+//! ```
+//!
+//! Only a subset of JSON Schema is supported. The keywords are:
+//!
+//! - `type`, `enum` and `const`.
+//! - `properties`, `patternProperties`, `additionalProperties` and `required`.
+//! - `items`, `minItems`, `maxItems` and `uniqueItems`.
+//! - `minLength`, `maxLength` and `pattern`.
+//! - `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum` and
+//!   `multipleOf`.
+//! - `allOf`, `anyOf`, `oneOf` and `not`.
+//! - `$ref` to a schema in the same document, like `#/$defs/person`. A
+//!   reference that loops back to itself without going into a property or
+//!   item is an error.
+//!
+//! Annotations, like `title`, `description`, `default` or `format`, are
+//! ignored. The other validation keywords, like `if` or `contains`, are an
+//! error when loading the schema, so a recipe is never valid because a rule
+//! was skipped.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use regex::Regex;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+pub use crate::yaml_location::PathSegment;
+
+/// A JSON Schema for the metadata
+///
+/// See the [module](self) documentation.
+#[derive(Debug, Clone)]
+pub struct MetadataSchema {
+    root: Value,
+    patterns: HashMap<String, Regex>,
+}
+
+/// Error loading a [`MetadataSchema`]
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid schema at '{path}': {reason}")]
+    InvalidSchema { path: String, reason: String },
+    #[error("Invalid pattern '{pattern}': {source}")]
+    InvalidPattern {
+        pattern: String,
+        source: Box<regex::Error>,
+    },
+    #[error("Reference not found: '{0}'")]
+    RefNotFound(String),
+}
+
+/// A metadata value that does not match the schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// Where the value is, empty for the whole metadata
+    pub path: Vec<PathSegment>,
+    /// If the problem is the key itself and not its value, like a key not
+    /// allowed by `additionalProperties`
+    pub is_key: bool,
+    /// What is wrong, like "must be a string"
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            return write!(f, "metadata {}", self.message);
+        }
+        f.write_str("'")?;
+        for (index, segment) in self.path.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if index == 0 => f.write_str(key)?,
+                PathSegment::Key(key) => write!(f, ".{key}")?,
+                PathSegment::Index(i) => write!(f, "[{i}]")?,
+            }
+        }
+        write!(f, "' {}", self.message)
+    }
+}
+
+impl MetadataSchema {
+    /// Loads a schema from its JSON text
+    pub fn from_json(json: &str) -> Result<Self, SchemaError> {
+        Self::from_value(serde_json::from_str(json)?)
+    }
+
+    /// Loads a schema from a JSON value
+    pub fn from_value(root: Value) -> Result<Self, SchemaError> {
+        let mut patterns = HashMap::new();
+        check_schema(&root, &root, "#", &mut patterns)?;
+        Ok(Self { root, patterns })
+    }
+
+    /// Validates the metadata
+    ///
+    /// Returns all the violations found, empty if it's valid.
+    pub fn validate(&self, metadata: &serde_yaml::Mapping) -> Vec<SchemaViolation> {
+        let metadata = serde_yaml::Value::Mapping(metadata.clone());
+        let value = yaml_to_json(&metadata);
+        let mut violations = Vec::new();
+        let mut path = Vec::new();
+        self.validate_value(&self.root, &value, &mut path, &mut violations);
+        // json objects are sorted by key, back to the metadata order
+        violations.sort_by_cached_key(|v| source_order(&metadata, &v.path));
+        violations
+    }
+
+    fn is_valid(&self, schema: &Value, value: &Value) -> bool {
+        let mut violations = Vec::new();
+        self.validate_value(schema, value, &mut Vec::new(), &mut violations);
+        violations.is_empty()
+    }
+
+    fn validate_value(
+        &self,
+        schema: &Value,
+        value: &Value,
+        path: &mut Vec<PathSegment>,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                out.push(violation(path, "is not allowed"));
+                return;
+            }
+            Value::Object(schema) => schema,
+            // checked when loaded
+            _ => return,
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let target = resolve_ref(&self.root, reference).expect("checked ref");
+            self.validate_value(target, value, path, out);
+        }
+
+        if let Some(types) = schema.get("type") {
+            let types = match types {
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                other => other.as_str().into_iter().collect::<Vec<_>>(),
+            };
+            if !types.iter().any(|t| has_type(value, t)) {
+                let expected = types
+                    .iter()
+                    .map(|t| type_name(t))
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                let found = type_name(value_type(value));
+                out.push(violation(
+                    path,
+                    format!("must be {expected}, found {found}"),
+                ));
+                // the other keywords would only add noise
+                return;
+            }
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            if !values.iter().any(|v| json_eq(v, value)) {
+                let values = values.iter().map(Value::to_string).collect::<Vec<_>>();
+                out.push(violation(
+                    path,
+                    format!("must be one of: {}", values.join(", ")),
+                ));
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if !json_eq(expected, value) {
+                out.push(violation(path, format!("must be {expected}")));
+            }
+        }
+
+        match value {
+            Value::Object(map) => self.validate_object(schema, map, path, out),
+            Value::Array(items) => self.validate_array(schema, items, path, out),
+            Value::String(s) => self.validate_string(schema, s, path, out),
+            Value::Number(n) => validate_number(schema, n.as_f64().unwrap_or(f64::NAN), path, out),
+            _ => {}
+        }
+
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            for sub in schemas {
+                self.validate_value(sub, value, path, out);
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+            if !schemas.iter().any(|sub| self.is_valid(sub, value)) {
+                out.push(violation(path, "does not match any of the allowed schemas"));
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+            let matches = schemas
+                .iter()
+                .filter(|sub| self.is_valid(sub, value))
+                .count();
+            if matches != 1 {
+                out.push(violation(
+                    path,
+                    format!("must match exactly one of the schemas, matches {matches}"),
+                ));
+            }
+        }
+        if let Some(sub) = schema.get("not") {
+            if self.is_valid(sub, value) {
+                out.push(violation(path, "must not match the schema"));
+            }
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema: &Map<String, Value>,
+        map: &Map<String, Value>,
+        path: &mut Vec<PathSegment>,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    out.push(violation(
+                        path,
+                        format!("is missing the required key '{key}'"),
+                    ));
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let pattern_properties = schema.get("patternProperties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        for (key, value) in map {
+            path.push(PathSegment::Key(key.clone()));
+            let mut matched = false;
+            if let Some(sub) = properties.and_then(|p| p.get(key)) {
+                matched = true;
+                self.validate_value(sub, value, path, out);
+            }
+            for (pattern, sub) in pattern_properties.into_iter().flatten() {
+                if self.patterns[pattern].is_match(key) {
+                    matched = true;
+                    self.validate_value(sub, value, path, out);
+                }
+            }
+            match additional {
+                Some(Value::Bool(false)) if !matched => out.push(SchemaViolation {
+                    path: path.clone(),
+                    is_key: true,
+                    message: "is not an allowed key".into(),
+                }),
+                Some(sub) if !matched => self.validate_value(sub, value, path, out),
+                _ => {}
+            }
+            path.pop();
+        }
+    }
+
+    fn validate_array(
+        &self,
+        schema: &Map<String, Value>,
+        items: &[Value],
+        path: &mut Vec<PathSegment>,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                out.push(violation(path, format!("must have at least {min} items")));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if items.len() as u64 > max {
+                out.push(violation(path, format!("must have at most {max} items")));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicated = items
+                .iter()
+                .enumerate()
+                .any(|(i, a)| items[..i].iter().any(|b| json_eq(a, b)));
+            if duplicated {
+                out.push(violation(path, "must not have duplicate items"));
+            }
+        }
+        if let Some(sub) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                path.push(PathSegment::Index(index));
+                self.validate_value(sub, item, path, out);
+                path.pop();
+            }
+        }
+    }
+
+    fn validate_string(
+        &self,
+        schema: &Map<String, Value>,
+        s: &str,
+        path: &mut [PathSegment],
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if len < min {
+                out.push(violation(
+                    path,
+                    format!("must have at least {min} characters"),
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if len > max {
+                out.push(violation(
+                    path,
+                    format!("must have at most {max} characters"),
+                ));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            if !self.patterns[pattern].is_match(s) {
+                out.push(violation(
+                    path,
+                    format!("must match the pattern '{pattern}'"),
+                ));
+            }
+        }
+    }
+}
+
+fn validate_number(
+    schema: &Map<String, Value>,
+    n: f64,
+    path: &mut [PathSegment],
+    out: &mut Vec<SchemaViolation>,
+) {
+    let limit = |keyword| schema.get(keyword).and_then(Value::as_f64);
+    if let Some(min) = limit("minimum").filter(|min| n < *min) {
+        out.push(violation(path, format!("must be at least {min}")));
+    }
+    if let Some(max) = limit("maximum").filter(|max| n > *max) {
+        out.push(violation(path, format!("must be at most {max}")));
+    }
+    if let Some(min) = limit("exclusiveMinimum").filter(|min| n <= *min) {
+        out.push(violation(path, format!("must be greater than {min}")));
+    }
+    if let Some(max) = limit("exclusiveMaximum").filter(|max| n >= *max) {
+        out.push(violation(path, format!("must be less than {max}")));
+    }
+    if let Some(m) = limit("multipleOf").filter(|m| *m > 0.0 && (n / m).fract() != 0.0) {
+        out.push(violation(path, format!("must be a multiple of {m}")));
+    }
+}
+
+fn violation(path: &[PathSegment], message: impl Into<String>) -> SchemaViolation {
+    SchemaViolation {
+        path: path.to_vec(),
+        is_key: false,
+        message: message.into(),
+    }
+}
+
+/// Checks the schema is valid and compiles the patterns
+fn check_schema(
+    root: &Value,
+    schema: &Value,
+    path: &str,
+    patterns: &mut HashMap<String, Regex>,
+) -> Result<(), SchemaError> {
+    let invalid = |reason: &str| SchemaError::InvalidSchema {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    };
+    let schema = match schema {
+        Value::Bool(_) => return Ok(()),
+        Value::Object(schema) => schema,
+        _ => return Err(invalid("a schema must be an object or a boolean")),
+    };
+    if let Some(keyword) = schema
+        .keys()
+        .find(|k| UNSUPPORTED_KEYWORDS.contains(&k.as_str()))
+    {
+        return Err(invalid(&format!("unsupported keyword '{keyword}'")));
+    }
+    if let Some(reference) = schema.get("$ref") {
+        let reference = reference
+            .as_str()
+            .ok_or_else(|| invalid("$ref must be a string"))?;
+        let Some(target) = resolve_ref(root, reference) else {
+            return Err(SchemaError::RefNotFound(reference.to_string()));
+        };
+        if !is_schema_pointer(&reference[1..]) {
+            return Err(invalid("$ref must point to a schema"));
+        }
+        if is_cyclic(root, target, &mut Vec::new(), &mut HashSet::new()) {
+            return Err(invalid("$ref is cyclic"));
+        }
+    }
+    if let Some(pattern) = schema.get("pattern") {
+        compile(
+            patterns,
+            pattern
+                .as_str()
+                .ok_or_else(|| invalid("pattern must be a string"))?,
+        )?;
+    }
+    if let Some(types) = schema.get("type") {
+        let known = |t: &Value| {
+            t.as_str().is_some_and(|t| {
+                matches!(
+                    t,
+                    "null" | "boolean" | "integer" | "number" | "string" | "array" | "object"
+                )
+            })
+        };
+        let ok = match types {
+            Value::Array(types) => types.iter().all(known),
+            t => known(t),
+        };
+        if !ok {
+            return Err(invalid("unknown type"));
+        }
+    }
+
+    for keyword in ["additionalProperties", "items", "not"] {
+        if let Some(sub) = schema.get(keyword) {
+            check_schema(root, sub, &format!("{path}/{keyword}"), patterns)?;
+        }
+    }
+    for keyword in ["allOf", "anyOf", "oneOf"] {
+        if let Some(subs) = schema.get(keyword) {
+            let subs = subs
+                .as_array()
+                .ok_or_else(|| invalid(&format!("{keyword} must be an array")))?;
+            for (i, sub) in subs.iter().enumerate() {
+                check_schema(root, sub, &format!("{path}/{keyword}/{i}"), patterns)?;
+            }
+        }
+    }
+    for keyword in ["properties", "patternProperties", "$defs", "definitions"] {
+        if let Some(subs) = schema.get(keyword) {
+            let subs = subs
+                .as_object()
+                .ok_or_else(|| invalid(&format!("{keyword} must be an object")))?;
+            for (key, sub) in subs {
+                if keyword == "patternProperties" {
+                    compile(patterns, key)?;
+                }
+                check_schema(root, sub, &format!("{path}/{keyword}/{key}"), patterns)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validation keywords of JSON Schema not implemented
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "if",
+    "then",
+    "else",
+    "dependentRequired",
+    "dependentSchemas",
+    "dependencies",
+    "prefixItems",
+    "additionalItems",
+    "contains",
+    "minContains",
+    "maxContains",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "minProperties",
+    "maxProperties",
+    "propertyNames",
+    "$dynamicRef",
+    "$recursiveRef",
+];
+
+fn compile(patterns: &mut HashMap<String, Regex>, pattern: &str) -> Result<(), SchemaError> {
+    if !patterns.contains_key(pattern) {
+        let regex = Regex::new(pattern).map_err(|e| SchemaError::InvalidPattern {
+            pattern: pattern.to_string(),
+            source: Box::new(e),
+        })?;
+        patterns.insert(pattern.to_string(), regex);
+    }
+    Ok(())
+}
+
+/// Resolves a reference in the same document, like `#/$defs/person`
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+/// Checks that a JSON pointer is to a schema, like `/$defs/person` or
+/// `/properties/tags/items`, and not to any value in the document
+fn is_schema_pointer(pointer: &str) -> bool {
+    if pointer.is_empty() {
+        return true;
+    }
+    let Some(pointer) = pointer.strip_prefix('/') else {
+        return false;
+    };
+    let mut tokens = pointer.split('/');
+    while let Some(keyword) = tokens.next() {
+        let ok = match keyword {
+            "additionalProperties" | "items" | "not" => true,
+            "allOf" | "anyOf" | "oneOf" => {
+                tokens.next().is_some_and(|i| i.parse::<usize>().is_ok())
+            }
+            "properties" | "patternProperties" | "$defs" | "definitions" => tokens.next().is_some(),
+            _ => false,
+        };
+        if !ok {
+            return false;
+        }
+    }
+    true
+}
+
+/// Checks if following the `$ref`s of a schema without going into a value,
+/// like a property or an item, comes back to it
+///
+/// That would make the validation loop forever.
+fn is_cyclic<'a>(
+    root: &'a Value,
+    schema: &'a Value,
+    stack: &mut Vec<*const Value>,
+    done: &mut HashSet<*const Value>,
+) -> bool {
+    let ptr = schema as *const Value;
+    if stack.contains(&ptr) {
+        return true;
+    }
+    if !done.insert(ptr) {
+        return false;
+    }
+    let Value::Object(map) = schema else {
+        return false;
+    };
+    stack.push(ptr);
+    let target = map
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| resolve_ref(root, r));
+    let subs = ["allOf", "anyOf", "oneOf"]
+        .into_iter()
+        .filter_map(|keyword| map.get(keyword).and_then(Value::as_array))
+        .flatten()
+        .chain(map.get("not"))
+        .chain(target);
+    let mut cyclic = false;
+    for sub in subs {
+        if is_cyclic(root, sub, stack, done) {
+            cyclic = true;
+            break;
+        }
+    }
+    stack.pop();
+    cyclic
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if is_integer(n) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, t: &str) -> bool {
+    match (t, value) {
+        ("number", Value::Number(_)) => true,
+        ("integer", Value::Number(n)) => is_integer(n),
+        _ => value_type(value) == t,
+    }
+}
+
+fn is_integer(n: &serde_json::Number) -> bool {
+    n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+}
+
+fn type_name(t: &str) -> &str {
+    match t {
+        "boolean" => "a boolean",
+        "integer" => "an integer",
+        "number" => "a number",
+        "string" => "a string",
+        "array" => "an array",
+        "object" => "an object",
+        t => t,
+    }
+}
+
+/// Equality with numbers compared by value, so `1` and `1.0` are the same
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_eq(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, v)| b.get(k).is_some_and(|other| json_eq(v, other)))
+        }
+        _ => a == b,
+    }
+}
+
+/// Indices of the path segments in the yaml value
+fn source_order(mut value: &serde_yaml::Value, path: &[PathSegment]) -> Vec<usize> {
+    let mut order = Vec::with_capacity(path.len());
+    for segment in path {
+        let found = match (segment, value) {
+            (PathSegment::Key(key), serde_yaml::Value::Mapping(map)) => map
+                .iter()
+                .enumerate()
+                .find(|(_, (k, _))| k.as_str() == Some(key))
+                .map(|(i, (_, v))| (i, v)),
+            (PathSegment::Index(i), serde_yaml::Value::Sequence(items)) => {
+                items.get(*i).map(|item| (*i, item))
+            }
+            _ => None,
+        };
+        let Some((index, inner)) = found else {
+            break;
+        };
+        order.push(index);
+        value = inner;
+    }
+    order
+}
+
+fn yaml_to_json(value: &serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(*b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into()
+            } else if let Some(u) = n.as_u64() {
+                u.into()
+            } else {
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map_or(Value::Null, Value::Number)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s.clone()),
+        serde_yaml::Value::Sequence(items) => items.iter().map(yaml_to_json).collect(),
+        serde_yaml::Value::Mapping(map) => map
+            .iter()
+            .map(|(k, v)| {
+                let key = match k {
+                    serde_yaml::Value::String(s) => s.clone(),
+                    other => serde_yaml::to_string(other)
+                        .map(|s| s.trim_end().to_string())
+                        .unwrap_or_default(),
+                };
+                (key, yaml_to_json(v))
+            })
+            .collect(),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(&tagged.value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use serde_json::json;
+
+    fn validate(schema: Value, yaml: &str) -> Vec<String> {
+        let schema = MetadataSchema::from_value(schema).unwrap();
+        let map = serde_yaml::from_str(yaml).unwrap();
+        schema
+            .validate(&map)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn keywords() {
+        let schema = json!({
+            "type": "object",
+            "required": ["title", "author"],
+            "additionalProperties": false,
+            "properties": {
+                "title": { "type": "string", "minLength": 3 },
+                "servings": { "type": "integer", "exclusiveMinimum": 0 },
+                "course": { "enum": ["starter", "main", "dessert"] },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string", "pattern": "^[a-z-]+$" },
+                    "uniqueItems": true
+                },
+                "author": { "$ref": "#/$defs/person" }
+            },
+            "patternProperties": { "^x-": true },
+            "$defs": {
+                "person": {
+                    "anyOf": [
+                        { "type": "string" },
+                        { "type": "object", "required": ["name"] }
+                    ]
+                }
+            }
+        });
+        let yaml = indoc! {"
+            title: Hi
+            servings: 0
+            course: brunch
+            tags: [sweet, Quick, sweet]
+            x-internal: 1
+            color: red
+        "};
+        assert_eq!(
+            validate(schema.clone(), yaml),
+            [
+                "metadata is missing the required key 'author'",
+                "'title' must have at least 3 characters",
+                "'servings' must be greater than 0",
+                r#"'course' must be one of: "starter", "main", "dessert""#,
+                "'tags' must not have duplicate items",
+                "'tags[1]' must match the pattern '^[a-z-]+$'",
+                "'color' is not an allowed key",
+            ]
+        );
+        assert_eq!(
+            validate(schema, "title: Pancakes\nauthor: {url: x}\n"),
+            ["'author' does not match any of the allowed schemas"]
+        );
+    }
+
+    #[test]
+    fn types() {
+        let schema = json!({ "properties": { "a": { "type": ["integer", "null"] } } });
+        assert!(validate(schema.clone(), "a: 1").is_empty());
+        assert!(validate(schema.clone(), "a: 1.0").is_empty());
+        assert!(validate(schema.clone(), "a: null").is_empty());
+        assert_eq!(
+            validate(schema, "a: 1.5"),
+            ["'a' must be an integer or null, found a number"]
+        );
+    }
+
+    #[test]
+    fn invalid_schemas() {
+        assert!(matches!(
+            MetadataSchema::from_json("{"),
+            Err(SchemaError::Json(_))
+        ));
+        assert!(matches!(
+            MetadataSchema::from_value(json!({ "properties": { "a": 1 } })),
+            Err(SchemaError::InvalidSchema { path, .. }) if path == "#/properties/a"
+        ));
+        assert!(matches!(
+            MetadataSchema::from_value(json!({ "pattern": "(" })),
+            Err(SchemaError::InvalidPattern { .. })
+        ));
+        assert!(matches!(
+            MetadataSchema::from_value(json!({ "$ref": "#/$defs/nope" })),
+            Err(SchemaError::RefNotFound(_))
+        ));
+        for keyword in ["if", "contains", "minProperties", "propertyNames"] {
+            let err = MetadataSchema::from_value(json!({
+                "properties": { "a": { keyword: true } }
+            }))
+            .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("Invalid schema at '#/properties/a': unsupported keyword '{keyword}'")
+            );
+        }
+        assert!(MetadataSchema::from_value(json!({
+            "title": "Recipe", "format": "date", "x-custom": 1
+        }))
+        .is_ok());
+        let invalid_ref = |schema: serde_json::Value, at: &str| {
+            let err = MetadataSchema::from_value(schema).unwrap_err();
+            assert!(
+                matches!(&err, SchemaError::InvalidSchema { path, .. } if path == at),
+                "{err}"
+            );
+        };
+        invalid_ref(json!({ "$ref": "#" }), "#");
+        invalid_ref(
+            json!({
+                "properties": { "a": { "$ref": "#/$defs/b" } },
+                "$defs": {
+                    "b": { "anyOf": [{ "type": "string" }, { "$ref": "#/$defs/c" }] },
+                    "c": { "not": { "$ref": "#/$defs/b" } }
+                }
+            }),
+            "#/properties/a",
+        );
+        invalid_ref(
+            json!({ "required": ["a"], "properties": { "a": { "$ref": "#/required" } } }),
+            "#/properties/a",
+        );
+        invalid_ref(
+            json!({ "$defs": { "a": true }, "properties": { "b": { "$ref": "#/$defs" } } }),
+            "#/properties/b",
+        );
+        // recursion through a value is fine
+        let schema = MetadataSchema::from_value(json!({
+            "$defs": {
+                "step": {
+                    "type": "object",
+                    "properties": { "next": { "$ref": "#/$defs/step" } }
+                }
+            },
+            "properties": { "first": { "$ref": "#/$defs/step" } }
+        }))
+        .unwrap();
+        let metadata = serde_yaml::from_str("first: { next: { next: 1 } }").unwrap();
+        assert_eq!(
+            schema.validate(&metadata)[0].to_string(),
+            "'first.next.next' must be an object, found an integer"
+        );
+    }
+}
@@ -9,6 +9,7 @@ use crate::{
     metadata::Metadata,
     parser::Modifiers,
     quantity::{GroupedValue, Quantity, QuantityValue, ScalableValue, ScaledQuantity},
+    temperature::Temperature,
     GroupedQuantity, Value,
 };
 
@@ -40,6 +41,9 @@ pub struct Recipe<D, V: QuantityValue> {
     pub timers: Vec<Timer<V>>,
     /// All the inline quantities
     pub inline_quantities: Vec<ScaledQuantity>,
+    /// All the temperatures, see [`crate::temperature`]
+    #[serde(default)]
+    pub temperatures: Vec<Temperature>,
     pub(crate) data: D,
 }
 
@@ -62,21 +66,28 @@ pub struct Section {
     pub name: Option<String>,
     /// Content inside
     pub content: Vec<Content>,
+    /// The section can be skipped, like `==? Sauce ==`
+    ///
+    /// To skip it or change its quantities, scale the recipe with
+    /// [`ScalableRecipe::scale_sections`].
+    #[serde(default)]
+    pub optional: bool,
 }
 
 impl Section {
-    pub(crate) fn new(name: Option<String>) -> Section {
+    pub(crate) fn new(name: Option<String>, optional: bool) -> Section {
         Self {
             name,
             content: Vec::new(),
+            optional,
         }
     }
 
     /// Check if the section is empty
     ///
-    /// A section is empty when it has no name and no content.
+    /// A section is empty when it has no name, no content and is not optional.
     pub fn is_empty(&self) -> bool {
-        self.name.is_none() && self.content.is_empty()
+        self.name.is_none() && self.content.is_empty() && !self.optional
     }
 }
 
@@ -136,6 +147,13 @@ pub struct Step {
     /// The step numbers start at 1 in each section and increase with non
     /// text step.
     pub number: u32,
+
+    /// Images of the step
+    ///
+    /// Paths or URLs. The parser leaves this empty, see
+    /// [`Recipe::attach_images`] to fill it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
 }
 
 /// A step item
@@ -161,6 +179,9 @@ pub enum Item {
     InlineQuantity {
         index: usize,
     },
+    Temperature {
+        index: usize,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -9,6 +9,7 @@ pub(crate) fn metadata_entry<'i>(block: &mut BlockParser<'_, 'i>) -> Option<Even
     let key_tokens = block.until(|t| t == T![:]).or_else(|| {
         block.warn(
             warning!(
+                InvalidMetadata,
                 "A metadata block is invalid and it will be a step",
                 label!(block.span()),
             )
@@ -26,6 +27,7 @@ pub(crate) fn metadata_entry<'i>(block: &mut BlockParser<'_, 'i>) -> Option<Even
     if key.is_text_empty() {
         block.error(
             error!(
+                EmptyMetadataKey,
                 "Empty metadata key",
                 label!(key.span(), "write the key here"),
             )
@@ -34,6 +36,7 @@ pub(crate) fn metadata_entry<'i>(block: &mut BlockParser<'_, 'i>) -> Option<Even
     } else if value.is_text_empty() {
         block.warn(
             warning!(
+                EmptyMetadataValue,
                 format!("Empty metadata value for key: {}", key.text_trimmed()),
                 label!(value.span(), "write a value here"),
             )
@@ -14,7 +14,7 @@
 //! section    = Eq+ (section_name Eq*)
 //! sect_name  = (!Eq ANY)*
 //!
-//! step       = TextStep? (component | ANY)*
+//! step       = TextStep? (component | temp | ANY)*
 //!
 //! component  = c_kind modifiers? c_body note?
 //! c_kind     = At | Hash | Tilde
@@ -29,6 +29,8 @@
 //!
 //! note       = OpenParen (!CloseParen ANY)* CloseParen
 //!
+//! temp       = Caret c_close note?
+//!
 //! quantity   = sc_lock? num_val Whitespace !unit_sep unit
 //!            | val (unit_sep unit)?
 //! sc_lock    = Whitespace Eq Whitespace
@@ -78,7 +80,8 @@ use crate::{
 };
 
 pub(crate) use block_parser::BlockParser;
-use token_stream::{Token, TokenStream};
+pub(crate) use quantity::parse_numeric_value;
+pub(crate) use token_stream::{Token, TokenStream};
 
 /// Events generated by [`PullParser`]
 #[derive(Debug, Clone, PartialEq)]
@@ -91,7 +94,11 @@ pub enum Event<'i> {
     /// Metadata entry (single line block)
     Metadata { key: Text<'i>, value: Text<'i> },
     /// Section (single line block)
-    Section { name: Option<Text<'i>> },
+    Section {
+        name: Option<Text<'i>>,
+        /// Marked as optional with `?`, like `==? Sauce ==`
+        optional: bool,
+    },
     /// Start of an element that can contain others.
     ///
     /// If this is emitted, a later [`Event::End`] of the same kind is
@@ -110,6 +117,8 @@ pub enum Event<'i> {
     Cookware(Located<Cookware<'i>>),
     /// Timer item
     Timer(Located<Timer<'i>>),
+    /// Temperature item
+    Temperature(Located<Temperature<'i>>),
 
     /// Parser error
     ///
@@ -150,45 +159,87 @@ where
     queue: VecDeque<Event<'i>>,
     extensions: Extensions,
     old_style_metadata: bool,
+    /// The end of the last block was found looking at the next token or the
+    /// end of the input
+    block_lookahead: bool,
 }
 
 impl<'i> PullParser<'i, TokenStream<'i>> {
     /// Creates a new parser
     pub fn new(input: &'i str, extensions: Extensions) -> Self {
-        if let Some(fm) = frontmatter::parse_frontmatter(input) {
-            let mut events = VecDeque::new();
-            events.push_back(Event::YAMLFrontMatter(Text::from_str(
-                fm.yaml_text,
-                fm.yaml_offset,
-            )));
+        Self::with_tokens(input, token_stream(input), extensions)
+    }
+}
+
+/// Tokens of the cooklang text of the input, after the frontmatter if any
+pub(crate) fn token_stream(input: &str) -> TokenStream<'_> {
+    match frontmatter::parse_frontmatter(input) {
+        Some(fm) => {
             let mut tokens = TokenStream::new(fm.cooklang_text);
             tokens.offset(fm.cooklang_offset);
-            Self {
-                input,
-                tokens: tokens.peekable(),
-                block: Vec::new(),
-                extensions,
-                queue: events,
-                old_style_metadata: false,
-            }
-        } else {
-            let tokens = TokenStream::new(input);
-            Self {
-                input,
-                tokens: tokens.peekable(),
-                block: Vec::new(),
-                extensions,
-                queue: VecDeque::new(),
-                old_style_metadata: true,
-            }
+            tokens
         }
+        None => TokenStream::new(input),
     }
 }
 
+/// Offset where the cooklang text starts, if the input has a frontmatter
+pub(crate) fn frontmatter_end(input: &str) -> Option<usize> {
+    frontmatter::parse_frontmatter(input).map(|fm| fm.cooklang_offset)
+}
+
 impl<'i, T> PullParser<'i, T>
 where
     T: Iterator<Item = Token>,
 {
+    /// Creates a parser from already lexed tokens
+    ///
+    /// The tokens must be the same [`token_stream`] generates for the input.
+    pub(crate) fn with_tokens(input: &'i str, tokens: T, extensions: Extensions) -> Self {
+        let mut queue = VecDeque::new();
+        let fm = frontmatter::parse_frontmatter(input);
+        if let Some(fm) = &fm {
+            queue.push_back(Event::YAMLFrontMatter(Text::from_str(
+                fm.yaml_text,
+                fm.yaml_offset,
+            )));
+        }
+        Self {
+            input,
+            tokens: tokens.peekable(),
+            block: Vec::new(),
+            queue,
+            extensions,
+            old_style_metadata: fm.is_none(),
+            block_lookahead: false,
+        }
+    }
+
+    /// Creates a parser from the tokens after a block, to resume parsing there
+    ///
+    /// Same as [`Self::with_tokens`], but the frontmatter is not emitted again.
+    pub(crate) fn resume_with_tokens(input: &'i str, tokens: T, extensions: Extensions) -> Self {
+        let mut parser = Self::with_tokens(input, tokens, extensions);
+        parser.queue.clear();
+        parser
+    }
+
+    /// Takes the events generated so far
+    ///
+    /// With [`Self::next_block`], the events can be consumed block by block.
+    pub(crate) fn take_events(&mut self) -> impl Iterator<Item = Event<'i>> + '_ {
+        self.queue.drain(..)
+    }
+
+    /// End offset of the tokens consumed by the last block
+    ///
+    /// Also if the block depends on what comes after those tokens, the next
+    /// token or the end of the input.
+    pub(crate) fn block_end(&self) -> Option<(usize, bool)> {
+        let last = self.block.last()?;
+        Some((last.span.end(), self.block_lookahead))
+    }
+
     /// Transforms the parser into another [`Event`] iterator that only
     /// generates [`Event::Metadata`] blocks.
     ///
@@ -250,6 +301,7 @@ where
     /// Advances a block. Store the tokens, newline/eof excluded.
     pub(crate) fn next_block(&mut self) -> Option<()> {
         self.block.clear();
+        self.block_lookahead = false;
 
         // start and end are used to track the "non empty" part of the block
         let mut start = 0;
@@ -269,10 +321,14 @@ where
         if multiline {
             loop {
                 if is_single_line_marker(self.tokens.peek()) {
+                    self.block_lookahead = true;
                     break;
                 }
                 match self.pull_line() {
-                    None => break,
+                    None => {
+                        self.block_lookahead = true;
+                        break;
+                    }
                     Some(line) if line.is_empty => break,
                     _ => {}
                 }
@@ -428,15 +484,25 @@ macro_rules! mt {
 pub(crate) use mt;
 
 macro_rules! error {
-    ($msg:expr, $label:expr $(,)?) => {
-        $crate::error::SourceDiag::error($msg, $label, $crate::error::Stage::Parse)
+    ($code:ident, $msg:expr, $label:expr $(,)?) => {
+        $crate::error::SourceDiag::error(
+            $crate::error::DiagCode::$code,
+            $msg,
+            $label,
+            $crate::error::Stage::Parse,
+        )
     };
 }
 use error;
 
 macro_rules! warning {
-    ($msg:expr, $label:expr $(,)?) => {
-        $crate::error::SourceDiag::warning($msg, $label, $crate::error::Stage::Parse)
+    ($code:ident, $msg:expr, $label:expr $(,)?) => {
+        $crate::error::SourceDiag::warning(
+            $crate::error::DiagCode::$code,
+            $msg,
+            $label,
+            $crate::error::Stage::Parse,
+        )
     };
 }
 use warning;
@@ -14,7 +14,11 @@ pub enum Block<'a> {
     /// Section divider
     ///
     /// In the ast, a section does not own steps, it just exists in between.
-    Section { name: Option<Text<'a>> },
+    Section {
+        name: Option<Text<'a>>,
+        /// Marked as optional with `==? Sauce ==`
+        optional: bool,
+    },
     /// Recipe step
     Step {
         /// Items that compose the step.
@@ -35,6 +39,7 @@ pub enum Item<'a> {
     Ingredient(Box<Located<Ingredient<'a>>>),
     Cookware(Box<Located<Cookware<'a>>>),
     Timer(Box<Located<Timer<'a>>>),
+    Temperature(Box<Located<Temperature<'a>>>),
 }
 
 impl Item<'_> {
@@ -45,6 +50,7 @@ impl Item<'_> {
             Item::Ingredient(c) => c.span(),
             Item::Cookware(c) => c.span(),
             Item::Timer(c) => c.span(),
+            Item::Temperature(c) => c.span(),
         }
     }
 }
@@ -94,6 +100,19 @@ pub struct Timer<'a> {
     pub quantity: Option<Located<Quantity<'a>>>,
 }
 
+/// Temperature [`Item`]
+///
+/// Only with the [`TEMPERATURES`](crate::Extensions::TEMPERATURES) extension.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Temperature<'a> {
+    /// Value and unit, like `180 %°C`
+    ///
+    /// The unit is guaranteed to be [`Some`].
+    pub quantity: Located<Quantity<'a>>,
+    /// Oven mode text from the note, like `fan`
+    pub mode: Option<Text<'a>>,
+}
+
 /// Quantity used in [items](Item)
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub struct Quantity<'a> {
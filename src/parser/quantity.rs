@@ -2,7 +2,7 @@ use smallvec::SmallVec;
 
 use crate::{
     error::Recover,
-    error::{label, SourceDiag},
+    error::{label, SourceDiag, Suggestion},
     lexer::T,
     located::Located,
     quantity::{Number, Value},
@@ -69,12 +69,19 @@ fn parse_regular_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> ParsedQuantity<'i
     let (unit_separator, mut unit) = unit.unzip();
     if let Some(unit_text) = &unit {
         if unit_text.is_text_empty() {
+            let separator = unit_separator.unwrap();
             bp.warn(
                 warning!(
+                    EmptyUnit,
                     "Empty quantity unit",
-                    label!(unit_separator.unwrap(), "remove this")
+                    label!(separator, "remove this")
                 )
-                .hint("Add a unit or remove the separator"),
+                .hint("Add a unit or remove the separator")
+                .suggest(Suggestion::new(
+                    "Remove the separator",
+                    Span::new(separator.start(), unit_text.span().end()),
+                    "",
+                )),
             );
             unit = None;
         }
@@ -86,6 +93,25 @@ fn parse_regular_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> ParsedQuantity<'i
     }
 }
 
+/// Suggests a `%` between the number and the unit of a text value, like `10min`
+pub(crate) fn unit_separator_suggestion(input: &str, span: Span) -> Option<Suggestion> {
+    let text = &input[span.range()];
+    let number_end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .filter(|&end| end > 0)?;
+    let (number, rest) = text.split_at(number_end);
+    let unit = rest.trim_start();
+    if unit.is_empty() || number.ends_with('.') || unit.starts_with(|c: char| !c.is_alphabetic()) {
+        return None;
+    }
+    let start = span.start() + number_end;
+    Some(Suggestion::new(
+        format!("Add the unit separator: `{number}%{unit}`"),
+        Span::new(start, start + rest.len() - unit.len()),
+        "%",
+    ))
+}
+
 fn parse_advanced_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<ParsedQuantity<'i>> {
     if bp
         .tokens()
@@ -194,6 +220,7 @@ fn text_value(tokens: &[Token], offset: usize, bp: &mut BlockParser) -> Value {
     let text = bp.text(offset, tokens);
     if text.is_text_empty() {
         bp.error(error!(
+            EmptyValue,
             "Empty quantity value",
             label!(text.span(), "add value here"),
         ));
@@ -225,6 +252,21 @@ fn range_value(tokens: &[Token], bp: &BlockParser) -> Option<Result<Value, Sourc
     Some(Ok(Value::Range { start, end }))
 }
 
+/// Parses a number, fraction or range outside a recipe
+///
+/// Same rules as a quantity value, but text is not a valid value.
+pub(crate) fn parse_numeric_value(input: &str) -> Option<Value> {
+    let tokens = super::TokenStream::new(input).collect::<Vec<_>>();
+    if tokens.is_empty() {
+        return None;
+    }
+    let mut events = std::collections::VecDeque::new();
+    let bp = BlockParser::new(&tokens, input, &mut events, Extensions::RANGE_VALUES);
+    range_value(&tokens, &bp)
+        .or_else(|| numeric_value(&tokens, &bp))?
+        .ok()
+}
+
 fn not_ws_comment(t: &Token) -> bool {
     !matches!(t.kind, T![ws] | T![line comment] | T![block comment])
 }
@@ -300,7 +342,7 @@ fn frac(a: Token, b: Token, line: &BlockParser) -> Result<Number, SourceDiag> {
     let b = int(b, line)?;
 
     if b == 0 {
-        Err(error!("Division by zero", label!(span))
+        Err(error!(DivisionByZero, "Division by zero", label!(span))
             .hint("Change this please, we don't want an infinite amount of anything"))
     } else {
         Ok(Number::Fraction {
@@ -314,15 +356,24 @@ fn frac(a: Token, b: Token, line: &BlockParser) -> Result<Number, SourceDiag> {
 
 fn int(tok: Token, block: &BlockParser) -> Result<u32, SourceDiag> {
     assert_eq!(tok.kind, T![int]);
-    block
-        .token_str(tok)
-        .parse()
-        .map_err(|e| error!("Error parsing integer number", label!(tok.span)).set_source(e))
+    block.token_str(tok).parse().map_err(|e| {
+        error!(
+            InvalidNumber,
+            "Error parsing integer number",
+            label!(tok.span)
+        )
+        .set_source(e)
+    })
 }
 
 fn float(tokens: &[Token], bp: &BlockParser) -> Result<f64, SourceDiag> {
     bp.slice_str(tokens).parse::<f64>().map_err(|e| {
-        error!("Error parsing decimal number", label!(tokens_span(tokens))).set_source(e)
+        error!(
+            InvalidNumber,
+            "Error parsing decimal number",
+            label!(tokens_span(tokens))
+        )
+        .set_source(e)
     })
 }
 
@@ -576,4 +627,14 @@ mod tests {
         assert!(r.is_empty(), "source error");
         n
     }
+
+    #[test_case("2" => Some("2".to_string()))]
+    #[test_case(" 1 1/2 " => Some("1 1/2".to_string()))]
+    #[test_case("1-2" => Some("1-2".to_string()))]
+    #[test_case("1/0" => None)]
+    #[test_case("a few" => None)]
+    #[test_case("" => None)]
+    fn numeric_value_text(s: &str) -> Option<String> {
+        parse_numeric_value(s).map(|v| v.to_string())
+    }
 }
@@ -1,10 +1,11 @@
-use crate::{error::label, lexer::T};
+use crate::{error::label, lexer::T, Extensions};
 
 use super::{tokens_span, warning, BlockParser, Event};
 
 pub(crate) fn section<'i>(block: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
     block.consume(T![=])?;
     block.consume_while(|t| t == T![=]);
+    let optional = block.extension(Extensions::OPTIONAL_SECTIONS) && block.consume(T![?]).is_some();
     let name_pos = block.current_offset();
     let name_tokens = block.consume_while(|t| t != T![=]);
     let name = block.text(name_pos, name_tokens);
@@ -14,6 +15,7 @@ pub(crate) fn section<'i>(block: &mut BlockParser<'_, 'i>) -> Option<Event<'i>>
     if !block.rest().is_empty() {
         block.warn(
             warning!(
+                InvalidSection,
                 "A section block is invalid and it will be a step",
                 label!(tokens_span(block.rest()), "remove this"),
             )
@@ -27,7 +29,7 @@ pub(crate) fn section<'i>(block: &mut BlockParser<'_, 'i>) -> Option<Event<'i>>
     } else {
         Some(name)
     };
-    Some(Event::Section { name })
+    Some(Event::Section { name, optional })
 }
 
 #[cfg(test)]
@@ -69,9 +71,25 @@ mod tests {
         let event = section(&mut bp).expect("failed to parse section");
         bp.finish();
         assert!(events.is_empty());
-        let Event::Section { name } = event else {
+        let Event::Section { name, .. } = event else {
             panic!()
         };
         name.map(|text| (text.text().into_owned(), text.span()))
     }
+
+    #[test_case("==? Sauce ==", Extensions::all() => (Some(" Sauce ".to_string()), true) ; "optional")]
+    #[test_case("=?", Extensions::all() => (None, true) ; "optional no name")]
+    #[test_case("== ?Sauce ==", Extensions::all() => (Some(" ?Sauce ".to_string()), false) ; "question mark in name")]
+    #[test_case("==? Sauce ==", Extensions::empty() => (Some("? Sauce ".to_string()), false) ; "no extension")]
+    fn optional_section(input: &'static str, extensions: Extensions) -> (Option<String>, bool) {
+        let tokens = TokenStream::new(input).collect::<Vec<_>>();
+        let mut events = VecDeque::new();
+        let mut bp = BlockParser::new(&tokens, input, &mut events, extensions);
+        let event = section(&mut bp).expect("failed to parse section");
+        bp.finish();
+        let Event::Section { name, optional } = event else {
+            panic!()
+        };
+        (name.map(|text| text.text().into_owned()), optional)
+    }
 }
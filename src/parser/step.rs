@@ -6,18 +6,22 @@ use crate::{
 };
 
 use super::{
-    error, mt, quantity::parse_quantity, token_stream::Token, tokens_span, warning, BlockKind,
-    BlockParser, Event,
+    error, mt,
+    quantity::{parse_quantity, unit_separator_suggestion},
+    token_stream::Token,
+    tokens_span, warning, BlockKind, BlockParser, Event,
 };
 
 pub(crate) fn parse_step(bp: &mut BlockParser<'_, '_>) {
     bp.event(Event::Start(BlockKind::Step));
+    let temperatures = bp.extension(Extensions::TEMPERATURES);
 
     while !bp.rest().is_empty() {
         let component = match bp.peek() {
             T![@] => bp.with_recover(ingredient),
             T![#] => bp.with_recover(cookware),
             T![~] => bp.with_recover(timer),
+            T![^] if temperatures => bp.with_recover(temperature),
             _ => None,
         };
         if let Some(ev) = component {
@@ -26,7 +30,11 @@ pub(crate) fn parse_step(bp: &mut BlockParser<'_, '_>) {
             let start = bp.current_offset();
             let tokens = bp.capture_slice(|bp| {
                 bp.bump_any(); // consume the first token, this avoids entering an infinite loop
-                bp.consume_while(|t| !matches!(t, T![@] | T![#] | T![~]));
+                bp.consume_while(|t| match t {
+                    T![@] | T![#] | T![~] => false,
+                    T![^] => !temperatures,
+                    _ => true,
+                });
             });
             let text = bp.text(start, tokens);
             if !text.fragments().is_empty() {
@@ -67,6 +75,7 @@ fn comp_body<'t>(bp: &mut BlockParser<'t, '_>) -> Option<Body<'t>> {
                 if !bp.rest().is_empty() && !bp.at(T![ws]) {
                     bp.warn(
                         warning!(
+                            InvalidSingleWordName,
                             "Invalid single word name, the component will be ignored",
                             label!(
                                 Span::pos(bp.current_offset()),
@@ -166,6 +175,7 @@ fn parse_modifiers(
             if modifiers.contains(new_m) {
                 bp.error(
                     error!(
+                        DuplicateModifier,
                         format!("Duplicate modifier: {}", bp.token_str(*tok)),
                         label!(modifiers_span, "only leave one {}", bp.token_str(*tok)),
                     )
@@ -224,6 +234,7 @@ fn parse_intermediate_ref_data(
         [] => {
             bp.error(
                 error!(
+                    InvalidIntermediateRef,
                     format!("{INVALID}: empty"),
                     label!(tokens_span(slice), "add the target preparation here"),
                 )
@@ -234,6 +245,7 @@ fn parse_intermediate_ref_data(
         [rel @ mt![~], sec @ mt![=], mt![int]] => {
             bp.error(
                 error!(
+                    InvalidIntermediateRef,
                     format!("{INVALID}: wrong relative section order"),
                     label!(rel.span, "the relative marker"),
                 )
@@ -245,6 +257,7 @@ fn parse_intermediate_ref_data(
         [.., s @ mt![- | +], mt![int]] => {
             bp.error(
                 error!(
+                    InvalidIntermediateRef,
                     format!("{INVALID}: value sign"),
                     label!(s.span, "remove this"),
                 )
@@ -253,7 +266,14 @@ fn parse_intermediate_ref_data(
             return None;
         }
         _ => {
-            bp.error(error!(INVALID, label!(tokens_span(inner_slice))).hint(INTER_PREP_HELP));
+            bp.error(
+                error!(
+                    InvalidIntermediateRef,
+                    INVALID,
+                    label!(tokens_span(inner_slice))
+                )
+                .hint(INTER_PREP_HELP),
+            );
             return None;
         }
     };
@@ -261,7 +281,14 @@ fn parse_intermediate_ref_data(
     let val = match bp.token_str(i).parse::<i16>() {
         Ok(val) => val,
         Err(err) => {
-            bp.error(error!("Error parsing integer number", label!(i.span)).set_source(err));
+            bp.error(
+                error!(
+                    InvalidNumber,
+                    "Error parsing integer number",
+                    label!(i.span)
+                )
+                .set_source(err),
+            );
             return None;
         }
     };
@@ -296,6 +323,7 @@ fn parse_alias<'i>(
             );
             bp.error(
                 error!(
+                    InvalidAlias,
                     format!("Invalid {container}: multiple aliases"),
                     label!(bad_bit, "more than one alias defined here"),
                 )
@@ -305,6 +333,7 @@ fn parse_alias<'i>(
         } else if alias_text.is_text_empty() {
             bp.error(
                 error!(
+                    InvalidAlias,
                     format!("Invalid {container}: empty alias"),
                     label!(alias_sep.span, "remove this"),
                 )
@@ -386,6 +415,7 @@ fn cookware<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
             };
             bp.error(
                 error!(
+                    CookwareUnit,
                     "Invalid cookware quantity: unit",
                     label!(span, "remove this"),
                 )
@@ -405,6 +435,7 @@ fn cookware<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
             .expect("no recipe token in modifiers with recipe");
         bp.error(
             error!(
+                InvalidModifier,
                 "Invalid cookware modifiers: recipe modifier not allowed",
                 label!(pos, "remove this"),
             )
@@ -443,16 +474,17 @@ fn timer<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
     let mut quantity = body.quantity.map(|tokens| {
         let q = parse_quantity(bp, tokens);
         if q.quantity.unit.is_none() {
-            bp.error(
-                error!(
-                    "Invalid timer quantity: missing unit",
-                    label!(
-                        Span::pos(q.quantity.value.span().end()),
-                        "expected unit here"
-                    ),
-                )
-                .hint("A timer needs a unit to know the duration"),
+            let value_span = q.quantity.value.span();
+            let mut e = error!(
+                TimerMissingUnit,
+                "Invalid timer quantity: missing unit",
+                label!(Span::pos(value_span.end()), "expected unit here"),
             )
+            .hint("A timer needs a unit to know the duration");
+            if let Some(suggestion) = unit_separator_suggestion(bp.input, value_span) {
+                e.add_suggestion(suggestion);
+            }
+            bp.error(e)
         }
         q.quantity
     });
@@ -460,6 +492,7 @@ fn timer<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
     if quantity.is_none() && bp.extension(Extensions::TIMER_REQUIRES_TIME) {
         let span = body.close.unwrap_or_else(|| Span::pos(name.span().end()));
         bp.error(error!(
+            TimerMissingQuantity,
             "Invalid timer: missing quantity",
             label!(span, "expected timer duration here"),
         ));
@@ -479,6 +512,7 @@ fn timer<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
             Span::pos(name_offset)
         };
         bp.error(error!(
+            EmptyTimer,
             "Invalid timer: neither quantity nor name",
             label!(span, "expected duration or name"),
         ));
@@ -491,12 +525,62 @@ fn timer<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
     )))
 }
 
+fn temperature<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
+    // Parse
+    let start = bp.current_offset();
+    bp.consume(T![^])?;
+    let open = bp.consume(T!['{'])?.span;
+    let quantity_tokens = bp.until(|t| t == T!['}'])?;
+    let close = bp.bump(T!['}']).span;
+    let mode = note(bp);
+    let end = bp.current_offset();
+
+    // Errors
+    let quantity_not_empty = quantity_tokens
+        .iter()
+        .any(|t| !matches!(t.kind, T![ws] | T![block comment]));
+    let quantity = if quantity_not_empty {
+        let mut q = parse_quantity(bp, quantity_tokens).quantity;
+        if q.unit.is_none() {
+            let value_span = q.value.span();
+            let mut e = error!(
+                TemperatureMissingUnit,
+                "Invalid temperature quantity: missing unit",
+                label!(Span::pos(value_span.end()), "expected unit here"),
+            )
+            .hint("Use °C, °F or gas mark, like `^{180%°C}`");
+            if let Some(suggestion) = unit_separator_suggestion(bp.input, value_span) {
+                e.add_suggestion(suggestion);
+            }
+            bp.error(e);
+            q.unit = Some(Recover::recover());
+        }
+        q
+    } else {
+        bp.error(error!(
+            EmptyValue,
+            "Invalid temperature: missing quantity",
+            label!(
+                Span::new(open.start(), close.end()),
+                "expected temperature here"
+            ),
+        ));
+        Recover::recover()
+    };
+
+    Some(Event::Temperature(Located::new(
+        Temperature { quantity, mode },
+        start..end,
+    )))
+}
+
 fn check_modifiers(bp: &mut BlockParser, modifiers_tokens: &[Token], container: &'static str) {
     assert_ne!(container, INGREDIENT);
     assert_ne!(container, COOKWARE);
     if !modifiers_tokens.is_empty() {
         bp.error(
             error!(
+                InvalidModifier,
                 format!("Invalid {container}: modifiers not allowed"),
                 label!(tokens_span(modifiers_tokens), "remove this"),
             )
@@ -514,6 +598,7 @@ fn check_intermediate_data(
     if let Some(inter_data) = parsed_modifiers.intermediate_data {
         bp.error(
             error!(
+                InvalidIntermediateRef,
                 format!("Invalid {container}: intermediate preparation reference not allowed"),
                 label!(inter_data.span(), "remove this"),
             )
@@ -536,6 +621,7 @@ fn check_alias(bp: &mut BlockParser, name_tokens: &[Token], container: &'static
         );
         bp.error(
             error!(
+                InvalidAlias,
                 format!("Invalid {container}: alias not allowed"),
                 label!(to_remove, "remove this"),
             )
@@ -555,6 +641,7 @@ fn check_note(bp: &mut BlockParser, container: &'static str) {
             let end = bp.bump(T![')']).span.end();
             bp.warn(
                 warning!(
+                    NoteNotAllowed,
                     format!("A {container} cannot have a note, it will be text"),
                     label!(Span::new(start, end)),
                 )
@@ -569,6 +656,7 @@ fn check_note(bp: &mut BlockParser, container: &'static str) {
 fn check_empty_name(container: &'static str, bp: &mut BlockParser, name: &Text) {
     if name.is_text_empty() {
         bp.error(error!(
+            EmptyName,
             format!("Invalid {container} name: is empty"),
             label!(name.span(), "add a name here"),
         ));
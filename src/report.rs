@@ -0,0 +1,430 @@
+//! Machine readable diagnostics
+//!
+//! [`SourceReport::write`] is for humans. This module converts the report to
+//! [`Diagnostic`]s, with line and column positions instead of byte offsets,
+//! that can be serialized. They can be written as JSON or as a
+//! [SARIF](https://sarifweb.azurewebsites.net/) log, so CI pipelines and code
+//! review tools can annotate recipes.
+//!
+//! Lines and columns start at 1 and columns count chars (unicode code points)
+//! by default. See [`crate::line_index`] for other conversions.
+//!
+//! ```
+//! # use cooklang::CooklangParser;
+//! let input = "Add @flour{100%}.";
+//! let report = CooklangParser::default().parse(input).into_report();
+//! let diagnostics = report.to_diagnostics(input);
+//! let label = &diagnostics[0].labels[0];
+//! assert_eq!((label.start.line, label.start.column), (1, 15));
+//!
+//! let mut json = Vec::new();
+//! report.write_json("recipe.cook", input, &mut json).unwrap();
+//! ```
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::error::{DiagCode, Severity, SourceDiag, SourceReport, Stage};
+use crate::line_index::{ColumnUnit, LineIndex};
+use crate::Span;
+
+/// A [`SourceDiag`] with line and column positions
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagCode,
+    pub stage: Stage,
+    pub message: String,
+    /// Lower level error that produced the problem, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Same order as [`SourceDiag::labels`], the first is the main location
+    pub labels: Vec<DiagnosticLabel>,
+    pub hints: Vec<String>,
+    pub suggestions: Vec<DiagnosticSuggestion>,
+}
+
+/// A label of a [`Diagnostic`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiagnosticLabel {
+    pub start: Position,
+    /// Exclusive
+    pub end: Position,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// A suggestion of a [`Diagnostic`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiagnosticSuggestion {
+    pub message: String,
+    pub start: Position,
+    /// Exclusive
+    pub end: Position,
+    pub replacement: String,
+}
+
+/// A location in the source code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    /// Byte offset
+    pub offset: usize,
+    /// Line, starting at 1
+    pub line: usize,
+    /// Column, starting at 1
+    ///
+    /// In chars, unless other [`ColumnUnit`] is requested with
+    /// [`diagnostics_with_unit`].
+    pub column: usize,
+}
+
+/// Positions of the offsets of one source code
+struct Locator<'a> {
+    source_code: &'a str,
+    index: LineIndex,
+    unit: ColumnUnit,
+}
+
+impl<'a> Locator<'a> {
+    fn new(source_code: &'a str, unit: ColumnUnit) -> Self {
+        Self {
+            source_code,
+            index: LineIndex::new(source_code),
+            unit,
+        }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let pos = self.index.line_col(self.source_code, offset, self.unit);
+        Position {
+            offset,
+            line: pos.line + 1,
+            column: pos.column + 1,
+        }
+    }
+
+    fn range(&self, span: Span) -> (Position, Position) {
+        (self.position(span.start()), self.position(span.end()))
+    }
+}
+
+impl Diagnostic {
+    fn new(diag: &SourceDiag, lines: &Locator) -> Self {
+        let labels = diag
+            .labels
+            .iter()
+            .map(|(span, message)| {
+                let (start, end) = lines.range(*span);
+                DiagnosticLabel {
+                    start,
+                    end,
+                    message: message.as_ref().map(|m| m.to_string()),
+                }
+            })
+            .collect();
+        let suggestions = diag
+            .suggestions
+            .iter()
+            .map(|s| {
+                let (start, end) = lines.range(s.span);
+                DiagnosticSuggestion {
+                    message: s.message.to_string(),
+                    start,
+                    end,
+                    replacement: s.replacement.to_string(),
+                }
+            })
+            .collect();
+        Self {
+            severity: diag.severity,
+            code: diag.code,
+            stage: diag.stage,
+            message: diag.message.to_string(),
+            source: std::error::Error::source(diag).map(|e| e.to_string()),
+            labels,
+            hints: diag.hints.iter().map(|h| h.to_string()).collect(),
+            suggestions,
+        }
+    }
+}
+
+/// Converts a report to [`Diagnostic`]s
+///
+/// The `source_code` has to be the same the report comes from.
+pub fn diagnostics(report: &SourceReport, source_code: &str) -> Vec<Diagnostic> {
+    diagnostics_with_unit(report, source_code, ColumnUnit::Char)
+}
+
+/// Converts a report to [`Diagnostic`]s, with columns in the given unit
+///
+/// Useful for editors, LSP and JS count columns in [`ColumnUnit::Utf16`].
+pub fn diagnostics_with_unit(
+    report: &SourceReport,
+    source_code: &str,
+    unit: ColumnUnit,
+) -> Vec<Diagnostic> {
+    let lines = Locator::new(source_code, unit);
+    report.iter().map(|d| Diagnostic::new(d, &lines)).collect()
+}
+
+/// Writes a report as JSON
+///
+/// The output is an object with the `file` name and the `diagnostics`.
+pub fn write_json(
+    report: &SourceReport,
+    file_name: &str,
+    source_code: &str,
+    w: impl std::io::Write,
+) -> std::io::Result<()> {
+    let value = json!({
+        "file": file_name,
+        "diagnostics": diagnostics(report, source_code),
+    });
+    serde_json::to_writer_pretty(w, &value)?;
+    Ok(())
+}
+
+/// SARIF log builder
+///
+/// A log can have the reports of many files.
+///
+/// ```
+/// # use cooklang::{CooklangParser, report::Sarif};
+/// let parser = CooklangParser::default();
+/// let mut sarif = Sarif::new();
+/// for (name, input) in [("a.cook", "Add @flour{100%}."), ("b.cook", "Add @&salt{}.")] {
+///     let report = parser.parse(input).into_report();
+///     sarif.add(name, input, &report);
+/// }
+/// let log = sarif.to_json();
+/// assert_eq!(log["runs"][0]["results"].as_array().unwrap().len(), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct Sarif {
+    rules: Vec<DiagCode>,
+    results: Vec<serde_json::Value>,
+}
+
+impl Sarif {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the diagnostics of a file
+    ///
+    /// The `source_code` has to be the same the report comes from.
+    pub fn add(&mut self, file_name: &str, source_code: &str, report: &SourceReport) {
+        let lines = Locator::new(source_code, ColumnUnit::Char);
+        for diag in report.iter() {
+            let rule_index = match self.rules.iter().position(|&c| c == diag.code) {
+                Some(i) => i,
+                None => {
+                    self.rules.push(diag.code);
+                    self.rules.len() - 1
+                }
+            };
+            self.results.push(sarif_result(
+                file_name,
+                &Diagnostic::new(diag, &lines),
+                rule_index,
+            ));
+        }
+    }
+
+    /// The log as a JSON value
+    pub fn to_json(&self) -> serde_json::Value {
+        let rules = self
+            .rules
+            .iter()
+            .map(|code| json!({ "id": code }))
+            .collect::<Vec<_>>();
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "cooklang",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://cooklang.org",
+                        "rules": rules,
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": self.results,
+            }]
+        })
+    }
+
+    /// Writes the log
+    pub fn write(&self, w: impl std::io::Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(w, &self.to_json())?;
+        Ok(())
+    }
+}
+
+fn sarif_result(file_name: &str, diag: &Diagnostic, rule_index: usize) -> serde_json::Value {
+    let artifact = json!({ "uri": file_name });
+    let region = |start: &Position, end: &Position| {
+        json!({
+            "startLine": start.line,
+            "startColumn": start.column,
+            "endLine": end.line,
+            "endColumn": end.column,
+        })
+    };
+    let location = |label: &DiagnosticLabel| {
+        let mut location = json!({
+            "physicalLocation": {
+                "artifactLocation": artifact,
+                "region": region(&label.start, &label.end),
+            }
+        });
+        if let Some(message) = &label.message {
+            location["message"] = json!({ "text": message });
+        }
+        location
+    };
+
+    let mut text = diag.message.clone();
+    if let Some(source) = &diag.source {
+        text.push_str(&format!(": {source}"));
+    }
+    for hint in &diag.hints {
+        text.push_str(&format!("\nhelp: {hint}"));
+    }
+
+    let mut labels = diag.labels.iter();
+    let locations = match labels.next() {
+        Some(main) => vec![location(main)],
+        None => vec![json!({ "physicalLocation": { "artifactLocation": artifact } })],
+    };
+    let related = labels
+        .enumerate()
+        .map(|(id, label)| {
+            let mut location = location(label);
+            location["id"] = json!(id);
+            location
+        })
+        .collect::<Vec<_>>();
+    let fixes = diag
+        .suggestions
+        .iter()
+        .map(|s| {
+            json!({
+                "description": { "text": s.message },
+                "artifactChanges": [{
+                    "artifactLocation": artifact,
+                    "replacements": [{
+                        "deletedRegion": region(&s.start, &s.end),
+                        "insertedContent": { "text": s.replacement },
+                    }]
+                }]
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut result = json!({
+        "ruleId": diag.code,
+        "ruleIndex": rule_index,
+        "level": match diag.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        "message": { "text": text },
+        "locations": locations,
+    });
+    if !related.is_empty() {
+        result["relatedLocations"] = json!(related);
+    }
+    if !fixes.is_empty() {
+        result["fixes"] = json!(fixes);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CooklangParser;
+
+    #[test]
+    fn positions() {
+        let input = "ñam\n\nAdd @flour{100%}.\nMore text";
+        let lines = Locator::new(input, ColumnUnit::Char);
+        let p = |offset| {
+            let p = lines.position(offset);
+            (p.line, p.column)
+        };
+        assert_eq!(p(0), (1, 1));
+        assert_eq!(p(2), (1, 2)); // after the 2 bytes of ñ
+        assert_eq!(p(4), (1, 4)); // the newline
+        assert_eq!(p(5), (2, 1));
+        assert_eq!(p(6), (3, 1));
+        assert_eq!(p(input.len()), (4, 10));
+    }
+
+    #[test]
+    fn utf16_columns() {
+        let input = "Add 🍅 and @flour{100%}.";
+        let report = CooklangParser::default().parse(input).into_report();
+        let chars = diagnostics(&report, input);
+        let utf16 = diagnostics_with_unit(&report, input, ColumnUnit::Utf16);
+        assert_eq!(chars[0].labels[0].start.column, 21);
+        assert_eq!(utf16[0].labels[0].start.column, 22);
+        assert_eq!(
+            chars[0].labels[0].start.offset,
+            utf16[0].labels[0].start.offset
+        );
+    }
+
+    #[test]
+    fn json() {
+        let input = "---\ntitle: x\n---\nAdd @flour{100%}.";
+        let report = CooklangParser::default().parse(input).into_report();
+        let mut out = Vec::new();
+        report.write_json("recipe.cook", input, &mut out).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(value["file"], "recipe.cook");
+        let diag = &value["diagnostics"][0];
+        assert_eq!(diag["severity"], "warning");
+        assert_eq!(diag["code"], "empty-unit");
+        assert_eq!(diag["stage"], "parse");
+        assert_eq!(diag["labels"][0]["start"]["line"], 4);
+        assert_eq!(diag["labels"][0]["start"]["column"], 15);
+        assert_eq!(diag["labels"][0]["end"]["column"], 16);
+        assert_eq!(diag["labels"][0]["message"], "remove this");
+        assert_eq!(diag["suggestions"][0]["replacement"], "");
+    }
+
+    #[test]
+    fn sarif() {
+        let parser = CooklangParser::default();
+        let mut sarif = Sarif::new();
+        let a = "Add @flour{100%} and @salt{1%}.";
+        sarif.add("a.cook", a, &parser.parse(a).into_report());
+        let b = "Add @&salt{}.";
+        sarif.add("b.cook", b, &parser.parse(b).into_report());
+        let log = sarif.to_json();
+
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["id"], "empty-unit");
+        assert_eq!(rules[1]["id"], "reference-not-found");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1]["ruleIndex"], 0);
+        assert_eq!(results[2]["level"], "error");
+        let location = &results[2]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "b.cook");
+        assert_eq!(location["region"]["startColumn"], 5);
+        assert_eq!(
+            results[0]["fixes"][0]["artifactChanges"][0]["replacements"][0]["deletedRegion"]
+                ["startColumn"],
+            15
+        );
+    }
+}
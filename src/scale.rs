@@ -6,7 +6,7 @@ use thiserror::Error;
 use crate::{
     convert::Converter,
     quantity::{ScalableQuantity, ScalableValue, ScaledQuantity, TextValueError, Value},
-    Cookware, Ingredient, Quantity, ScalableRecipe, ScaledRecipe, Timer,
+    Content, Cookware, Ingredient, Item, Quantity, ScalableRecipe, ScaledRecipe, Timer,
 };
 
 /// Configures the scaling target
@@ -33,7 +33,25 @@ impl ScaleTarget {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// How to scale a section
+///
+/// Used in [`ScalableRecipe::scale_sections`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "factor", rename_all = "camelCase")]
+pub enum SectionScale {
+    /// Multiplies the factor of the recipe
+    Factor(f64),
+    /// The section is left out
+    Skip,
+}
+
+impl Default for SectionScale {
+    fn default() -> Self {
+        Self::Factor(1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Servings(pub(crate) Option<Vec<u32>>);
 
 /// Possible scaled states of a recipe
@@ -54,6 +72,9 @@ pub enum Scaled {
 pub struct ScaledData {
     /// What the target was
     pub target: ScaleTarget,
+    /// How each section was scaled. Use the same index as in the recipe.
+    #[serde(default)]
+    pub sections: Vec<SectionScale>,
     /// Outcome of scaling the ingredients. Use the same index as in the recipe.
     pub ingredients: Vec<ScaleOutcome>,
     /// Outcome of scaling the cookware items. Use the same index as in the recipe.
@@ -65,6 +86,7 @@ pub struct ScaledData {
 /// Possible outcomes from scaling a component
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub enum ScaleOutcome {
     /// Success
     Scaled,
@@ -72,6 +94,10 @@ pub enum ScaleOutcome {
     Fixed,
     /// It has no quantity, so it can't be scaled
     NoQuantity,
+    /// It's in a skipped section
+    ///
+    /// The quantity is scaled with the recipe factor, but it's not used.
+    Skipped,
     /// Error scaling
     Error(#[serde(skip)] ScaleError),
 }
@@ -109,12 +135,77 @@ impl ScalableRecipe {
     /// Note that this returns a [`ScaledRecipe`] wich doesn't implement this
     /// method. A recipe can only be scaled once.
     pub fn scale(self, factor: f64, converter: &Converter) -> ScaledRecipe {
+        self.scale_sections(factor, &[], converter)
+    }
+
+    /// Scale a recipe with a different factor for some sections
+    ///
+    /// `sections` has the index of a section in [`Recipe::sections`] and how
+    /// to scale it. The components of the other sections, or not in any
+    /// section, are scaled by `factor`.
+    ///
+    /// The components of skipped sections have the [`ScaleOutcome::Skipped`]
+    /// and are left out of [`ScaledRecipe::group_ingredients`] and
+    /// [`ScaledRecipe::group_cookware`].
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, scale::SectionScale};
+    /// let parser = CooklangParser::default();
+    /// let input = "Cook @pasta{200%g}.\n\n==? Sauce ==\nMix @tomato{100%g} and @pasta water{50%ml}.";
+    /// let recipe = || parser.parse(input).into_output().unwrap();
+    /// assert!(recipe().sections[1].optional);
+    ///
+    /// // double the sauce
+    /// let doubled =
+    ///     recipe().scale_sections(1.0, &[(1, SectionScale::Factor(2.0))], parser.converter());
+    /// let grouped = doubled.group_ingredients(parser.converter());
+    /// assert_eq!(grouped[1].quantity.to_string(), "200 g");
+    ///
+    /// // or skip it
+    /// let skipped = recipe().scale_sections(1.0, &[(1, SectionScale::Skip)], parser.converter());
+    /// assert_eq!(skipped.group_ingredients(parser.converter()).len(), 1);
+    /// ```
+    ///
+    /// [`Recipe::sections`]: crate::Recipe::sections
+    pub fn scale_sections(
+        self,
+        factor: f64,
+        sections: &[(usize, SectionScale)],
+        converter: &Converter,
+    ) -> ScaledRecipe {
         let target = ScaleTarget::new(factor);
 
+        let mut section_scales = vec![SectionScale::default(); self.sections.len()];
+        for &(index, scale) in sections {
+            if let Some(s) = section_scales.get_mut(index) {
+                *s = scale;
+            }
+        }
+
+        // scale of each component, from the section it's in
+        let mut ingredient_scales = vec![SectionScale::default(); self.ingredients.len()];
+        let mut cookware_scales = vec![SectionScale::default(); self.cookware.len()];
+        let mut timer_scales = vec![SectionScale::default(); self.timers.len()];
+        for (section, &scale) in self.sections.iter().zip(&section_scales) {
+            let items = section.content.iter().flat_map(|content| match content {
+                Content::Step(step) => step.items.as_slice(),
+                Content::Text(_) => &[],
+            });
+            for item in items {
+                match *item {
+                    Item::Ingredient { index } => ingredient_scales[index] = scale,
+                    Item::Cookware { index } => cookware_scales[index] = scale,
+                    Item::Timer { index } => timer_scales[index] = scale,
+                    Item::Text { .. } | Item::InlineQuantity { .. } | Item::Temperature { .. } => {}
+                }
+            }
+        }
+
         let (ingredients, ingredient_outcomes): (Vec<_>, Vec<_>) = self
             .ingredients
             .into_iter()
-            .map(|i| i.scale(target))
+            .zip(ingredient_scales)
+            .map(|(i, scale)| scale_in_section(i, target, scale))
             .map(|(mut i, o)| {
                 if let Some(q) = &mut i.quantity {
                     let _ = q.fit(converter);
@@ -123,13 +214,18 @@ impl ScalableRecipe {
             })
             .unzip();
 
-        let (cookware, cookware_outcomes): (Vec<_>, Vec<_>) =
-            self.cookware.into_iter().map(|c| c.scale(target)).unzip();
+        let (cookware, cookware_outcomes): (Vec<_>, Vec<_>) = self
+            .cookware
+            .into_iter()
+            .zip(cookware_scales)
+            .map(|(c, scale)| scale_in_section(c, target, scale))
+            .unzip();
 
         let (timers, timer_outcomes): (Vec<_>, Vec<_>) = self
             .timers
             .into_iter()
-            .map(|c| c.scale(target))
+            .zip(timer_scales)
+            .map(|(t, scale)| scale_in_section(t, target, scale))
             .map(|(mut t, o)| {
                 if let Some(q) = &mut t.quantity {
                     let _ = q.fit(converter);
@@ -140,6 +236,7 @@ impl ScalableRecipe {
 
         let data = ScaledData {
             target,
+            sections: section_scales,
             ingredients: ingredient_outcomes,
             cookware: cookware_outcomes,
             timers: timer_outcomes,
@@ -152,6 +249,7 @@ impl ScalableRecipe {
             cookware,
             timers,
             inline_quantities: self.inline_quantities,
+            temperatures: self.temperatures,
             data: Scaled::Scaled(data),
         }
     }
@@ -190,6 +288,7 @@ impl ScalableRecipe {
             cookware,
             timers,
             inline_quantities: self.inline_quantities,
+            temperatures: self.temperatures,
             data: Scaled::DefaultScaling,
         }
     }
@@ -202,6 +301,17 @@ trait Scale: Sized {
     fn default_scale(self) -> Self::Output;
 }
 
+fn scale_in_section<T: Scale>(
+    component: T,
+    target: ScaleTarget,
+    section: SectionScale,
+) -> (T::Output, ScaleOutcome) {
+    match section {
+        SectionScale::Factor(factor) => component.scale(ScaleTarget::new(target.factor() * factor)),
+        SectionScale::Skip => (component.scale(target).0, ScaleOutcome::Skipped),
+    }
+}
+
 impl Scale for ScalableValue {
     type Output = Value;
 
@@ -373,6 +483,15 @@ impl ScaledRecipe {
         }
     }
 
+    /// Checks if a section was skipped when scaling
+    ///
+    /// See [`ScalableRecipe::scale_sections`]. Renderers should leave these
+    /// sections out.
+    pub fn is_section_skipped(&self, index: usize) -> bool {
+        self.scaled_data()
+            .is_some_and(|data| matches!(data.sections.get(index), Some(SectionScale::Skip)))
+    }
+
     /// Shorthand to check if [`Self::scaled_data`] is [`Scaled::DefaultScaling`].
     pub fn is_default_scaled(&self) -> bool {
         matches!(self.data, Scaled::DefaultScaling)
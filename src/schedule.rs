@@ -0,0 +1,697 @@
+//! Step dependency graph and cooking schedule
+//!
+//! Steps depend on each other when a component used in one of them comes from
+//! another one. This happens with:
+//! - Intermediate preparations (`@&(~1)dough{}`), which depend on the referenced
+//!   step or on all the steps of the referenced section.
+//! - Ingredient and cookware references (`@&flour{}`, `#&pan{}`), which depend
+//!   on the step where the definition is.
+//!
+//! With that, a [`StepGraph`] can be built and from it a [`Schedule`] telling
+//! which steps can overlap. Steps that can overlap but need the same piece of
+//! cookware are reported with [`check_cookware_conflicts`].
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    analysis::SourceMap,
+    convert::Converter,
+    error::{label, DiagCode, Severity, SourceDiag, SourceReport, Stage},
+    metadata::time_to_minutes,
+    quantity::QuantityValue,
+    Content, IngredientReferenceTarget, Item, Modifiers, Recipe, ScaledRecipe, Value,
+};
+
+/// Location of a step in a recipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct StepRef {
+    /// Index in [`Recipe::sections`]
+    pub section: usize,
+    /// Index in [`Section::content`](crate::Section::content). It's guaranteed
+    /// that the content is a step.
+    pub content: usize,
+}
+
+/// Dependency graph of the steps of a recipe
+///
+/// Nodes are the steps of the recipe in document order. Every dependency of a
+/// step is before it, so the node order is always a valid order to cook the
+/// recipe.
+///
+/// Created from [`Recipe::step_graph`].
+#[derive(Debug, Clone, Serialize)]
+pub struct StepGraph {
+    steps: Vec<StepRef>,
+    dependencies: Vec<Vec<usize>>,
+    dependents: Vec<Vec<usize>>,
+    ingredient_nodes: Vec<Option<usize>>,
+    cookware_nodes: Vec<Option<usize>>,
+}
+
+impl<D, V: QuantityValue> Recipe<D, V> {
+    /// Builds the dependency graph of the steps
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Extensions, Converter};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser.parse(r#"
+    /// Mix @flour{} and @water{}.
+    ///
+    /// Chop the @onion{}.
+    ///
+    /// Knead the @&(~2)dough{}.
+    /// "#).into_output().unwrap();
+    /// let graph = recipe.step_graph();
+    ///
+    /// assert_eq!(graph.len(), 3);
+    /// assert_eq!(graph.dependencies(2), &[0]);
+    /// assert!(graph.can_overlap(0, 1));
+    /// ```
+    pub fn step_graph(&self) -> StepGraph {
+        StepGraph::new(self)
+    }
+}
+
+impl StepGraph {
+    fn new<D, V: QuantityValue>(recipe: &Recipe<D, V>) -> Self {
+        let mut steps = Vec::new();
+        let mut ingredient_nodes = vec![None; recipe.ingredients.len()];
+        let mut cookware_nodes = vec![None; recipe.cookware.len()];
+
+        for (section_index, section) in recipe.sections.iter().enumerate() {
+            for (content_index, content) in section.content.iter().enumerate() {
+                let Content::Step(step) = content else {
+                    continue;
+                };
+                let node = steps.len();
+                steps.push(StepRef {
+                    section: section_index,
+                    content: content_index,
+                });
+                for item in &step.items {
+                    match *item {
+                        Item::Ingredient { index } => ingredient_nodes[index] = Some(node),
+                        Item::Cookware { index } => cookware_nodes[index] = Some(node),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let find = |step: StepRef| steps.binary_search(&step).ok();
+
+        let mut dependencies: Vec<Vec<usize>> = vec![Vec::new(); steps.len()];
+        for (index, ingredient) in recipe.ingredients.iter().enumerate() {
+            let Some(node) = ingredient_nodes[index] else {
+                continue;
+            };
+            let Some((target_index, target)) = ingredient.relation.references_to() else {
+                continue;
+            };
+            match target {
+                IngredientReferenceTarget::Ingredient => {
+                    dependencies[node].extend(ingredient_nodes[target_index]);
+                }
+                IngredientReferenceTarget::Step => {
+                    let step = StepRef {
+                        section: steps[node].section,
+                        content: target_index,
+                    };
+                    dependencies[node].extend(find(step));
+                }
+                IngredientReferenceTarget::Section => {
+                    let section_steps = steps
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, s)| s.section == target_index)
+                        .map(|(n, _)| n);
+                    dependencies[node].extend(section_steps);
+                }
+            }
+        }
+        for (index, cookware) in recipe.cookware.iter().enumerate() {
+            if let (Some(node), Some(target_index)) =
+                (cookware_nodes[index], cookware.relation.references_to())
+            {
+                dependencies[node].extend(cookware_nodes[target_index]);
+            }
+        }
+
+        let mut dependents = vec![Vec::new(); steps.len()];
+        for (node, deps) in dependencies.iter_mut().enumerate() {
+            // references can only point backwards, but make sure the graph
+            // is acyclic and there are no self loops
+            deps.retain(|&d| d < node);
+            deps.sort_unstable();
+            deps.dedup();
+            for &d in deps.iter() {
+                dependents[d].push(node);
+            }
+        }
+
+        Self {
+            steps,
+            dependencies,
+            dependents,
+            ingredient_nodes,
+            cookware_nodes,
+        }
+    }
+
+    /// Number of steps in the graph
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Checks if the graph has no steps
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Steps of the graph in document order
+    ///
+    /// The index in this slice is the node used in the rest of the methods.
+    pub fn steps(&self) -> &[StepRef] {
+        &self.steps
+    }
+
+    /// Get the node of a step
+    pub fn node(&self, step: StepRef) -> Option<usize> {
+        self.steps.binary_search(&step).ok()
+    }
+
+    /// Node of the step where an ingredient is used
+    ///
+    /// The index is the same as in [`Recipe::ingredients`]. Returns `None` if
+    /// the ingredient is not in a step.
+    pub fn ingredient_node(&self, index: usize) -> Option<usize> {
+        self.ingredient_nodes.get(index).copied().flatten()
+    }
+
+    /// Node of the step where a cookware item is used
+    ///
+    /// The index is the same as in [`Recipe::cookware`]. Returns `None` if the
+    /// item is not in a step.
+    pub fn cookware_node(&self, index: usize) -> Option<usize> {
+        self.cookware_nodes.get(index).copied().flatten()
+    }
+
+    /// Nodes the given node directly depends on
+    ///
+    /// # Panics
+    /// If the node is out of bounds.
+    pub fn dependencies(&self, node: usize) -> &[usize] {
+        &self.dependencies[node]
+    }
+
+    /// Nodes that directly depend on the given node
+    ///
+    /// # Panics
+    /// If the node is out of bounds.
+    pub fn dependents(&self, node: usize) -> &[usize] {
+        &self.dependents[node]
+    }
+
+    /// Checks if `node` depends, directly or not, on `other`
+    pub fn depends_on(&self, node: usize, other: usize) -> bool {
+        if other >= node {
+            return false;
+        }
+        let mut visited = vec![false; node + 1];
+        let mut stack = vec![node];
+        while let Some(n) = stack.pop() {
+            for &d in &self.dependencies[n] {
+                if d == other {
+                    return true;
+                }
+                if d > other && !visited[d] {
+                    visited[d] = true;
+                    stack.push(d);
+                }
+            }
+        }
+        false
+    }
+
+    /// Checks if two steps can be done at the same time
+    ///
+    /// This is, neither of them depends on the other.
+    pub fn can_overlap(&self, a: usize, b: usize) -> bool {
+        a != b && !self.depends_on(a, b) && !self.depends_on(b, a)
+    }
+
+    /// Groups the steps in stages
+    ///
+    /// All the steps in a stage can be done at the same time. The dependencies
+    /// of a step are always in a previous stage.
+    pub fn stages(&self) -> Vec<Vec<usize>> {
+        let mut level = vec![0; self.steps.len()];
+        let mut stages: Vec<Vec<usize>> = Vec::new();
+        for node in 0..self.steps.len() {
+            level[node] = self.dependencies[node]
+                .iter()
+                .map(|&d| level[d] + 1)
+                .max()
+                .unwrap_or(0);
+            if stages.len() <= level[node] {
+                stages.resize(level[node] + 1, Vec::new());
+            }
+            stages[level[node]].push(node);
+        }
+        stages
+    }
+}
+
+/// A step placed in a [`Schedule`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScheduledStep {
+    /// The step
+    pub step: StepRef,
+    /// Minutes from the start of the recipe when the step can begin
+    pub start: f64,
+    /// Minutes the step takes
+    pub duration: f64,
+}
+
+impl ScheduledStep {
+    /// Minutes from the start of the recipe when the step ends
+    pub fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+}
+
+/// Gantt-like schedule of the steps of a recipe
+///
+/// Every step starts as soon as all of its dependencies are done.
+///
+/// Created from [`ScaledRecipe::schedule`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Schedule {
+    /// Dependency graph the schedule is built from
+    pub graph: StepGraph,
+    /// Scheduled steps, with the same index as the nodes in the graph
+    pub steps: Vec<ScheduledStep>,
+}
+
+impl ScaledRecipe {
+    /// Builds a [`Schedule`] for the recipe
+    ///
+    /// The duration of a step is the sum of its timers. If a timer is a range,
+    /// the end is used. Timers without a quantity or with a value or unit that
+    /// can't be converted to minutes are ignored, so steps without timers take
+    /// no time.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Extensions, Converter};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser.parse(r#"
+    /// Let the @dough{} rest for ~{1%hour}.
+    ///
+    /// Meanwhile, cook the @sauce{} for ~{20%min}.
+    ///
+    /// Bake the @&(~2)dough{} for ~{30%min}.
+    /// "#).into_output().unwrap().default_scale();
+    /// let schedule = recipe.schedule(parser.converter());
+    ///
+    /// assert_eq!(schedule.steps[1].start, 0.0);
+    /// assert_eq!(schedule.steps[2].start, 60.0);
+    /// assert_eq!(schedule.total_time(), 90.0);
+    /// assert_eq!(schedule.critical_path(), vec![0, 2]);
+    /// ```
+    pub fn schedule(&self, converter: &Converter) -> Schedule {
+        let graph = self.step_graph();
+        let mut steps: Vec<ScheduledStep> = Vec::with_capacity(graph.len());
+        for (node, &step) in graph.steps().iter().enumerate() {
+            let start = graph
+                .dependencies(node)
+                .iter()
+                .map(|&d| steps[d].end())
+                .fold(0.0, f64::max);
+            let duration = self.step_duration(step, converter);
+            steps.push(ScheduledStep {
+                step,
+                start,
+                duration,
+            });
+        }
+        Schedule { graph, steps }
+    }
+
+    fn step_duration(&self, step: StepRef, converter: &Converter) -> f64 {
+        let step = self.sections[step.section].content[step.content].unwrap_step();
+        step.items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Timer { index } => self.timers[*index].quantity.as_ref(),
+                _ => None,
+            })
+            .filter_map(|q| {
+                let value = match q.value() {
+                    Value::Number(n) => n.value(),
+                    Value::Range { end, .. } => end.value(),
+                    Value::Text(_) => return None,
+                };
+                time_to_minutes(value, q.unit()?, converter).ok()
+            })
+            .sum()
+    }
+}
+
+impl Schedule {
+    /// Minutes needed to complete all the steps
+    pub fn total_time(&self) -> f64 {
+        self.steps
+            .iter()
+            .map(ScheduledStep::end)
+            .fold(0.0, f64::max)
+    }
+
+    /// Longest chain of dependent steps, which determines the total time
+    ///
+    /// Returns the nodes in order.
+    pub fn critical_path(&self) -> Vec<usize> {
+        let Some(mut current) = self
+            .steps
+            .iter()
+            .enumerate()
+            // `max_by` keeps the last step with the max end time
+            .max_by(|(_, a), (_, b)| a.end().total_cmp(&b.end()))
+            .map(|(n, _)| n)
+        else {
+            return Vec::new();
+        };
+        let mut path = vec![current];
+        while let Some(&prev) = self
+            .graph
+            .dependencies(current)
+            .iter()
+            .max_by(|&&a, &&b| self.steps[a].end().total_cmp(&self.steps[b].end()))
+        {
+            path.push(prev);
+            current = prev;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Two steps that can be done at the same time but need the same cookware
+///
+/// Created from [`StepGraph::cookware_conflicts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CookwareConflict {
+    /// Index in [`Recipe::cookware`] of the usage in the first step
+    pub first: usize,
+    /// Index in [`Recipe::cookware`] of the usage in the second step
+    pub second: usize,
+}
+
+impl StepGraph {
+    /// Finds steps that can overlap but use the same piece of cookware
+    ///
+    /// Cookware items are the same piece if they are references to the same
+    /// definition or if they have the same name. Definitions with the new
+    /// (`+`) modifier are always a different piece. Items with an amount
+    /// greater than 1 are never in conflict.
+    ///
+    /// Only one conflict is returned for each pair of steps and piece of
+    /// cookware.
+    pub fn cookware_conflicts(&self, recipe: &ScaledRecipe) -> Vec<CookwareConflict> {
+        #[derive(PartialEq, Eq, Hash)]
+        enum Piece {
+            Name(String),
+            Definition(usize),
+        }
+
+        let mut pieces: HashMap<Piece, Vec<(usize, usize)>> = HashMap::new();
+        for (index, cookware) in recipe.cookware.iter().enumerate() {
+            let Some(node) = self.cookware_node(index) else {
+                continue;
+            };
+            let definition_index = cookware.relation.references_to().unwrap_or(index);
+            let definition = &recipe.cookware[definition_index];
+            let more_than_one = match &definition.quantity {
+                Some(Value::Number(n)) => n.value() > 1.0,
+                Some(Value::Range { end, .. }) => end.value() > 1.0,
+                _ => false,
+            };
+            if more_than_one {
+                continue;
+            }
+            let piece = if definition.modifiers().contains(Modifiers::NEW) {
+                Piece::Definition(definition_index)
+            } else {
+                Piece::Name(definition.name.to_lowercase())
+            };
+            pieces.entry(piece).or_default().push((index, node));
+        }
+
+        let mut usages = pieces.into_values().collect::<Vec<_>>();
+        // keep the recipe order for deterministic output
+        usages.sort_unstable_by_key(|u| u[0].0);
+
+        let mut conflicts = Vec::new();
+        for usages in usages {
+            let mut reported = Vec::new();
+            for (i, &(first, a)) in usages.iter().enumerate() {
+                for &(second, b) in &usages[i + 1..] {
+                    if self.can_overlap(a, b) && !reported.contains(&(a, b)) {
+                        reported.push((a, b));
+                        conflicts.push(CookwareConflict { first, second });
+                    }
+                }
+            }
+        }
+        conflicts.sort_unstable_by_key(|c| (c.first, c.second));
+        conflicts
+    }
+}
+
+impl CookwareConflict {
+    /// Creates a warning with labels to both usages
+    ///
+    /// The warning has no labels if the `source_map` was not filled.
+    pub fn to_warning(&self, recipe: &ScaledRecipe, source_map: &SourceMap) -> SourceDiag {
+        let name = recipe.cookware[self.first].display_name();
+        let mut diag = SourceDiag::unlabeled(
+            DiagCode::CookwareConflict,
+            format!("Cookware '{name}' is needed by two steps at the same time"),
+            Severity::Warning,
+            Stage::Analysis,
+        );
+        if let (Some(first), Some(second)) = (
+            source_map.cookware.get(self.first),
+            source_map.cookware.get(self.second),
+        ) {
+            diag.add_label(label!(first, "used here"));
+            diag.add_label(label!(
+                second,
+                "and here, in a step that can be done at the same time"
+            ));
+        }
+        diag.hint(format!(
+            "Use '#&{name}' to reuse the same one after the first step, or '#+{name}' if it's a different one"
+        ))
+    }
+}
+
+/// Checks a recipe for [`CookwareConflict`]s
+///
+/// Returns a warning for each conflict. The `source_map` has to be from the
+/// same recipe, see [`ParseOptions::source_map`](crate::ParseOptions::source_map).
+///
+/// ```
+/// # use cooklang::{CooklangParser, ParseOptions, analysis::SourceMap};
+/// let input = "Roast the @peppers{} in the #oven.\n\nBake the @bread{} in the #oven.";
+/// let mut source_map = SourceMap::default();
+/// let recipe = CooklangParser::default()
+///     .parse_with_options(input, ParseOptions { source_map: Some(&mut source_map), ..Default::default() })
+///     .into_output()
+///     .unwrap()
+///     .default_scale();
+/// let report = cooklang::schedule::check_cookware_conflicts(&recipe, &source_map);
+/// assert_eq!(report.warnings().count(), 1);
+/// ```
+pub fn check_cookware_conflicts(recipe: &ScaledRecipe, source_map: &SourceMap) -> SourceReport {
+    let mut report = SourceReport::empty();
+    for conflict in recipe.step_graph().cookware_conflicts(recipe) {
+        report.warn(conflict.to_warning(recipe, source_map));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{CooklangParser, Extensions};
+
+    fn parse(input: &str) -> ScaledRecipe {
+        CooklangParser::new(Extensions::all(), Converter::bundled())
+            .parse(input)
+            .into_output()
+            .unwrap()
+            .default_scale()
+    }
+
+    #[test]
+    fn intermediate_references() {
+        let recipe = parse(indoc! {"
+            = Dough
+            Mix @flour{} and @water{}.
+
+            Knead the @&(~1)mix{}.
+
+            = Sauce
+            Fry the @onion{}.
+
+            > Some text
+
+            Add @&(=1)dough{} and @&(~1)onion{}.
+        "});
+        let graph = recipe.step_graph();
+        assert_eq!(graph.len(), 4);
+        assert_eq!(
+            graph.steps()[3],
+            StepRef {
+                section: 1,
+                content: 2
+            }
+        );
+        assert_eq!(graph.dependencies(1), &[0]);
+        assert_eq!(graph.dependencies(3), &[0, 1, 2]);
+        assert_eq!(graph.dependents(0), &[1, 3]);
+        assert!(graph.depends_on(3, 0));
+        assert!(!graph.depends_on(0, 3));
+        assert!(graph.can_overlap(1, 2));
+        assert_eq!(graph.stages(), vec![vec![0, 2], vec![1], vec![3]]);
+    }
+
+    #[test]
+    fn component_references() {
+        let recipe = parse(indoc! {"
+            Add @flour{100%g} to the #bowl.
+
+            Boil @water{}.
+
+            Add more @&flour{50%g}.
+
+            Clean the #&bowl.
+        "});
+        let graph = recipe.step_graph();
+        assert_eq!(graph.dependencies(2), &[0]);
+        assert_eq!(graph.dependencies(3), &[0]);
+        assert!(graph.dependencies(1).is_empty());
+        assert!(graph.can_overlap(2, 3));
+    }
+
+    #[test]
+    fn schedule_times() {
+        let recipe = parse(indoc! {"
+            Rest the @dough{} for ~{1-2%hour}.
+
+            Cook @sauce{} for ~{10%min} and then ~{5%min}.
+
+            Add @&(~1)sauce{} to @&dough{} and bake ~{1800%s}.
+
+            Serve.
+        "});
+        let schedule = recipe.schedule(&Converter::bundled());
+        let times = schedule
+            .steps
+            .iter()
+            .map(|s| (s.start, s.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            vec![(0.0, 120.0), (0.0, 15.0), (120.0, 150.0), (0.0, 0.0)]
+        );
+        assert_eq!(schedule.total_time(), 150.0);
+        assert_eq!(schedule.critical_path(), vec![0, 2]);
+    }
+
+    #[test]
+    fn critical_path_tie() {
+        let recipe = parse(indoc! {"
+            Boil the @potatoes{} for ~{10%min}.
+
+            Roast the @peppers{} for ~{10%min}.
+        "});
+        let schedule = recipe.schedule(&Converter::bundled());
+        assert_eq!(schedule.total_time(), 10.0);
+        assert_eq!(schedule.critical_path(), vec![1]);
+    }
+
+    fn conflicts(input: &str) -> (Vec<CookwareConflict>, SourceReport) {
+        let mut source_map = SourceMap::default();
+        let recipe = CooklangParser::new(Extensions::all(), Converter::bundled())
+            .parse_with_options(
+                input,
+                crate::ParseOptions {
+                    source_map: Some(&mut source_map),
+                    ..Default::default()
+                },
+            )
+            .into_output()
+            .unwrap()
+            .default_scale();
+        let conflicts = recipe.step_graph().cookware_conflicts(&recipe);
+        let report = check_cookware_conflicts(&recipe, &source_map);
+        (conflicts, report)
+    }
+
+    #[test]
+    fn cookware_conflict() {
+        let input = indoc! {"
+            Roast the @peppers{} in the #oven at 220 °C.
+
+            Bake the @bread{} in the #Oven at 180 °C.
+
+            Blend the @&(~2)peppers{} in the #blender.
+        "};
+        let (c, report) = conflicts(input);
+        assert_eq!(
+            c,
+            vec![CookwareConflict {
+                first: 0,
+                second: 1
+            }]
+        );
+        let diag = report.warnings().next().unwrap();
+        assert_eq!(diag.labels.len(), 2);
+        assert_eq!(&input[diag.labels[0].0.range()], "#oven");
+        assert_eq!(&input[diag.labels[1].0.range()], "#Oven");
+    }
+
+    #[test_case("Use the #pan.\n\nThen the @&(~1)thing{} in the #&pan." ; "dependent steps")]
+    #[test_case("Use the #pan.\n\nUse the #+pan." ; "new modifier")]
+    #[test_case("Use the #pan{2}.\n\nUse the #pan." ; "more than one")]
+    #[test_case("Use the #pan and the #pan." ; "same step")]
+    fn no_cookware_conflict(input: &str) {
+        let (c, report) = conflicts(input);
+        assert!(c.is_empty());
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn cookware_conflict_without_source_map() {
+        let recipe = parse("Use the #oven.\n\nUse the #oven.");
+        let report = check_cookware_conflicts(&recipe, &SourceMap::default());
+        let diag = report.warnings().next().unwrap();
+        assert_eq!(diag.code, DiagCode::CookwareConflict);
+        assert!(diag.labels.is_empty());
+    }
+
+    #[test]
+    fn empty_schedule() {
+        let recipe = parse("");
+        let schedule = recipe.schedule(&Converter::bundled());
+        assert!(schedule.graph.is_empty());
+        assert_eq!(schedule.total_time(), 0.0);
+        assert!(schedule.critical_path().is_empty());
+    }
+}
@@ -0,0 +1,388 @@
+//! Temperatures as structured data
+//!
+//! With the [`TEMPERATURES`](crate::Extensions::TEMPERATURES) extension,
+//! temperatures are components with the value and unit between braces and
+//! the [`OvenMode`] as a note. A step can have alternatives:
+//!
+//! ```cooklang
+//! Bake at ^{180%°C}(fan) / ^{200%°C}(conventional) for ~{25%min}.
+//! ```
+//!
+//! The unit is °C, °F or gas mark, like `^{4%gas mark}`. They are in
+//! [`Recipe::temperatures`] and [`Recipe::step_temperatures`] gives the ones
+//! of a step.
+//!
+//! Gas marks are not a unit of the [`Converter`], so
+//! [`Recipe::convert`](crate::Recipe::convert) leaves them as they are. Use
+//! [`Temperature::convert`] to get them in degrees.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    convert::{ConvertError, ConvertTo, ConvertUnit, ConvertValue, Converter, PhysicalQuantity},
+    quantity::{Number, QuantityValue},
+    Item, Recipe, Step, Value,
+};
+
+/// Scale of a [`Temperature`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TemperatureScale {
+    Celsius,
+    Fahrenheit,
+    /// British gas oven scale
+    ///
+    /// From gas mark 1 (275 °F) each mark is 25 °F more. Below it are gas
+    /// mark 1/4 (225 °F) and 1/2 (250 °F).
+    GasMark,
+}
+
+/// Unit of gas marks, in temperatures and inline quantities
+pub(crate) const GAS_MARK_UNIT: &str = "gas mark";
+
+impl TemperatureScale {
+    /// Name of the unit of the scale
+    ///
+    /// Gas mark is not in the [`Converter`], the others are.
+    pub fn unit_name(self) -> &'static str {
+        match self {
+            TemperatureScale::Celsius => "celsius",
+            TemperatureScale::Fahrenheit => "fahrenheit",
+            TemperatureScale::GasMark => GAS_MARK_UNIT,
+        }
+    }
+
+    /// Scales with a unit in the [`Converter`]
+    const DEGREES: [Self; 2] = [Self::Celsius, Self::Fahrenheit];
+
+    /// Finds the scale of a unit, like `°C` or `gas mark`
+    pub(crate) fn from_unit(unit: &str, converter: &Converter) -> Option<Self> {
+        if unit.eq_ignore_ascii_case(GAS_MARK_UNIT) {
+            return Some(Self::GasMark);
+        }
+        let unit = converter
+            .find_unit(unit)
+            .filter(|unit| unit.physical_quantity == PhysicalQuantity::Temperature)?;
+        Self::DEGREES.into_iter().find(|scale| {
+            converter
+                .find_unit(scale.unit_name())
+                .is_some_and(|u| Arc::ptr_eq(&u, &unit))
+        })
+    }
+}
+
+/// How the oven heats
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase", ascii_case_insensitive)]
+#[non_exhaustive]
+pub enum OvenMode {
+    /// Top and bottom heat
+    #[strum(to_string = "conventional", serialize = "static")]
+    Conventional,
+    /// Fan assisted or convection
+    #[strum(
+        to_string = "fan",
+        serialize = "fan-forced",
+        serialize = "fan-assisted",
+        serialize = "convection"
+    )]
+    Fan,
+    /// Top heat only
+    #[strum(to_string = "grill", serialize = "broil")]
+    Grill,
+    /// Steam oven
+    Steam,
+}
+
+/// A temperature component, like `^{180%°C}(fan)`
+///
+/// They are in [`Recipe::temperatures`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Temperature {
+    /// Value, a number or a range
+    pub value: Value,
+    /// Scale of the value
+    pub scale: TemperatureScale,
+    /// Oven mode, from the note
+    pub mode: Option<OvenMode>,
+}
+
+impl std::fmt::Display for Temperature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.scale {
+            TemperatureScale::Celsius => write!(f, "{} °C", self.value)?,
+            TemperatureScale::Fahrenheit => write!(f, "{} °F", self.value)?,
+            TemperatureScale::GasMark => write!(f, "{GAS_MARK_UNIT} {}", self.value)?,
+        }
+        if let Some(mode) = self.mode {
+            write!(f, " ({mode})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Temperature {
+    /// Converts the temperature to another scale
+    ///
+    /// Gas marks are rounded to the nearest mark, 1/4 and 1/2 included.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, temperature::TemperatureScale};
+    /// let parser = CooklangParser::default();
+    /// let recipe = parser.parse("Bake at ^{180%°C}.").into_output().unwrap();
+    /// let temp = &recipe.temperatures[0];
+    ///
+    /// let f = temp.convert(TemperatureScale::Fahrenheit, parser.converter()).unwrap();
+    /// assert_eq!(f.value.to_string(), "356");
+    /// let gas = temp.convert(TemperatureScale::GasMark, parser.converter()).unwrap();
+    /// assert_eq!(gas.value.to_string(), "4");
+    /// ```
+    pub fn convert(
+        &self,
+        to: TemperatureScale,
+        converter: &Converter,
+    ) -> Result<Temperature, ConvertError> {
+        // gas marks go through fahrenheit
+        let degrees = |scale| match scale {
+            TemperatureScale::GasMark => TemperatureScale::Fahrenheit,
+            scale => scale,
+        };
+        let mut value = ConvertValue::try_from(&self.value)?;
+        if self.scale == TemperatureScale::GasMark {
+            value = map_value(value, gas_mark_to_fahrenheit);
+        }
+        let (mut value, _) = converter.convert(
+            value,
+            ConvertUnit::Key(degrees(self.scale).unit_name()),
+            ConvertTo::Unit(ConvertUnit::Key(degrees(to).unit_name())),
+        )?;
+        if to == TemperatureScale::GasMark {
+            value = map_value(value, |f| round_gas_mark(fahrenheit_to_gas_mark(f)));
+        }
+        let value = match value {
+            ConvertValue::Number(n) => Value::Number(Number::Regular(n)),
+            ConvertValue::Range(r) => Value::Range {
+                start: Number::Regular(*r.start()),
+                end: Number::Regular(*r.end()),
+            },
+        };
+        Ok(Temperature {
+            value,
+            scale: to,
+            mode: self.mode,
+        })
+    }
+}
+
+impl<D, V: QuantityValue> Recipe<D, V> {
+    /// Temperatures in a step, in order
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, temperature::{OvenMode, TemperatureScale}};
+    /// let parser = CooklangParser::default();
+    /// let recipe = parser
+    ///     .parse("Bake at ^{180%°C}(fan) / ^{200%°C}(conventional) or ^{6%gas mark}.")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let step = recipe.sections[0].content[0].unwrap_step();
+    /// let temps = recipe.step_temperatures(step);
+    ///
+    /// assert_eq!(temps.len(), 3);
+    /// assert_eq!(temps[0].mode, Some(OvenMode::Fan));
+    /// assert_eq!(temps[1].mode, Some(OvenMode::Conventional));
+    /// assert_eq!(temps[2].scale, TemperatureScale::GasMark);
+    /// ```
+    pub fn step_temperatures(&self, step: &Step) -> Vec<&Temperature> {
+        step.items
+            .iter()
+            .filter_map(|item| match *item {
+                Item::Temperature { index } => Some(&self.temperatures[index]),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn map_value(value: ConvertValue, f: impl Fn(f64) -> f64) -> ConvertValue {
+    match value {
+        ConvertValue::Number(n) => ConvertValue::Number(f(n)),
+        ConvertValue::Range(r) => ConvertValue::Range(f(*r.start())..=f(*r.end())),
+    }
+}
+
+/// Linear between the marks 1/4, 1/2 and 1, then 25 °F per mark
+fn gas_mark_to_fahrenheit(mark: f64) -> f64 {
+    if mark >= 1.0 {
+        250.0 + 25.0 * mark
+    } else if mark >= 0.5 {
+        250.0 + 50.0 * (mark - 0.5)
+    } else {
+        225.0 + 100.0 * (mark - 0.25)
+    }
+}
+
+/// Inverse of [`gas_mark_to_fahrenheit`]
+fn fahrenheit_to_gas_mark(fahrenheit: f64) -> f64 {
+    if fahrenheit >= 275.0 {
+        (fahrenheit - 250.0) / 25.0
+    } else if fahrenheit >= 250.0 {
+        0.5 + (fahrenheit - 250.0) / 50.0
+    } else {
+        0.25 + (fahrenheit - 225.0) / 100.0
+    }
+}
+
+/// Nearest existing mark, 1/4, 1/2 or a whole number
+fn round_gas_mark(mark: f64) -> f64 {
+    if mark >= 0.75 {
+        mark.round()
+    } else if mark >= 0.375 {
+        0.5
+    } else {
+        0.25
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::DiagCode, CooklangParser, Extensions, ScalableRecipe};
+    use test_case::test_case;
+
+    fn temperatures(input: &str) -> Vec<Temperature> {
+        let parser = CooklangParser::default();
+        let recipe: ScalableRecipe = parser.parse(input).into_output().unwrap();
+        let step = recipe.sections[0].content[0].unwrap_step();
+        recipe
+            .step_temperatures(step)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test_case("^{180%°C}(fan)" => Some(OvenMode::Fan))]
+    #[test_case("^{180%°C}(convection)" => Some(OvenMode::Fan))]
+    #[test_case("^{180%°C}( Fan-Forced )" => Some(OvenMode::Fan))]
+    #[test_case("^{200%°C}(static)" => Some(OvenMode::Conventional))]
+    #[test_case("^{250%F}(broil)" => Some(OvenMode::Grill))]
+    #[test_case("^{180%°C}" => None)]
+    #[test_case("^{180%°C} fan" => None ; "mode outside the note")]
+    fn mode(input: &str) -> Option<OvenMode> {
+        let temps = temperatures(input);
+        assert_eq!(temps.len(), 1);
+        temps[0].mode
+    }
+
+    #[test]
+    fn unknown_mode() {
+        let res = CooklangParser::default().parse("Bake at ^{180%°C}(hot).");
+        let warnings = res.report().warnings().collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, DiagCode::UnknownOvenMode);
+        let recipe = res.into_output().unwrap();
+        assert_eq!(recipe.temperatures[0].mode, None);
+    }
+
+    #[test]
+    fn only_components() {
+        let temps = temperatures("Heat the water to 90 °C and the oven to ^{180%°C}.");
+        assert_eq!(temps.len(), 1);
+        assert_eq!(temps[0].value, Value::from(180.0));
+        assert_eq!(temps[0].scale, TemperatureScale::Celsius);
+    }
+
+    #[test_case("^{4%gas mark}" => (TemperatureScale::GasMark, Value::from(4.0)))]
+    #[test_case("^{7 % Gas Mark }" => (TemperatureScale::GasMark, Value::from(7.0)))]
+    #[test_case("^{350%°F}" => (TemperatureScale::Fahrenheit, Value::from(350.0)))]
+    #[test_case("^{180%celsius}" => (TemperatureScale::Celsius, Value::from(180.0)))]
+    #[test_case("^{170-180%°C}" => (TemperatureScale::Celsius, Value::Range { start: 170.0.into(), end: 180.0.into() }))]
+    fn scale(input: &str) -> (TemperatureScale, Value) {
+        let temps = temperatures(input);
+        assert_eq!(temps.len(), 1);
+        (temps[0].scale, temps[0].value.clone())
+    }
+
+    #[test_case("^{180}" => DiagCode::TemperatureMissingUnit)]
+    #[test_case("^{}" => DiagCode::EmptyValue)]
+    #[test_case("^{180%kg}" => DiagCode::UnknownTemperatureUnit)]
+    #[test_case("^{hot%°C}" => DiagCode::TemperatureTextValue)]
+    fn errors(input: &str) -> DiagCode {
+        let res = CooklangParser::default().parse(input);
+        let errors = res.report().errors().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        errors[0].code
+    }
+
+    #[test]
+    fn needs_extension() {
+        let parser = CooklangParser::new(Extensions::empty(), Converter::bundled());
+        let recipe = parser.parse("Bake at ^{180%°C}.").into_output().unwrap();
+        assert!(recipe.temperatures.is_empty());
+        let step = recipe.sections[0].content[0].unwrap_step();
+        assert_eq!(
+            step.items,
+            [Item::Text {
+                value: "Bake at ^{180%°C}.".to_string()
+            }]
+        );
+    }
+
+    #[test_case("^{180%°C}(fan)" => "180 °C (fan)")]
+    #[test_case("^{4%gas mark}" => "gas mark 4")]
+    #[test_case("^{170-180%F}" => "170-180 °F")]
+    fn display(input: &str) -> String {
+        temperatures(input)[0].to_string()
+    }
+
+    #[test]
+    fn recipe_convert() {
+        let parser = CooklangParser::default();
+        let recipe = parser
+            .parse("Bake at ^{350%°F}(fan) or ^{4%gas mark}, not at gas mark 5.")
+            .into_output()
+            .unwrap();
+        let mut recipe = recipe.default_scale();
+        let errors = recipe.convert(crate::convert::System::Metric, parser.converter());
+        assert!(errors.is_empty());
+        assert_eq!(recipe.temperatures[0].scale, TemperatureScale::Celsius);
+        assert_eq!(recipe.temperatures[0].mode, Some(OvenMode::Fan));
+        assert_eq!(recipe.temperatures[1].to_string(), "gas mark 4");
+        assert_eq!(recipe.inline_quantities[0].to_string(), "5 gas mark");
+    }
+
+    #[test_case(TemperatureScale::Fahrenheit, 350.0, TemperatureScale::GasMark => 4.0)]
+    #[test_case(TemperatureScale::GasMark, 4.0, TemperatureScale::Fahrenheit => 350.0)]
+    #[test_case(TemperatureScale::Celsius, 230.0, TemperatureScale::GasMark => 8.0)]
+    #[test_case(TemperatureScale::GasMark, 0.25, TemperatureScale::Fahrenheit => 225.0)]
+    #[test_case(TemperatureScale::GasMark, 0.5, TemperatureScale::Fahrenheit => 250.0)]
+    #[test_case(TemperatureScale::Fahrenheit, 255.0, TemperatureScale::GasMark => 0.5)]
+    #[test_case(TemperatureScale::Celsius, 110.0, TemperatureScale::GasMark => 0.25)]
+    #[test_case(TemperatureScale::GasMark, 2.0, TemperatureScale::GasMark => 2.0)]
+    fn convert(from: TemperatureScale, value: f64, to: TemperatureScale) -> f64 {
+        let temp = Temperature {
+            value: Value::from(value),
+            scale: from,
+            mode: None,
+        };
+        let converted = temp.convert(to, &Converter::bundled()).unwrap();
+        match converted.value {
+            Value::Number(n) => (n.value() * 1000.0).round() / 1000.0,
+            _ => panic!("not a number"),
+        }
+    }
+}
@@ -223,6 +223,11 @@ impl<'a> TextFragment<'a> {
         self.text
     }
 
+    /// Get the kind of fragment
+    pub fn kind(&self) -> TextFragmentKind {
+        self.kind
+    }
+
     /// Get the span of the original input of the fragment
     pub fn span(&self) -> Span {
         Span::new(self.start(), self.end())
@@ -0,0 +1,206 @@
+//! Locations of metadata values in the YAML text
+//!
+//! Used to point diagnostics about a metadata value to the value itself
+//! instead of the whole frontmatter.
+
+use std::ops::Range;
+
+/// Part of the path to a metadata value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Key of a mapping
+    Key(String),
+    /// Index of a sequence
+    #[cfg_attr(not(feature = "schema"), allow(dead_code))]
+    Index(usize),
+}
+
+/// Location of a value in the YAML text
+///
+/// Like other places in the crate, this looks at the lines of the text instead
+/// of using a YAML parser with locations. It understands the block style, in
+/// flow style (`[a, b]`, `{a: 1}`) it stops at the containing value.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct YamlLocation {
+    /// The last key in the path
+    pub key: Option<Range<usize>>,
+    /// The value itself, or the closest parent found
+    pub value: Option<Range<usize>>,
+}
+
+pub(crate) fn locate(text: &str, path: &[PathSegment]) -> YamlLocation {
+    let trimmed = text.trim();
+    let start = text.len() - text.trim_start().len();
+    let mut location = YamlLocation {
+        key: None,
+        value: Some(start..start + trimmed.len()),
+    };
+    for segment in path {
+        let Some(region) = location.value.clone() else {
+            break;
+        };
+        match segment {
+            PathSegment::Key(key) => {
+                let Some((key, value)) = find_key(text, region, key) else {
+                    break;
+                };
+                location.key = Some(key);
+                location.value = value;
+            }
+            PathSegment::Index(index) => {
+                let Some(value) = find_item(text, region, *index) else {
+                    break;
+                };
+                location.value = Some(value);
+            }
+        }
+    }
+    location
+}
+
+/// Non empty lines of a region of the text
+///
+/// Yields the indentation, the offset where the content starts and the content.
+fn lines(text: &str, region: Range<usize>) -> impl Iterator<Item = (usize, usize, &str)> {
+    let line_start = text[..region.start].rfind('\n').map_or(0, |i| i + 1);
+    let first_indent = region.start - line_start;
+    let mut offset = region.start;
+    text[region]
+        .split_inclusive('\n')
+        .enumerate()
+        .filter_map(move |(i, line)| {
+            let line_offset = offset;
+            offset += line.len();
+            let content = line.trim_start();
+            let skipped = line.len() - content.len();
+            let content = content.trim_end();
+            if content.is_empty() || content.starts_with('#') {
+                return None;
+            }
+            let indent = if i == 0 { first_indent } else { 0 } + skipped;
+            Some((indent, line_offset + skipped, content))
+        })
+}
+
+fn is_item(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+/// Finds a key of the mapping in `region`, returns the key and value ranges
+fn find_key(
+    text: &str,
+    region: Range<usize>,
+    key: &str,
+) -> Option<(Range<usize>, Option<Range<usize>>)> {
+    let mut lines = lines(text, region).peekable();
+    let base = lines.peek()?.0;
+    while let Some((indent, start, content)) = lines.next() {
+        if indent != base {
+            continue;
+        }
+        let Some((k, rest)) = content.split_once(':') else {
+            continue;
+        };
+        let k = k.trim_end();
+        let unquoted = k
+            .strip_prefix('"')
+            .and_then(|k| k.strip_suffix('"'))
+            .or_else(|| k.strip_prefix('\'').and_then(|k| k.strip_suffix('\'')))
+            .unwrap_or(k);
+        if unquoted != key {
+            continue;
+        }
+        let key_range = start..start + k.len();
+
+        let inline = rest.trim();
+        if !inline.is_empty() {
+            let value_start = start + content.len() - rest.trim_start().len();
+            return Some((key_range, Some(value_start..value_start + inline.len())));
+        }
+        // block value, the more indented lines or a sequence at the same level
+        let mut value: Option<Range<usize>> = None;
+        while let Some(&(indent, start, content)) = lines.peek() {
+            if indent < base || (indent == base && !is_item(content)) {
+                break;
+            }
+            value = Some(value.map_or(start, |v| v.start)..start + content.len());
+            lines.next();
+        }
+        return Some((key_range, value));
+    }
+    None
+}
+
+/// Finds an item of the sequence in `region`
+fn find_item(text: &str, region: Range<usize>, index: usize) -> Option<Range<usize>> {
+    if text[region.clone()].starts_with('[') {
+        return None;
+    }
+    let mut lines = lines(text, region).peekable();
+    let base = lines.peek()?.0;
+    let mut items: Vec<Option<Range<usize>>> = Vec::new();
+    for (indent, start, content) in lines {
+        if indent == base && is_item(content) {
+            if items.len() > index {
+                break;
+            }
+            let item = content[1..].trim_start();
+            let item_start = start + content.len() - item.len();
+            items.push((!item.is_empty()).then(|| item_start..item_start + item.len()));
+        } else if let Some(last) = items.last_mut() {
+            *last = Some(last.clone().map_or(start, |r| r.start)..start + content.len());
+        }
+    }
+    items.into_iter().nth(index).flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn locations() {
+        let text = indoc! {"
+            title: Pancakes
+            tags:
+            - sweet
+            - quick
+            author:
+              name: Rachel
+              links:
+                - https://a.url
+                - https://b.url
+            'quoted key': [1, 2]
+        "};
+        let loc = |path: &[PathSegment]| {
+            let l = locate(text, path);
+            (l.key.map(|r| &text[r]), l.value.map(|r| &text[r]))
+        };
+        let key = |k: &str| PathSegment::Key(k.into());
+        assert_eq!(loc(&[key("title")]), (Some("title"), Some("Pancakes")));
+        assert_eq!(
+            loc(&[key("tags")]),
+            (Some("tags"), Some("- sweet\n- quick"))
+        );
+        assert_eq!(
+            loc(&[key("tags"), PathSegment::Index(1)]),
+            (Some("tags"), Some("quick"))
+        );
+        assert_eq!(
+            loc(&[key("author"), key("name")]),
+            (Some("name"), Some("Rachel"))
+        );
+        assert_eq!(
+            loc(&[key("author"), key("links"), PathSegment::Index(0)]),
+            (Some("links"), Some("https://a.url"))
+        );
+        // flow style, stays at the parent
+        assert_eq!(
+            loc(&[key("quoted key"), PathSegment::Index(0)]),
+            (Some("'quoted key'"), Some("[1, 2]"))
+        );
+        assert_eq!(loc(&[key("missing")]).0, None);
+    }
+}
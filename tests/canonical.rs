@@ -161,6 +161,7 @@ impl TestStepItem {
                 }
             }
             Item::InlineQuantity { index: _ } => panic!("Unexpected inline quantity"),
+            Item::Temperature { index: _ } => panic!("Unexpected temperature"),
         }
     }
 }
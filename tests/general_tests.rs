@@ -1,4 +1,9 @@
-use cooklang::{Content, CooklangParser, Extensions, Item, Value};
+use cooklang::{
+    analysis::SourceMap,
+    error::{DiagCode, LintLevel, Lints},
+    metadata::{Term, Vocabulary},
+    Content, CooklangParser, Extensions, Item, ParseOptions, Value,
+};
 use indoc::indoc;
 use test_case::test_case;
 
@@ -188,6 +193,17 @@ fn single_line_no_separator() {
     assert_eq!(r.metadata.map.len(), 1);
 }
 
+#[test_case("Heat to 180 °C." => vec!["180 °C"]; "temperature before punctuation")]
+#[test_case("Bake at 350 F, then rest." => vec!["350 F"]; "temperature before comma")]
+#[test_case("Add 2 l of water." => vec!["2 l"]; "other unit")]
+#[test_case("Add 500 g, then 2 l." => Vec::<&str>::new(); "other unit before punctuation")]
+#[test_case("Pour 1 cup; stir 3 min." => Vec::<&str>::new(); "other units before punctuation")]
+fn inline_quantities(input: &str) -> Vec<String> {
+    let parser = CooklangParser::new(Extensions::all(), Default::default());
+    let r = parser.parse(input).unwrap_output();
+    r.inline_quantities.iter().map(|q| q.to_string()).collect()
+}
+
 #[test]
 fn multiple_temperatures() {
     let input = "text 2ºC more text 150 F end text";
@@ -247,3 +263,352 @@ fn text_steps_extension() {
         [Content::Text(_)]
     ));
 }
+
+#[test]
+fn source_map() {
+    let input = indoc! {r#"
+        Preheat the #oven to 180 °C now.
+        Add @salt{1%tsp}.
+
+        = Sauce
+        > Some text
+
+        Cook for ~{5%min}.
+    "#};
+    let mut source_map = SourceMap::default();
+    let r = CooklangParser::default()
+        .parse_with_options(
+            input,
+            ParseOptions {
+                source_map: Some(&mut source_map),
+                ..Default::default()
+            },
+        )
+        .unwrap_output();
+    let text = |span: cooklang::Span| &input[span.range()];
+
+    assert_eq!(source_map.ingredients.len(), r.ingredients.len());
+    assert_eq!(text(source_map.ingredients[0]), "@salt{1%tsp}");
+    assert_eq!(text(source_map.cookware[0]), "#oven");
+    assert_eq!(text(source_map.timers[0]), "~{5%min}");
+    assert_eq!(text(source_map.inline_quantities[0]), "180 °C");
+
+    assert_eq!(source_map.sections.len(), 2);
+    assert_eq!(source_map.sections[0].name, None);
+    assert_eq!(
+        text(source_map.sections[0].content[0]),
+        "Preheat the #oven to 180 °C now.\nAdd @salt{1%tsp}."
+    );
+    assert_eq!(text(source_map.sections[1].name.unwrap()), " Sauce");
+    assert_eq!(text(source_map.sections[1].content[0]), "Some text");
+    assert_eq!(
+        text(source_map.sections[1].content[1]),
+        "Cook for ~{5%min}."
+    );
+}
+
+#[test_case("Add @&flour{100%g}." => "Add @flour{100%g}." ; "undefined reference")]
+#[test_case("Add @&?flour{}." => "Add @?flour{}." ; "undefined reference with modifiers")]
+#[test_case("Wait ~{10min}." => "Wait ~{10%min}." ; "missing unit separator")]
+#[test_case("Wait ~{10%minutess}." => "Wait ~{10%minutes}." ; "misspelled unit")]
+#[test_case("Add @flour{100%}." => "Add @flour{100}." ; "empty unit")]
+fn suggestions(src: &str) -> String {
+    let parser = CooklangParser::default();
+    let report = parser.parse(src).into_report();
+    let diag = report.iter().next().expect("no diagnostic");
+    assert_eq!(diag.suggestions.len(), 1, "{diag:?}");
+    diag.suggestions[0].apply(src)
+}
+
+#[test]
+fn no_suggestion_for_unknown_unit() {
+    let parser = CooklangParser::default();
+    let report = parser.parse("Wait ~{10%blorps}.").into_report();
+    let diag = report.iter().next().unwrap();
+    assert!(diag.suggestions.is_empty());
+}
+
+#[test_case("Add @flour{100%}." => vec!["empty-unit"] ; "parse stage")]
+#[test_case("Add @&flour{}." => vec!["reference-not-found"] ; "analysis stage")]
+#[test_case("---\ntime: 1h\nprep time: 10 min\n---\nWait." => vec!["time-overridden"] ; "frontmatter")]
+fn diagnostic_codes(src: &str) -> Vec<&'static str> {
+    let parser = CooklangParser::default();
+    let report = parser.parse(src).into_report();
+    report.iter().map(|d| d.code.as_str()).collect()
+}
+
+#[test]
+fn lint_levels_in_options() {
+    let src = "Add @flour{100%} and @salt{1%}.";
+    let parser = CooklangParser::default();
+
+    let mut lints = Lints::new();
+    lints.set(DiagCode::EmptyUnit, LintLevel::Allow);
+    let options = ParseOptions {
+        lints,
+        ..Default::default()
+    };
+    let result = parser.parse_with_options(src, options);
+    assert!(result.is_valid());
+    assert!(result.report().is_empty());
+
+    let mut lints = Lints::new();
+    lints.set(DiagCode::EmptyUnit, LintLevel::Deny);
+    let options = ParseOptions {
+        lints,
+        ..Default::default()
+    };
+    let result = parser.parse_with_options(src, options);
+    assert!(!result.is_valid());
+    assert_eq!(result.report().errors().count(), 2);
+    assert!(result.into_result().is_err());
+}
+
+#[test]
+fn lint_levels_in_file() {
+    let parser = CooklangParser::default();
+
+    let src = indoc! {"
+        >> [allow]: empty-unit, time-overridden
+        Add @flour{100%}.
+    "};
+    assert!(parser.parse(src).report().is_empty());
+
+    let src = indoc! {"
+        >> [deny]: warnings
+        >> [warn]: empty-unit
+        Add @flour{100%} and @&flour{1%l}.
+    "};
+    let report = parser.parse(src).into_report();
+    let levels = report
+        .iter()
+        .map(|d| (d.code.as_str(), d.is_error()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        levels,
+        [("empty-unit", false), ("incompatible-units", true)]
+    );
+
+    // the recipe has preference over the options
+    let mut lints = Lints::new();
+    lints.set(DiagCode::EmptyUnit, LintLevel::Deny);
+    let options = ParseOptions {
+        lints,
+        ..Default::default()
+    };
+    let src = ">> [allow]: empty-unit\nAdd @flour{100%}.";
+    assert!(parser.parse_with_options(src, options).report().is_empty());
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn metadata_schema() {
+    use cooklang::metadata_schema::MetadataSchema;
+
+    let schema = MetadataSchema::from_json(
+        r#"{
+            "additionalProperties": false,
+            "properties": {
+                "rating": { "type": "integer" },
+                "tags": { "type": "array", "items": { "enum": ["sweet", "quick"] } }
+            }
+        }"#,
+    )
+    .unwrap();
+    let src = indoc! {"
+        ---
+        rating: good
+        tags:
+          - sweet
+          - spicy
+        color: red
+        ---
+        Add @flour{}.
+    "};
+    let parser = CooklangParser::default();
+    let options = ParseOptions {
+        metadata_schema: Some(&schema),
+        ..Default::default()
+    };
+    let report = parser.parse_with_options(src, options).into_report();
+    let labels = report
+        .iter()
+        .map(|d| {
+            assert_eq!(d.code, DiagCode::MetadataSchema);
+            assert!(d.is_warning());
+            &src[d.labels[0].0.range()]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["good", "spicy", "color"]);
+
+    let mut lints = Lints::new();
+    lints.set(DiagCode::MetadataSchema, LintLevel::Deny);
+    let options = ParseOptions {
+        metadata_schema: Some(&schema),
+        lints,
+        ..Default::default()
+    };
+    assert!(!parser.parse_with_options(src, options).is_valid());
+}
+
+#[test]
+fn metadata_vocabulary() {
+    let src = indoc! {"
+        ---
+        course: desert
+        cuisine: Italian
+        diet: [vegan, gluten-free, raw]
+        difficulty: tricky
+        ---
+        Add @flour{}.
+    "};
+    let parser = CooklangParser::default();
+    let report = parser.parse(src).into_report();
+    let diags = report.iter().collect::<Vec<_>>();
+    let codes = diags.iter().map(|d| d.code).collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        [
+            DiagCode::UnknownMetadataValue,
+            DiagCode::UnknownMetadataValue,
+            DiagCode::UnsupportedMetadataValue
+        ]
+    );
+    assert_eq!(&src[diags[0].labels[0].0.range()], "desert");
+    assert_eq!(diags[0].hints, ["Did you mean 'dessert'?"]);
+    assert_eq!(
+        &src[diags[1].labels[0].0.range()],
+        "[vegan, gluten-free, raw]"
+    );
+    assert_eq!(diags[1].message, "Unknown value for key 'diet': 'raw'");
+    assert!(diags[1].hints[0].starts_with("Known values are: vegan, vegetarian,"));
+
+    let mut vocabulary = Vocabulary::empty();
+    vocabulary.courses.push(Term::new("dessert", ["desert"]));
+    vocabulary.cuisines.push(Term::new("italian", []));
+    vocabulary.diets.push(Term::new("raw", []));
+    vocabulary.diets.push(Term::new("vegan", []));
+    vocabulary.diets.push(Term::new("gluten free", []));
+    let options = ParseOptions {
+        vocabulary: Some(&vocabulary),
+        ..Default::default()
+    };
+    let result = parser.parse_with_options(src, options);
+    assert_eq!(result.report().iter().count(), 1);
+    let recipe = result.into_output().unwrap();
+    assert_eq!(recipe.metadata.course(&vocabulary), Some("dessert"));
+    assert_eq!(
+        recipe.metadata.diet(&vocabulary),
+        Some(vec!["vegan", "gluten free", "raw"])
+    );
+    assert_eq!(recipe.metadata.difficulty(), None);
+}
+
+#[test]
+fn unknown_lint() {
+    let parser = CooklangParser::default();
+    let src = ">> [allow]: empty-unit, not-a-lint\nAdd @flour{100%}.";
+    let report = parser.parse(src).into_report();
+    let diags = report.into_vec();
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, DiagCode::UnknownLint);
+    assert_eq!(&src[diags[0].labels[0].0.range()], "not-a-lint");
+}
+
+#[test]
+fn errors_cannot_be_allowed() {
+    let parser = CooklangParser::default();
+    let src = ">> [allow]: reference-not-found\nAdd @&flour{}.";
+    assert!(parser.parse(src).report().has_errors());
+}
+
+#[test]
+fn group_step_ingredients() {
+    let input = indoc! {r#"
+        Mix @flour{500%g}, @salt and @water{300%ml}.
+
+        Add @&flour{50%g} and knead the @&(~1)dough{}. Add @&flour{25%g} and @&salt{}.
+    "#};
+    let parser = CooklangParser::new(Extensions::all(), Default::default());
+    let recipe = parser.parse(input).into_output().unwrap().default_scale();
+    let Content::Step(step) = &recipe.sections[0].content[1] else {
+        panic!("not a step")
+    };
+    let grouped = recipe.group_step_ingredients(step, parser.converter());
+    let names = grouped
+        .iter()
+        .map(|e| (e.index, e.ingredient.name.as_str(), e.quantity.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            (0, "flour", "75 g".to_string()),
+            (4, "dough", "".to_string()),
+            (1, "salt", "".to_string()),
+        ]
+    );
+
+    let section = recipe.group_section_ingredients(&recipe.sections[0], parser.converter());
+    assert_eq!(section.len(), 4);
+    assert_eq!(section[0].quantity.to_string(), "575 g");
+}
+
+#[test]
+fn scale_sections() {
+    use cooklang::{
+        ingredient_list::IngredientList,
+        scale::{ScaleOutcome, SectionScale},
+    };
+
+    let input = indoc! {r#"
+        Boil @water{1%l} in a #pot and cook @pasta{200%g} for ~{10%min}.
+
+        ==? Sauce ==
+        Fry @garlic{2} and add @tomato{400%g} and @&water{100%ml} in a #&pot.
+
+        == Serve ==
+        Add @basil{4%leaves}.
+    "#};
+    let parser = CooklangParser::new(Extensions::all(), Default::default());
+    let recipe = parser.parse(input).into_output().unwrap();
+    assert_eq!(
+        recipe
+            .sections
+            .iter()
+            .map(|s| s.optional)
+            .collect::<Vec<_>>(),
+        [false, true, false]
+    );
+
+    let scales = [(1, SectionScale::Skip), (2, SectionScale::Factor(0.5))];
+    let scaled = recipe.scale_sections(2.0, &scales, parser.converter());
+    let data = scaled.scaled_data().unwrap();
+    assert_eq!(
+        data.sections,
+        [
+            SectionScale::Factor(1.0),
+            SectionScale::Skip,
+            SectionScale::Factor(0.5)
+        ]
+    );
+    assert!(matches!(data.ingredients[2], ScaleOutcome::Skipped));
+    assert!(matches!(data.timers[0], ScaleOutcome::Fixed));
+
+    let grouped = scaled.group_ingredients(parser.converter());
+    let names = grouped
+        .iter()
+        .map(|e| (e.ingredient.name.as_str(), e.quantity.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            ("water", "2 l".to_string()),
+            ("pasta", "400 g".to_string()),
+            ("basil", "4 leaves".to_string()),
+        ]
+    );
+    assert_eq!(scaled.group_cookware().len(), 1);
+
+    let list = IngredientList::from_recipe(&scaled, parser.converter(), true);
+    assert_eq!(list.iter().count(), 3);
+}
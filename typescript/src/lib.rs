@@ -1,5 +1,7 @@
 use cooklang::ast::build_ast;
 use cooklang::error::SourceReport;
+use cooklang::highlight::highlight;
+use cooklang::line_index::ColumnUnit;
 use cooklang::metadata::{CooklangValueExt, NameAndUrl, RecipeTime};
 use cooklang::{parser::PullParser, Extensions};
 use cooklang::{Converter, CooklangParser, IngredientReferenceTarget, Item};
@@ -58,6 +60,30 @@ impl State {
         s
     }
 
+    /// Highlights as a JSON array of `{ start, end, kind }`
+    ///
+    /// Offsets are in UTF-16 code units, like JS strings.
+    pub fn highlight(&self, input: &str) -> String {
+        let mut byte = 0;
+        let mut utf16 = 0;
+        let mut to_utf16 = |offset: usize| {
+            utf16 += input[byte..offset].encode_utf16().count();
+            byte = offset;
+            utf16
+        };
+        let highlights = highlight(input, self.extensions)
+            .into_iter()
+            .map(|h| {
+                serde_json::json!({
+                    "start": to_utf16(h.span.start()),
+                    "end": to_utf16(h.span.end()),
+                    "kind": h.kind,
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_string(&highlights).unwrap()
+    }
+
     pub fn parse_ast(&self, input: &str, json: bool) -> FallibleResult {
         let events = PullParser::new(input, self.extensions);
         let (ast, report) = build_ast(events).into_tuple();
@@ -155,6 +181,10 @@ impl State {
 pub struct FallibleResult {
     pub value: String,
     pub error: String,
+    /// Diagnostics as a JSON array, see `cooklang::report::Diagnostic`
+    ///
+    /// Columns are in UTF-16 code units, like JS strings.
+    pub diagnostics: String,
 }
 
 impl FallibleResult {
@@ -163,7 +193,13 @@ impl FallibleResult {
         report.write("playground", input, true, &mut buf).unwrap();
         let ansi_error = String::from_utf8_lossy(&buf);
         let error = ansi_to_html::convert(&ansi_error).unwrap_or_else(|_| ansi_error.into_owned());
-        FallibleResult { value, error }
+        let diagnostics = report.to_diagnostics_with_unit(input, ColumnUnit::Utf16);
+        let diagnostics = serde_json::to_string(&diagnostics).unwrap();
+        FallibleResult {
+            value,
+            error,
+            diagnostics,
+        }
     }
 }
 
@@ -273,6 +309,10 @@ fn render(r: cooklang::ScaledRecipe, converter: &Converter) -> String {
                                     @let q = &r.inline_quantities[*index];
                                     i.temp { (q) }
                                 }
+                                Item::Temperature { index } => {
+                                    @let temp = &r.temperatures[*index];
+                                    i.temp { (temp) }
+                                }
                                 Item::Text { value } => {
                                     (value)
                                 }